@@ -1,12 +1,127 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use soft_plc::{
+    blocks::BlockConfig,
+    engine::{PlcConfig, ScanEngine, SignalConfig},
+    signal::{SignalBus, SignalValue},
+};
+use std::collections::HashMap;
 
-fn dummy_benchmark(c: &mut Criterion) {
-    c.bench_function("dummy", |b| {
-        b.iter(|| {
-            black_box(1 + 1);
-        })
+/// Blocks per rung of the generated program
+const RUNG_BLOCKS: usize = 5;
+
+fn signal(name: String, signal_type: &str, initial: serde_yaml::Value) -> SignalConfig {
+    SignalConfig {
+        name,
+        signal_type: signal_type.to_string(),
+        initial,
+        ..Default::default()
+    }
+}
+
+fn block(name: String, block_type: &str, inputs: &[(&str, String)], outputs: &[(&str, String)]) -> BlockConfig {
+    let ports = |ports: &[(&str, String)]| ports.iter()
+        .map(|(port, signal)| (port.to_string(), signal.clone()))
+        .collect::<HashMap<_, _>>();
+    
+    BlockConfig {
+        name,
+        block_type: block_type.to_string(),
+        inputs: ports(inputs),
+        outputs: ports(outputs),
+        params: HashMap::new(),
+        retain: false,
+    }
+}
+
+/// A program of `rungs` independent rungs, each a level check feeding a
+/// delayed start and a run counter: ADD -> GT -> AND -> TON -> COUNTER
+fn program(rungs: usize) -> PlcConfig {
+    let mut config = PlcConfig {
+        scan_time_ms: 10,
+        ..Default::default()
+    };
+    
+    config.signals.push(signal("offset".to_string(), "float", 1.5.into()));
+    config.signals.push(signal("limit".to_string(), "float", 50.0.into()));
+    config.signals.push(signal("enable".to_string(), "bool", true.into()));
+    config.signals.push(signal("reset".to_string(), "bool", false.into()));
+    
+    for i in 0..rungs {
+        config.signals.push(signal(format!("level_{}", i), "float", ((i % 100) as f64).into()));
+        config.signals.push(signal(format!("sum_{}", i), "float", serde_yaml::Value::Null));
+        config.signals.push(signal(format!("high_{}", i), "bool", serde_yaml::Value::Null));
+        config.signals.push(signal(format!("run_{}", i), "bool", serde_yaml::Value::Null));
+        config.signals.push(signal(format!("done_{}", i), "bool", serde_yaml::Value::Null));
+        config.signals.push(signal(format!("count_{}", i), "int", serde_yaml::Value::Null));
+        
+        config.blocks.push(block(format!("add_{}", i), "ADD",
+            &[("in1", format!("level_{}", i)), ("in2", "offset".to_string())],
+            &[("out", format!("sum_{}", i))]));
+        config.blocks.push(block(format!("gt_{}", i), "GT",
+            &[("in1", format!("sum_{}", i)), ("in2", "limit".to_string())],
+            &[("out", format!("high_{}", i))]));
+        config.blocks.push(block(format!("and_{}", i), "AND",
+            &[("in1", format!("high_{}", i)), ("in2", "enable".to_string())],
+            &[("out", format!("run_{}", i))]));
+        
+        let mut timer = block(format!("ton_{}", i), "TON",
+            &[("in", format!("run_{}", i))],
+            &[("q", format!("done_{}", i))]);
+        timer.params.insert("preset_ms".to_string(), 500.into());
+        config.blocks.push(timer);
+        
+        config.blocks.push(block(format!("ctr_{}", i), "COUNTER",
+            &[("cu", format!("done_{}", i)), ("cd", "reset".to_string()), ("r", "reset".to_string())],
+            &[("cv", format!("count_{}", i))]));
+    }
+    
+    config
+}
+
+fn scan_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan");
+    
+    for blocks in [1_000, 10_000] {
+        let mut engine = ScanEngine::new(program(blocks / RUNG_BLOCKS)).unwrap();
+        
+        group.throughput(Throughput::Elements(blocks as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(blocks), &blocks, |b, _| {
+            b.iter(|| engine.execute_blocks().unwrap())
+        });
+    }
+    
+    group.finish();
+}
+
+fn load_benchmark(c: &mut Criterion) {
+    let config = program(10_000 / RUNG_BLOCKS);
+    
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+    group.bench_function("10000", |b| {
+        b.iter(|| ScanEngine::new(config.clone()).unwrap())
+    });
+    group.finish();
+}
+
+fn signal_access_benchmark(c: &mut Criterion) {
+    let bus = SignalBus::new();
+    for i in 0..10_000 {
+        bus.set(&format!("signal_{}", i), SignalValue::Float(i as f64)).unwrap();
+    }
+    let id = bus.register("signal_5000");
+    
+    let mut group = c.benchmark_group("signal_access");
+    group.bench_function("read_by_handle", |b| b.iter(|| bus.read_float(black_box(id)).unwrap()));
+    group.bench_function("get_by_name", |b| b.iter(|| bus.get_float(black_box("signal_5000")).unwrap()));
+    group.bench_function("write_by_handle", |b| {
+        b.iter(|| bus.write(black_box(id), SignalValue::Float(1.0)).unwrap())
+    });
+    group.bench_function("set_by_name", |b| {
+        b.iter(|| bus.set(black_box("signal_5000"), SignalValue::Float(1.0)).unwrap())
     });
+    group.finish();
 }
 
-criterion_group!(benches, dummy_benchmark);
+criterion_group!(benches, scan_benchmark, load_benchmark, signal_access_benchmark);
 criterion_main!(benches);
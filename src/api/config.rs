@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// `api` section of a PLC configuration
+///
+/// ```yaml
+/// api:
+///   bind: "127.0.0.1:8080"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    #[serde(default = "default_bind")]
+    pub bind: String,
+}
+
+fn default_bind() -> String {
+    "127.0.0.1:8080".to_string()
+}
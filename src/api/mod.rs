@@ -0,0 +1,6 @@
+mod config;
+mod server;
+mod stream;
+
+pub use config::ApiConfig;
+pub use server::ApiServer;
@@ -0,0 +1,441 @@
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use crate::{Result, PlcError, signal::{Force, Quality, Sample, SignalBus, SignalValue}};
+use crate::engine::{
+    BlockStatus, ChangeReport, EngineHandle, EngineStatus, FaultKind, FaultPolicy, FaultRecord, PlcConfig, ReloadHandle, TaskStats,
+};
+use super::config::ApiConfig;
+use super::stream::{millis, stream};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::net::TcpListener;
+use tracing::info;
+
+/// HTTP/JSON API for HMIs and scripts.
+///
+/// | Method | Path | |
+/// |---|---|---|
+/// | GET | `/api/signals` | every signal with its value, quality and timestamp |
+/// | GET | `/api/signals?of={name}` | the elements of an array or struct signal, in declaration order |
+/// | GET | `/api/signals/{name}` | one signal, or one element such as `pumps[2].run` |
+/// | PUT | `/api/signals/{name}` | write `{"value": ...}` with an optional `"quality"`, applied at the start of the next scan |
+/// | GET | `/api/status` | engine state and task statistics |
+/// | POST | `/api/engine/start`, `/api/engine/stop` | resume or pause logic execution |
+/// | GET | `/api/blocks` | every block with its error status |
+/// | GET | `/api/faults?since={sequence}` | the diagnostic buffer, or only the faults after `sequence` |
+/// | POST | `/api/faults/clear` | acknowledge all faults at the start of the next scan; 409 once a fault has stopped the engine |
+/// | POST | `/api/reload` | load the YAML configuration in the body as an online change and return the change report |
+/// | POST | `/api/reload?dry_run=true` | only report what loading the configuration would change |
+/// | GET | `/api/forces` | the force table |
+/// | PUT | `/api/forces/{name}` | force a signal to `{"value": ..., "by": "who"}` |
+/// | DELETE | `/api/forces/{name}`, `/api/forces` | remove one force or every force, with an optional `{"by": "who"}` |
+/// | GET | `/api/ws` | WebSocket stream of signal changes |
+///
+/// A WebSocket client sends `{"subscribe": [names or patterns]}`, with an
+/// optional `deadband` and `min_interval_ms`, and receives the current
+/// values followed by every change as
+/// `{"updates": [{"name", "value", "quality", "timestamp", "scan"}]}`.
+/// Timestamps are milliseconds since the Unix epoch at which the value or
+/// quality last changed.
+///
+/// Like the Modbus server it works on the `SignalBus`, so reads see the
+/// values published by the last scan. Errors are answered as
+/// `{"error": "..."}` with a 4xx status. `/api/reload` is only served
+/// when the server is given the engine's `ReloadHandle`.
+#[derive(Clone)]
+pub struct ApiServer {
+    bind: String,
+    state: Arc<ApiState>,
+}
+
+#[derive(Clone)]
+struct ApiState {
+    bus: SignalBus,
+    engine: EngineHandle,
+    reload: Option<ReloadHandle>,
+}
+
+#[derive(Serialize)]
+struct SignalEntry {
+    name: String,
+    value: SignalValue,
+    quality: Quality,
+    /// When the value or quality last changed, in milliseconds since the Unix epoch
+    timestamp: u64,
+}
+
+impl SignalEntry {
+    fn new(name: String, sample: Sample) -> Self {
+        Self {
+            name,
+            value: sample.value,
+            quality: sample.quality,
+            timestamp: millis(sample.timestamp),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SignalQuery {
+    /// Array or struct signal whose elements to list
+    of: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SignalWrite {
+    value: serde_json::Value,
+    /// For simulating a value, say
+    #[serde(default)]
+    quality: Quality,
+}
+
+#[derive(Serialize)]
+struct TaskEntry {
+    name: String,
+    interval_ms: u64,
+    priority: u32,
+    executions: u64,
+    overruns: u64,
+    last_execution_us: u64,
+    max_execution_us: u64,
+    average_execution_us: u64,
+}
+
+impl From<&TaskStats> for TaskEntry {
+    fn from(stats: &TaskStats) -> Self {
+        Self {
+            name: stats.name.clone(),
+            interval_ms: stats.interval.as_millis() as u64,
+            priority: stats.priority,
+            executions: stats.executions,
+            overruns: stats.overruns,
+            last_execution_us: stats.last_execution.as_micros() as u64,
+            max_execution_us: stats.max_execution.as_micros() as u64,
+            average_execution_us: stats.average_execution().as_micros() as u64,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct StatusEntry {
+    running: bool,
+    paused: bool,
+    faulted: bool,
+    scan_count: u64,
+    /// Number of signals in the force table
+    forces: usize,
+    tasks: Vec<TaskEntry>,
+}
+
+impl StatusEntry {
+    fn new(status: EngineStatus, bus: &SignalBus) -> Self {
+        Self {
+            running: status.running,
+            paused: status.paused,
+            faulted: status.faulted,
+            scan_count: status.scan_count,
+            forces: bus.forces().len(),
+            tasks: status.tasks.iter().map(TaskEntry::from).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FaultQuery {
+    /// Sequence number of the last fault the client has seen
+    #[serde(default)]
+    since: u64,
+}
+
+#[derive(Serialize)]
+struct FaultEntry {
+    sequence: u64,
+    /// Milliseconds since the Unix epoch
+    time: u64,
+    kind: FaultKind,
+    task: String,
+    program: Option<String>,
+    block: Option<String>,
+    message: String,
+    reaction: FaultPolicy,
+}
+
+impl From<FaultRecord> for FaultEntry {
+    fn from(record: FaultRecord) -> Self {
+        Self {
+            sequence: record.sequence,
+            time: millis(record.time),
+            kind: record.kind,
+            task: record.task,
+            program: record.program,
+            block: record.block,
+            message: record.message,
+            reaction: record.reaction,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ReloadQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Serialize)]
+struct ForceEntry {
+    signal: String,
+    value: SignalValue,
+    by: String,
+    /// Milliseconds since the Unix epoch
+    since: u64,
+}
+
+impl From<Force> for ForceEntry {
+    fn from(force: Force) -> Self {
+        Self {
+            signal: force.signal,
+            value: force.value,
+            by: force.by,
+            since: millis(force.since),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ForceWrite {
+    value: serde_json::Value,
+    /// Who places or removes the force, for the log
+    #[serde(default = "default_user")]
+    by: String,
+}
+
+#[derive(Deserialize)]
+struct ForceRemoval {
+    #[serde(default = "default_user")]
+    by: String,
+}
+
+fn default_user() -> String {
+    "api".to_string()
+}
+
+struct ApiError(StatusCode, String);
+
+impl From<PlcError> for ApiError {
+    fn from(error: PlcError) -> Self {
+        let status = match error {
+            PlcError::SignalNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        ApiError(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+impl ApiServer {
+    pub fn new(config: &ApiConfig, bus: SignalBus, engine: EngineHandle) -> Result<Self> {
+        config.bind.parse::<SocketAddr>().map_err(|e| PlcError::ConfigError(
+            format!("api: invalid bind address '{}': {}", config.bind, e)))?;
+        
+        Ok(Self {
+            bind: config.bind.clone(),
+            state: Arc::new(ApiState { bus, engine, reload: None }),
+        })
+    }
+    
+    /// Serve `/api/reload` through the engine's reload handle
+    pub fn with_reload(mut self, reload: ReloadHandle) -> Self {
+        Arc::make_mut(&mut self.state).reload = Some(reload);
+        self
+    }
+    
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/api/signals", get(list_signals))
+            .route("/api/signals/:name", get(get_signal).put(set_signal))
+            .route("/api/status", get(status))
+            .route("/api/engine/start", post(start))
+            .route("/api/engine/stop", post(stop))
+            .route("/api/blocks", get(list_blocks))
+            .route("/api/faults", get(list_faults))
+            .route("/api/faults/clear", post(clear_faults))
+            .route("/api/reload", post(reload))
+            .route("/api/forces", get(list_forces).delete(clear_forces))
+            .route("/api/forces/:name", put(force).delete(unforce))
+            .route("/api/ws", get(subscribe))
+            .with_state(self.state.clone())
+    }
+    
+    /// Listen on the configured address until the server fails
+    pub async fn run(self) -> Result<()> {
+        let listener = TcpListener::bind(&self.bind).await?;
+        self.serve(listener).await
+    }
+    
+    /// Serve clients connecting to an already bound listener
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        info!("HTTP API listening on {}", listener.local_addr()?);
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+async fn list_signals(State(state): State<Arc<ApiState>>, Query(query): Query<SignalQuery>) -> ApiResult<Vec<SignalEntry>> {
+    let Some(name) = query.of else {
+        let mut signals: Vec<SignalEntry> = state.bus.samples().into_iter()
+            .map(|(name, sample)| SignalEntry::new(name, sample))
+            .collect();
+        signals.sort_by(|a, b| a.name.cmp(&b.name));
+        return Ok(Json(signals));
+    };
+    
+    let elements = state.bus.element_samples(&name);
+    if elements.is_empty() {
+        return Err(PlcError::SignalNotFound(name).into());
+    }
+    Ok(Json(elements.into_iter().map(|(name, sample)| SignalEntry::new(name, sample)).collect()))
+}
+
+async fn get_signal(State(state): State<Arc<ApiState>>, Path(name): Path<String>) -> ApiResult<SignalEntry> {
+    let sample = state.bus.get_sample(&name)?;
+    Ok(Json(SignalEntry::new(name, sample)))
+}
+
+async fn set_signal(
+    State(state): State<Arc<ApiState>>,
+    Path(name): Path<String>,
+    Json(write): Json<SignalWrite>,
+) -> ApiResult<SignalEntry> {
+    if state.bus.is_forced(&name) {
+        return Err(ApiError(StatusCode::CONFLICT, format!("Signal '{}' is forced", name)));
+    }
+    let value = to_value(&state.bus, &name, &write.value)?;
+    
+    state.bus.set_with_quality(&name, value.clone(), write.quality)?;
+    Ok(Json(SignalEntry { name, value, quality: write.quality, timestamp: millis(SystemTime::now()) }))
+}
+
+/// Convert a JSON value for a signal. Only declared signals can be written,
+/// and only with values of their own type or numbers that convert to it.
+fn to_value(bus: &SignalBus, name: &str, json: &serde_json::Value) -> Result<SignalValue> {
+    let current = bus.get(name)?;
+    let mismatch = || PlcError::TypeMismatch {
+        expected: current.type_name().to_string(),
+        actual: json.to_string(),
+    };
+    let yaml = serde_yaml::to_value(json).map_err(|_| mismatch())?;
+    if yaml.is_null() {
+        return Err(mismatch());
+    }
+    SignalValue::from_yaml(&yaml, current.signal_type()).map_err(|e| match e {
+        PlcError::ConversionError(_) => e,
+        _ => mismatch(),
+    })
+}
+
+async fn status(State(state): State<Arc<ApiState>>) -> Json<StatusEntry> {
+    Json(StatusEntry::new(state.engine.status(), &state.bus))
+}
+
+async fn start(State(state): State<Arc<ApiState>>) -> Json<StatusEntry> {
+    state.engine.resume();
+    info!("Engine resumed through the HTTP API");
+    Json(StatusEntry::new(state.engine.status(), &state.bus))
+}
+
+async fn stop(State(state): State<Arc<ApiState>>) -> Json<StatusEntry> {
+    state.engine.pause();
+    info!("Engine paused through the HTTP API");
+    Json(StatusEntry::new(state.engine.status(), &state.bus))
+}
+
+async fn list_forces(State(state): State<Arc<ApiState>>) -> Json<Vec<ForceEntry>> {
+    Json(state.bus.forces().into_iter().map(ForceEntry::from).collect())
+}
+
+async fn force(
+    State(state): State<Arc<ApiState>>,
+    Path(name): Path<String>,
+    Json(write): Json<ForceWrite>,
+) -> ApiResult<Vec<ForceEntry>> {
+    let value = to_value(&state.bus, &name, &write.value)?;
+    state.bus.force(&name, value, &write.by)?;
+    Ok(list_forces(State(state)).await)
+}
+
+async fn unforce(
+    State(state): State<Arc<ApiState>>,
+    Path(name): Path<String>,
+    removal: Option<Json<ForceRemoval>>,
+) -> ApiResult<Vec<ForceEntry>> {
+    let by = removal.map(|Json(removal)| removal.by).unwrap_or_else(default_user);
+    if !state.bus.unforce(&name, &by) {
+        return Err(ApiError(StatusCode::NOT_FOUND, format!("Signal '{}' is not forced", name)));
+    }
+    Ok(list_forces(State(state)).await)
+}
+
+async fn clear_forces(
+    State(state): State<Arc<ApiState>>,
+    removal: Option<Json<ForceRemoval>>,
+) -> Json<serde_json::Value> {
+    let by = removal.map(|Json(removal)| removal.by).unwrap_or_else(default_user);
+    Json(serde_json::json!({ "cleared": state.bus.clear_forces(&by) }))
+}
+
+async fn list_blocks(State(state): State<Arc<ApiState>>) -> Json<Vec<BlockStatus>> {
+    Json(state.engine.status().blocks)
+}
+
+async fn list_faults(State(state): State<Arc<ApiState>>, Query(query): Query<FaultQuery>) -> Json<Vec<FaultEntry>> {
+    Json(state.engine.faults().since(query.since).into_iter().map(FaultEntry::from).collect())
+}
+
+async fn clear_faults(State(state): State<Arc<ApiState>>) -> ApiResult<StatusEntry> {
+    // A fault stop ends `run`, so there is no scan left to pick the request up
+    if state.engine.status().faulted {
+        return Err(ApiError(StatusCode::CONFLICT,
+            "Engine was stopped by a fault; restart it to clear the fault".to_string()));
+    }
+    state.engine.clear_faults();
+    info!("Faults acknowledged through the HTTP API");
+    Ok(Json(StatusEntry::new(state.engine.status(), &state.bus)))
+}
+
+async fn reload(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ReloadQuery>,
+    body: String,
+) -> ApiResult<ChangeReport> {
+    let Some(reload) = &state.reload else {
+        return Err(ApiError(StatusCode::NOT_FOUND, "Online change is not available".to_string()));
+    };
+    let config = PlcConfig::from_yaml(&body)?;
+    
+    let report = if query.dry_run {
+        reload.dry_run(config).await?
+    } else {
+        let report = reload.apply(config).await?;
+        info!("Configuration changed through the HTTP API: {}", report);
+        report
+    };
+    Ok(Json(report))
+}
+
+async fn subscribe(State(state): State<Arc<ApiState>>, upgrade: WebSocketUpgrade) -> Response {
+    let bus = state.bus.clone();
+    upgrade.on_upgrade(move |socket| stream(socket, bus))
+}
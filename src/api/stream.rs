@@ -0,0 +1,194 @@
+use axum::extract::ws::{Message, WebSocket};
+use serde::{Deserialize, Serialize};
+use crate::signal::{matches_pattern, ChangeSet, Quality, SignalBus, SignalValue};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{interval, Interval, MissedTickBehavior};
+use tracing::debug;
+
+/// What a client wants to receive. A new request replaces the previous one.
+///
+/// ```json
+/// {"subscribe": ["tank_*", "pump?.run"], "deadband": 0.5, "min_interval_ms": 250}
+/// ```
+#[derive(Debug, Deserialize)]
+struct Subscribe {
+    /// Signal names, or patterns with `*` and `?`
+    subscribe: Vec<String>,
+    /// Numeric changes smaller than this since the last value sent are not sent
+    #[serde(default)]
+    deadband: f64,
+    /// Send at most one message per interval, holding the latest value of
+    /// each signal that changed in between
+    #[serde(default)]
+    min_interval_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Update {
+    name: String,
+    value: SignalValue,
+    quality: Quality,
+    /// Milliseconds since the Unix epoch
+    timestamp: u64,
+    scan: u64,
+}
+
+#[derive(Serialize)]
+struct Updates<'a> {
+    updates: Vec<&'a Update>,
+}
+
+struct Subscription {
+    request: Subscribe,
+    /// Value and quality last sent for every signal, for the deadband
+    sent: BTreeMap<String, (SignalValue, Quality)>,
+    /// Updates held back by the rate limit
+    pending: BTreeMap<String, Update>,
+    ticker: Option<Interval>,
+}
+
+impl Subscription {
+    fn new(request: Subscribe) -> Self {
+        let ticker = (request.min_interval_ms > 0).then(|| {
+            let mut ticker = interval(Duration::from_millis(request.min_interval_ms));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker
+        });
+        Self { request, sent: BTreeMap::new(), pending: BTreeMap::new(), ticker }
+    }
+    
+    fn wants(&self, name: &str) -> bool {
+        self.request.subscribe.iter().any(|pattern| matches_pattern(pattern, name))
+    }
+    
+    /// Whether `value` differs enough from what the client last got. A
+    /// change of quality is always sent.
+    fn outside_deadband(&self, name: &str, value: &SignalValue, quality: Quality) -> bool {
+        let Some((last, last_quality)) = self.sent.get(name) else { return true };
+        if *last_quality != quality {
+            return true;
+        }
+        match (last.as_float(), value.as_float()) {
+            (Some(last), Some(value)) => value != last && (value - last).abs() >= self.request.deadband,
+            _ => last != value,
+        }
+    }
+    
+    /// Updates to send now from a published change set
+    fn accept(&mut self, changes: &ChangeSet) -> Vec<Update> {
+        let updates: Vec<Update> = changes.changes.iter()
+            .filter(|change| self.wants(&change.name)
+                && self.outside_deadband(&change.name, &change.value, change.quality))
+            .map(|change| Update {
+                name: change.name.clone(),
+                value: change.value.clone(),
+                quality: change.quality,
+                timestamp: millis(change.timestamp),
+                scan: changes.scan,
+            })
+            .collect();
+        self.hold(updates)
+    }
+    
+    /// Every subscribed signal with its current value, for the start of a
+    /// subscription and after missing changes
+    fn current(&mut self, bus: &SignalBus) -> Vec<Update> {
+        let scan = bus.scan_number();
+        let updates: Vec<Update> = bus.samples().into_iter()
+            .filter(|(name, _)| self.wants(name))
+            .map(|(name, sample)| Update {
+                name,
+                value: sample.value,
+                quality: sample.quality,
+                timestamp: millis(sample.timestamp),
+                scan,
+            })
+            .collect();
+        self.hold(updates)
+    }
+    
+    fn hold(&mut self, updates: Vec<Update>) -> Vec<Update> {
+        for update in &updates {
+            self.sent.insert(update.name.clone(), (update.value.clone(), update.quality));
+        }
+        if self.ticker.is_none() {
+            return updates;
+        }
+        for update in updates {
+            self.pending.insert(update.name.clone(), update);
+        }
+        Vec::new()
+    }
+    
+    async fn tick(&mut self) -> Vec<Update> {
+        match &mut self.ticker {
+            Some(ticker) => {
+                ticker.tick().await;
+                std::mem::take(&mut self.pending).into_values().collect()
+            }
+            None => std::future::pending().await,
+        }
+    }
+}
+
+pub(crate) fn millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+async fn send(socket: &mut WebSocket, updates: &[Update]) -> bool {
+    if updates.is_empty() {
+        return true;
+    }
+    let message = serde_json::to_string(&Updates { updates: updates.iter().collect() })
+        .unwrap_or_default();
+    socket.send(Message::Text(message)).await.is_ok()
+}
+
+/// Serve one WebSocket client until it disconnects
+pub(crate) async fn stream(mut socket: WebSocket, bus: SignalBus) {
+    let mut changes = bus.changes();
+    let mut subscription: Option<Subscription> = None;
+    
+    loop {
+        let updates = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<Subscribe>(&text) {
+                    Ok(request) => {
+                        let subscription = subscription.insert(Subscription::new(request));
+                        subscription.current(&bus)
+                    }
+                    Err(e) => {
+                        let error = serde_json::json!({ "error": format!("invalid subscription: {}", e) });
+                        if socket.send(Message::Text(error.to_string())).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                },
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => continue,
+            },
+            changed = changes.recv() => match (changed, &mut subscription) {
+                (Ok(changes), Some(subscription)) => subscription.accept(&changes),
+                (Err(RecvError::Lagged(missed)), Some(subscription)) => {
+                    debug!("WebSocket client missed {} change sets, resending values", missed);
+                    subscription.current(&bus)
+                }
+                (Err(RecvError::Closed), _) => break,
+                _ => continue,
+            },
+            updates = async {
+                match &mut subscription {
+                    Some(subscription) => subscription.tick().await,
+                    None => std::future::pending().await,
+                }
+            } => updates,
+        };
+        
+        if !send(&mut socket, &updates).await {
+            break;
+        }
+    }
+}
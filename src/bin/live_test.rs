@@ -32,7 +32,7 @@ async fn main() -> Result<()> {
         // Show pump states
         print!("Pumps: ");
         for i in 1..=5 {
-            if let Ok(SignalValue::Bool(running)) = bus.get(&format!("pump{}_run", i)) {
+            if let Ok(SignalValue::Bool(running)) = bus.get(&format!("pumps[{}].run", i)) {
                 if running {
                     print!("[P{}:ON] ", i);
                 } else {
@@ -78,11 +78,10 @@ async fn main() -> Result<()> {
             }
             "5" => {
                 println!("\n=== All Signals ===");
-                let signals = bus.iter();  // Already returns Vec
-                let mut signals = signals;
+                let mut signals = bus.samples();
                 signals.sort_by(|a, b| a.0.cmp(&b.0));
-                for (name, value) in signals {
-                    println!("  {:<25} = {:?}", name, value);
+                for (name, sample) in signals {
+                    println!("  {:<25} = {:?} ({:?})", name, sample.value, sample.quality);
                 }
             }
             "q" => break,
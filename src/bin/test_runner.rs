@@ -1,6 +1,7 @@
 // Fix src/bin/test_runner.rs
-use soft_plc::{Result, engine::ScanEngine, signal::SignalValue};
-use tokio::time::{sleep, Duration};
+use soft_plc::{Result, engine::{ScanEngine, SimulatedClock}, signal::SignalValue};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -16,7 +17,7 @@ async fn main() -> Result<()> {
     // Check initial state
     let output = engine.signal_bus().get_bool("test_output")?;
     println!("  Input: false, Output: {} (expected: true)", output);
-    assert_eq!(output, true);
+    assert!(output);
     
     // Change input
     engine.signal_bus().set("test_input", SignalValue::Bool(true))?;
@@ -24,13 +25,13 @@ async fn main() -> Result<()> {
     
     let output = engine.signal_bus().get_bool("test_output")?;
     println!("  Input: true, Output: {} (expected: false)", output);
-    assert_eq!(output, false);
+    assert!(!output);
     
     println!("✓ Test 1 passed!\n");
     
     // Test 2: Timer functionality
     println!("Test 2: Timer (TON) functionality");
-    test_timer().await?;
+    test_timer()?;
     
     // Test 3: Sequencer
     println!("\nTest 3: Sequencer functionality");
@@ -40,7 +41,7 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn test_timer() -> Result<()> {
+fn test_timer() -> Result<()> {
     let yaml = r#"
 signals:
   - name: "timer_input"
@@ -64,30 +65,24 @@ scan_time_ms: 50
 "#;
 
     let config = soft_plc::engine::PlcConfig::from_yaml(yaml)?;
-    let mut engine = ScanEngine::new(config)?;
+    let mut engine = ScanEngine::with_clock(config, Arc::new(SimulatedClock::new()))?;
     
     // Start timer
     engine.signal_bus().set("timer_input", SignalValue::Bool(true))?;
     
     // Run scans for 100ms (timer not done)
-    for _ in 0..2 {
-        engine.execute_blocks()?;
-        sleep(Duration::from_millis(50)).await;
-    }
+    engine.run_simulated(Duration::from_millis(100))?;
     
     let done = engine.signal_bus().get_bool("timer_done")?;
     println!("  After 100ms: timer_done = {} (expected: false)", done);
-    assert_eq!(done, false);
+    assert!(!done);
     
     // Run scans for another 150ms (timer should complete)
-    for _ in 0..3 {
-        engine.execute_blocks()?;
-        sleep(Duration::from_millis(50)).await;
-    }
+    engine.run_simulated(Duration::from_millis(150))?;
     
     let done = engine.signal_bus().get_bool("timer_done")?;
     println!("  After 250ms: timer_done = {} (expected: true)", done);
-    assert_eq!(done, true);
+    assert!(done);
     
     println!("✓ Test 2 passed!");
     Ok(())
@@ -0,0 +1,3 @@
+mod scale;
+
+pub use scale::ScaleBlock;
@@ -0,0 +1,91 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId, SignalType, SignalValue}};
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use std::collections::HashMap;
+
+/// Linear scaling of a raw analog value to engineering units, e.g. counts
+/// 0 to 27648 of an analog card or 4 to 20 mA to 0 to 10 bar.
+///
+/// `raw_min`/`raw_max` map to `eu_min`/`eu_max`; either range may be
+/// inverted. With `clamp` (the default) the result stays within the EU
+/// range. `out_of_range` is true while the raw value lies outside its
+/// range, as with a broken 4-20 mA loop, whether or not the result is
+/// clamped.
+pub struct ScaleBlock {
+    name: String,
+    input: SignalId,
+    output: SignalId,
+    out_of_range: Option<SignalId>,
+    raw: (f64, f64),
+    eu: (f64, f64),
+    clamp: bool,
+}
+
+impl ScaleBlock {
+    pub fn new(
+        name: String,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| PlcError::ConfigError("SCALE requires 'in' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError("SCALE requires 'out' output".to_string()))?);
+        
+        let out_of_range = outputs.get("out_of_range").map(|name| bus.register(name));
+        
+        let param = |key: &str| params.get(key)
+            .and_then(|v| v.as_f64())
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| PlcError::ConfigError(format!("SCALE requires numeric '{}' parameter", key)));
+        let raw = (param("raw_min")?, param("raw_max")?);
+        let eu = (param("eu_min")?, param("eu_max")?);
+        if raw.0 == raw.1 {
+            return Err(PlcError::ConfigError("SCALE 'raw_min' and 'raw_max' must differ".to_string()));
+        }
+        
+        let clamp = params.get("clamp").and_then(|v| v.as_bool()).unwrap_or(true);
+        
+        Ok(Self { name, input, output, out_of_range, raw, eu, clamp })
+    }
+}
+
+impl Block for ScaleBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let value = bus.read(self.input)?;
+        let raw = value.as_float().ok_or_else(|| PlcError::TypeMismatch {
+            expected: "numeric".to_string(),
+            actual: value.type_name().to_string(),
+        })?;
+        
+        let (raw_min, raw_max) = self.raw;
+        let (eu_min, eu_max) = self.eu;
+        let mut scaled = eu_min + (raw - raw_min) * (eu_max - eu_min) / (raw_max - raw_min);
+        if self.clamp {
+            scaled = scaled.clamp(eu_min.min(eu_max), eu_min.max(eu_max));
+        }
+        
+        let result = match bus.declared_type(self.output) {
+            Some(SignalType::Real) => SignalValue::Real(scaled as f32),
+            _ => SignalValue::Float(scaled),
+        };
+        bus.write(self.output, result)?;
+        
+        if let Some(out_of_range) = self.out_of_range {
+            let in_range = (raw_min.min(raw_max)..=raw_min.max(raw_max)).contains(&raw);
+            bus.write(out_of_range, SignalValue::Bool(!in_range))?;
+        }
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "SCALE"
+    }
+}
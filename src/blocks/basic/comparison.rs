@@ -1,47 +1,78 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
+/// Order of two numeric or time values. Integers of different widths compare
+/// by value, and reals as LREAL; under strict type checking both must have
+/// the same type. `None` if either is NaN.
+pub(crate) fn compare(a: &SignalValue, b: &SignalValue, strict: bool) -> Result<Option<Ordering>> {
+    if strict && a.signal_type() != b.signal_type() {
+        return Err(crate::PlcError::TypeMismatch {
+            expected: a.type_name().to_string(),
+            actual: b.type_name().to_string(),
+        });
+    }
+    if let (Some(a), Some(b)) = (a.as_i128(), b.as_i128()) {
+        return Ok(Some(a.cmp(&b)));
+    }
+    match (a.as_float(), b.as_float()) {
+        (Some(a), Some(b)) => Ok(a.partial_cmp(&b)),
+        (None, _) => Err(crate::PlcError::TypeMismatch {
+            expected: "numeric".to_string(),
+            actual: a.type_name().to_string(),
+        }),
+        (_, None) => Err(crate::PlcError::TypeMismatch {
+            expected: "numeric".to_string(),
+            actual: b.type_name().to_string(),
+        }),
+    }
+}
+
 /// Equal comparison block
 pub struct EqBlock {
     name: String,
-    input1: String,
-    input2: String,
-    output: String,
+    input1: SignalId,
+    input2: SignalId,
+    output: SignalId,
 }
 
 impl EqBlock {
-    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let input1 = inputs.get("in1")
-            .ok_or_else(|| crate::PlcError::ConfigError("EQ requires 'in1' input".to_string()))?
-            .clone();
-            
-        let input2 = inputs.get("in2")
-            .ok_or_else(|| crate::PlcError::ConfigError("EQ requires 'in2' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("out")
-            .ok_or_else(|| crate::PlcError::ConfigError("EQ requires 'out' output".to_string()))?
-            .clone();
-            
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let input1 = bus.register(inputs.get("in1")
+            .ok_or_else(|| crate::PlcError::ConfigError("EQ requires 'in1' input".to_string()))?);
+        
+        let input2 = bus.register(inputs.get("in2")
+            .ok_or_else(|| crate::PlcError::ConfigError("EQ requires 'in2' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("EQ requires 'out' output".to_string()))?);
+        
         Ok(Self { name, input1, input2, output })
     }
 }
 
 impl Block for EqBlock {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let val1 = bus.get(&self.input1)?;
-        let val2 = bus.get(&self.input2)?;
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let val1 = bus.read(self.input1)?;
+        let val2 = bus.read(self.input2)?;
         
         let result = match (&val1, &val2) {
             (SignalValue::Bool(a), SignalValue::Bool(b)) => a == b,
             (SignalValue::Int(a), SignalValue::Int(b)) => a == b,
             (SignalValue::Float(a), SignalValue::Float(b)) => (a - b).abs() < f64::EPSILON,
             (SignalValue::String(a), SignalValue::String(b)) => a == b,
+            (a, b) if a.signal_type() == b.signal_type() => a == b,
+            // Different types are never equal, unless types are checked strictly
+            (a, b) if bus.is_strict() => return Err(crate::PlcError::TypeMismatch {
+                expected: a.type_name().to_string(),
+                actual: b.type_name().to_string(),
+            }),
             _ => false,
         };
         
-        bus.set(&self.output, SignalValue::Bool(result))?;
+        bus.write(self.output, SignalValue::Bool(result))?;
         Ok(())
     }
     
@@ -57,44 +88,34 @@ impl Block for EqBlock {
 /// Greater than comparison block
 pub struct GtBlock {
     name: String,
-    input1: String,
-    input2: String,
-    output: String,
+    input1: SignalId,
+    input2: SignalId,
+    output: SignalId,
 }
 
 impl GtBlock {
-    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let input1 = inputs.get("in1")
-            .ok_or_else(|| crate::PlcError::ConfigError("GT requires 'in1' input".to_string()))?
-            .clone();
-            
-        let input2 = inputs.get("in2")
-            .ok_or_else(|| crate::PlcError::ConfigError("GT requires 'in2' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("out")
-            .ok_or_else(|| crate::PlcError::ConfigError("GT requires 'out' output".to_string()))?
-            .clone();
-            
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let input1 = bus.register(inputs.get("in1")
+            .ok_or_else(|| crate::PlcError::ConfigError("GT requires 'in1' input".to_string()))?);
+        
+        let input2 = bus.register(inputs.get("in2")
+            .ok_or_else(|| crate::PlcError::ConfigError("GT requires 'in2' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("GT requires 'out' output".to_string()))?);
+        
         Ok(Self { name, input1, input2, output })
     }
 }
 
 impl Block for GtBlock {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let val1 = bus.get(&self.input1)?;
-        let val2 = bus.get(&self.input2)?;
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let val1 = bus.read(self.input1)?;
+        let val2 = bus.read(self.input2)?;
         
-        let result = match (&val1, &val2) {
-            (SignalValue::Int(a), SignalValue::Int(b)) => a > b,
-            (SignalValue::Float(a), SignalValue::Float(b)) => a > b,
-            _ => return Err(crate::PlcError::TypeMismatch {
-                expected: "numeric".to_string(),
-                actual: "non-numeric".to_string(),
-            }),
-        };
+        let result = compare(&val1, &val2, bus.is_strict())? == Some(Ordering::Greater);
         
-        bus.set(&self.output, SignalValue::Bool(result))?;
+        bus.write(self.output, SignalValue::Bool(result))?;
         Ok(())
     }
     
@@ -110,44 +131,34 @@ impl Block for GtBlock {
 /// Less than comparison block
 pub struct LtBlock {
     name: String,
-    input1: String,
-    input2: String,
-    output: String,
+    input1: SignalId,
+    input2: SignalId,
+    output: SignalId,
 }
 
 impl LtBlock {
-    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let input1 = inputs.get("in1")
-            .ok_or_else(|| crate::PlcError::ConfigError("LT requires 'in1' input".to_string()))?
-            .clone();
-            
-        let input2 = inputs.get("in2")
-            .ok_or_else(|| crate::PlcError::ConfigError("LT requires 'in2' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("out")
-            .ok_or_else(|| crate::PlcError::ConfigError("LT requires 'out' output".to_string()))?
-            .clone();
-            
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let input1 = bus.register(inputs.get("in1")
+            .ok_or_else(|| crate::PlcError::ConfigError("LT requires 'in1' input".to_string()))?);
+        
+        let input2 = bus.register(inputs.get("in2")
+            .ok_or_else(|| crate::PlcError::ConfigError("LT requires 'in2' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("LT requires 'out' output".to_string()))?);
+        
         Ok(Self { name, input1, input2, output })
     }
 }
 
 impl Block for LtBlock {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let val1 = bus.get(&self.input1)?;
-        let val2 = bus.get(&self.input2)?;
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let val1 = bus.read(self.input1)?;
+        let val2 = bus.read(self.input2)?;
         
-        let result = match (&val1, &val2) {
-            (SignalValue::Int(a), SignalValue::Int(b)) => a < b,
-            (SignalValue::Float(a), SignalValue::Float(b)) => a < b,
-            _ => return Err(crate::PlcError::TypeMismatch {
-                expected: "numeric".to_string(),
-                actual: "non-numeric".to_string(),
-            }),
-        };
+        let result = compare(&val1, &val2, bus.is_strict())? == Some(Ordering::Less);
         
-        bus.set(&self.output, SignalValue::Bool(result))?;
+        bus.write(self.output, SignalValue::Bool(result))?;
         Ok(())
     }
     
@@ -1,11 +1,14 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
 use std::collections::HashMap;
 
 /// Constant value block - outputs a constant value
 pub struct ConstBlock {
     name: String,
-    output: String,
+    output: SignalId,
+    /// The `value` parameter, read again as the output's declared type
+    literal: serde_yaml::Value,
     value: SignalValue,
 }
 
@@ -13,20 +16,23 @@ impl ConstBlock {
     pub fn new(
         name: String,
         outputs: &HashMap<String, String>,
-        params: &HashMap<String, serde_yaml::Value>
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
     ) -> Result<Self> {
-        let output = outputs.get("out")
-            .ok_or_else(|| crate::PlcError::ConfigError("CONST requires 'out' output".to_string()))?
-            .clone();
-            
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("CONST requires 'out' output".to_string()))?);
+        
         let value_param = params.get("value")
             .ok_or_else(|| crate::PlcError::ConfigError("CONST requires 'value' parameter".to_string()))?;
-            
+        
         // Determine value type from YAML
         let value = if let Some(b) = value_param.as_bool() {
             SignalValue::Bool(b)
         } else if let Some(i) = value_param.as_i64() {
-            SignalValue::Int(i as i32)
+            // Written as the signal's own integer type when it is stored
+            i32::try_from(i).map(SignalValue::Int).unwrap_or(SignalValue::LInt(i))
+        } else if let Some(u) = value_param.as_u64() {
+            SignalValue::ULInt(u)
         } else if let Some(f) = value_param.as_f64() {
             SignalValue::Float(f)
         } else if let Some(s) = value_param.as_str() {
@@ -34,14 +40,20 @@ impl ConstBlock {
         } else {
             return Err(crate::PlcError::ConfigError("CONST value must be bool, int, float, or string".to_string()));
         };
-            
-        Ok(Self { name, output, value })
+        
+        Ok(Self { name, output, literal: value_param.clone(), value })
     }
 }
 
 impl Block for ConstBlock {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        bus.set(&self.output, self.value.clone())?;
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        // The literal takes the output's type, like a typed IEC literal
+        if let Some(declared) = bus.declared_type(self.output) {
+            if self.value.signal_type() != declared {
+                self.value = SignalValue::from_yaml(&self.literal, declared)?;
+            }
+        }
+        bus.write(self.output, self.value.clone())?;
         Ok(())
     }
     
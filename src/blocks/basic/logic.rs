@@ -1,37 +1,37 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
 use std::collections::HashMap;
 
 pub struct AndBlock {
     name: String,
-    inputs: Vec<String>,
-    output: String,
+    inputs: Vec<SignalId>,
+    output: SignalId,
 }
 
 impl AndBlock {
-    pub fn new(name: String, config: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let inputs: Vec<String> = config.iter()
+    pub fn new(name: String, config: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let inputs: Vec<SignalId> = config.iter()
             .filter(|(k, _)| k.starts_with("in"))
-            .map(|(_, v)| v.clone())
+            .map(|(_, v)| bus.register(v))
             .collect();
-            
-        let output = outputs.get("out")
-            .ok_or_else(|| crate::PlcError::ConfigError("AND block requires 'out' output".to_string()))?
-            .clone();
-            
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("AND block requires 'out' output".to_string()))?);
+        
         Ok(Self { name, inputs, output })
     }
 }
 
 impl Block for AndBlock {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
         let mut result = true;
         
-        for input in &self.inputs {
-            result = result && bus.get_bool(input)?;
+        for &input in &self.inputs {
+            result = result && bus.read_bool(input)?;
         }
         
-        bus.set(&self.output, SignalValue::Bool(result))?;
+        bus.write(self.output, SignalValue::Bool(result))?;
         Ok(())
     }
     
@@ -46,34 +46,33 @@ impl Block for AndBlock {
 
 pub struct OrBlock {
     name: String,
-    inputs: Vec<String>,
-    output: String,
+    inputs: Vec<SignalId>,
+    output: SignalId,
 }
 
 impl OrBlock {
-    pub fn new(name: String, config: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let inputs: Vec<String> = config.iter()
+    pub fn new(name: String, config: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let inputs: Vec<SignalId> = config.iter()
             .filter(|(k, _)| k.starts_with("in"))
-            .map(|(_, v)| v.clone())
+            .map(|(_, v)| bus.register(v))
             .collect();
-            
-        let output = outputs.get("out")
-            .ok_or_else(|| crate::PlcError::ConfigError("OR block requires 'out' output".to_string()))?
-            .clone();
-            
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("OR block requires 'out' output".to_string()))?);
+        
         Ok(Self { name, inputs, output })
     }
 }
 
 impl Block for OrBlock {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
         let mut result = false;
         
-        for input in &self.inputs {
-            result = result || bus.get_bool(input)?;
+        for &input in &self.inputs {
+            result = result || bus.read_bool(input)?;
         }
         
-        bus.set(&self.output, SignalValue::Bool(result))?;
+        bus.write(self.output, SignalValue::Bool(result))?;
         Ok(())
     }
     
@@ -88,28 +87,26 @@ impl Block for OrBlock {
 
 pub struct NotBlock {
     name: String,
-    input: String,
-    output: String,
+    input: SignalId,
+    output: SignalId,
 }
 
 impl NotBlock {
-    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let input = inputs.get("in")
-            .ok_or_else(|| crate::PlcError::ConfigError("NOT block requires 'in' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("out")
-            .ok_or_else(|| crate::PlcError::ConfigError("NOT block requires 'out' output".to_string()))?
-            .clone();
-            
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| crate::PlcError::ConfigError("NOT block requires 'in' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("NOT block requires 'out' output".to_string()))?);
+        
         Ok(Self { name, input, output })
     }
 }
 
 impl Block for NotBlock {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let value = bus.get_bool(&self.input)?;
-        bus.set(&self.output, SignalValue::Bool(!value))?;
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let value = bus.read_bool(self.input)?;
+        bus.write(self.output, SignalValue::Bool(!value))?;
         Ok(())
     }
     
@@ -1,7 +1,10 @@
 mod logic;
 mod comparison;
 mod const_block;
+mod unit_delay;
 
 pub use logic::{AndBlock, OrBlock, NotBlock};
 pub use comparison::{EqBlock, GtBlock, LtBlock};
+pub(crate) use comparison::compare;
 pub use const_block::ConstBlock;
+pub use unit_delay::UnitDelay;
@@ -0,0 +1,92 @@
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Unit delay (z^-1) - outputs the value its input had at the end of the previous scan.
+/// The engine runs these blocks before all others, which is what makes a
+/// feedback loop through one well defined.
+pub struct UnitDelay {
+    name: String,
+    input: SignalId,
+    output: SignalId,
+    initial: Option<SignalValue>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UnitDelayState {
+    initial: Option<SignalValue>,
+}
+
+impl UnitDelay {
+    pub fn new(
+        name: String,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| crate::PlcError::ConfigError("UNIT_DELAY requires 'in' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| crate::PlcError::ConfigError("UNIT_DELAY requires 'out' output".to_string()))?);
+        
+        // Optional value for the first scan, before the input has been produced
+        let initial = match params.get("initial") {
+            None => None,
+            Some(value) => Some(if let Some(b) = value.as_bool() {
+                SignalValue::Bool(b)
+            } else if let Some(i) = value.as_i64() {
+                // Written as the signal's own integer type when it is stored
+                i32::try_from(i).map(SignalValue::Int).unwrap_or(SignalValue::LInt(i))
+            } else if let Some(u) = value.as_u64() {
+                SignalValue::ULInt(u)
+            } else if let Some(f) = value.as_f64() {
+                SignalValue::Float(f)
+            } else if let Some(s) = value.as_str() {
+                SignalValue::String(s.to_string())
+            } else {
+                return Err(crate::PlcError::ConfigError("UNIT_DELAY 'initial' must be bool, int, float, or string".to_string()));
+            }),
+        };
+        
+        Ok(Self { name, input, output, initial })
+    }
+}
+
+impl Block for UnitDelay {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let value = match (self.initial.take(), bus.declared_type(self.output)) {
+            // Written as the output's type, even when types are checked strictly
+            (Some(initial), Some(declared)) => initial.coerce(declared)?,
+            (Some(initial), None) => initial,
+            (None, _) => bus.read(self.input)?,
+        };
+        
+        bus.write(self.output, value)?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "UNIT_DELAY"
+    }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(UnitDelayState {
+            initial: self.initial.clone(),
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, _clock: &dyn Clock) -> Result<()> {
+        let state: UnitDelayState = serde_json::from_value(state)
+            .map_err(|e| crate::PlcError::StateError(format!("UNIT_DELAY '{}': {}", self.name, e)))?;
+        self.initial = state.initial;
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+mod bitwise;
+mod shift;
+mod pack;
+
+pub use bitwise::{BitOp, BitwiseBlock};
+pub use shift::{ShiftBlock, ShiftOp};
+pub use pack::{PackBlock, UnpackBlock};
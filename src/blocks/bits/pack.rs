@@ -0,0 +1,131 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId, SignalType, SignalValue}};
+use crate::blocks::ports::bit_index;
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use std::collections::HashMap;
+use super::bitwise::bits_of;
+
+/// Signals of the `bit0` ... `bit63` ports, by bit number
+fn bit_ports(ports: &HashMap<String, String>, bus: &SignalBus) -> Vec<(u32, SignalId)> {
+    let mut bits: Vec<(u32, SignalId)> = ports.iter()
+        .filter_map(|(port, signal)| Some((bit_index(port)?, bus.register(signal))))
+        .collect();
+    bits.sort_by_key(|&(bit, _)| bit);
+    bits
+}
+
+/// Pack bools on `bit0` ... `bit63` into the integer or bit string on `out`,
+/// a WORD unless declared otherwise. Bits without an input are zero.
+pub struct PackBlock {
+    name: String,
+    bits: Vec<(u32, SignalId)>,
+    output: SignalId,
+}
+
+impl PackBlock {
+    pub fn new(
+        name: String,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let bits = bit_ports(inputs, bus);
+        if bits.is_empty() {
+            return Err(PlcError::ConfigError("PACK requires at least one 'bitN' input".to_string()));
+        }
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError("PACK requires 'out' output".to_string()))?);
+        
+        Ok(Self { name, bits, output })
+    }
+}
+
+impl Block for PackBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let out_type = bus.declared_type(self.output).unwrap_or(SignalType::Word);
+        let width = out_type.bit_width().ok_or_else(|| PlcError::TypeMismatch {
+            expected: "integer".to_string(),
+            actual: out_type.name().to_string(),
+        })?;
+        
+        let mut packed: u64 = 0;
+        for &(bit, input) in &self.bits {
+            if bit >= width {
+                return Err(PlcError::ExecutionError(format!(
+                    "PACK '{}': {} has no bit{}", self.name, out_type, bit
+                )));
+            }
+            if bus.read_bool(input)? {
+                packed |= 1 << bit;
+            }
+        }
+        
+        let value = SignalValue::from_bits(packed, out_type).ok_or_else(|| PlcError::ExecutionError(format!(
+            "PACK '{}': {} has no bit pattern", self.name, out_type
+        )))?;
+        bus.write(self.output, value)?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "PACK"
+    }
+}
+
+/// Unpack the integer or bit string on `in` into bools on `bit0` ... `bit63`.
+/// Only the bits with an output are written.
+pub struct UnpackBlock {
+    name: String,
+    input: SignalId,
+    bits: Vec<(u32, SignalId)>,
+}
+
+impl UnpackBlock {
+    pub fn new(
+        name: String,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| PlcError::ConfigError("UNPACK requires 'in' input".to_string()))?);
+        
+        let bits = bit_ports(outputs, bus);
+        if bits.is_empty() {
+            return Err(PlcError::ConfigError("UNPACK requires at least one 'bitN' output".to_string()));
+        }
+        
+        Ok(Self { name, input, bits })
+    }
+}
+
+impl Block for UnpackBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let value = bus.read(self.input)?;
+        let packed = bits_of(&value)?;
+        let width = value.signal_type().bit_width().unwrap_or(64);
+        
+        for &(bit, output) in &self.bits {
+            if bit >= width {
+                return Err(PlcError::ExecutionError(format!(
+                    "UNPACK '{}': {} has no bit{}", self.name, value.type_name(), bit
+                )));
+            }
+            bus.write(output, SignalValue::Bool(packed & (1 << bit) != 0))?;
+        }
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "UNPACK"
+    }
+}
@@ -0,0 +1,3 @@
+mod pid;
+
+pub use pid::PID;
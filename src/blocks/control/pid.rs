@@ -0,0 +1,197 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId, SignalValue}};
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// PID controller with output clamping, anti-windup and bumpless auto/manual transfer
+///
+/// Parallel form: `out = kp*e + ki*∫e dt - kd*d(pv)/dt`, with `ki` in 1/s and
+/// `kd` in s. The derivative acts on the process value rather than the error,
+/// so setpoint steps do not kick the output. Without a `sample_ms` parameter
+/// the controller runs every scan with the measured scan period as its sample time.
+pub struct PID {
+    name: String,
+    setpoint: SignalId,
+    process_value: SignalId,
+    auto_input: Option<SignalId>,
+    manual_input: Option<SignalId>,
+    output: SignalId,
+    kp: f64,
+    ki: f64,
+    kd: f64,
+    out_min: f64,
+    out_max: f64,
+    sample_time: Option<Duration>,
+    integral: f64,
+    prev_pv: Option<f64>,
+    last_time: Option<Duration>,
+    last_output: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PidState {
+    integral: f64,
+    prev_pv: Option<f64>,
+    /// Whether the controller has executed before
+    started: bool,
+    last_output: f64,
+}
+
+impl PID {
+    pub fn new(
+        name: String,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let setpoint = bus.register(inputs.get("sp")
+            .ok_or_else(|| PlcError::ConfigError("PID requires 'sp' input".to_string()))?);
+        
+        let process_value = bus.register(inputs.get("pv")
+            .ok_or_else(|| PlcError::ConfigError("PID requires 'pv' input".to_string()))?);
+        
+        let auto_input = inputs.get("auto").map(|name| bus.register(name));
+        let manual_input = inputs.get("man").map(|name| bus.register(name));
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError("PID requires 'out' output".to_string()))?);
+        
+        let param = |key: &str, default: f64| params.get(key).and_then(|v| v.as_f64()).unwrap_or(default);
+        
+        let kp = param("kp", 1.0);
+        let ki = param("ki", 0.0);
+        let kd = param("kd", 0.0);
+        let out_min = param("out_min", 0.0);
+        let out_max = param("out_max", 100.0);
+        
+        if out_min >= out_max {
+            return Err(PlcError::ConfigError("PID 'out_min' must be less than 'out_max'".to_string()));
+        }
+        
+        let sample_time = params.get("sample_ms")
+            .and_then(|v| v.as_u64())
+            .map(Duration::from_millis);
+        
+        Ok(Self {
+            name,
+            setpoint,
+            process_value,
+            auto_input,
+            manual_input,
+            output,
+            kp,
+            ki,
+            kd,
+            out_min,
+            out_max,
+            sample_time,
+            integral: 0.0,
+            prev_pv: None,
+            last_time: None,
+            last_output: out_min,
+        })
+    }
+    
+    fn clamp(&self, value: f64) -> f64 {
+        value.clamp(self.out_min, self.out_max)
+    }
+}
+
+impl Block for PID {
+    fn execute(&mut self, bus: &SignalBus, clock: &dyn Clock) -> Result<()> {
+        let sp = bus.read_float(self.setpoint)?;
+        let pv = bus.read_float(self.process_value)?;
+        let now = clock.now();
+        
+        let auto = match self.auto_input {
+            Some(auto) => bus.read_bool(auto)?,
+            None => true,
+        };
+        
+        let error = sp - pv;
+        
+        if !auto {
+            // Manual: follow the manual value (or hold the last output) and
+            // keep the integrator tracking so the switch back to auto is bumpless
+            if let Some(man) = self.manual_input {
+                self.last_output = self.clamp(bus.read_float(man)?);
+            }
+            self.integral = self.last_output - self.kp * error;
+            self.prev_pv = Some(pv);
+            self.last_time = Some(now);
+            bus.write(self.output, SignalValue::Float(self.last_output))?;
+            return Ok(());
+        }
+        
+        let dt = match self.last_time {
+            Some(last) => now.saturating_sub(last),
+            None => Duration::ZERO,
+        };
+        
+        let due = match (self.last_time, self.sample_time) {
+            (Some(_), Some(sample)) => dt >= sample,
+            _ => true,
+        };
+        
+        if due {
+            let dt_s = dt.as_secs_f64();
+            
+            if dt_s > 0.0 {
+                self.integral += self.ki * error * dt_s;
+            }
+            
+            let derivative = match self.prev_pv {
+                Some(prev) if dt_s > 0.0 => -self.kd * (pv - prev) / dt_s,
+                _ => 0.0,
+            };
+            
+            let unclamped = self.kp * error + self.integral + derivative;
+            self.last_output = self.clamp(unclamped);
+            
+            // Anti-windup: back off the integrator by whatever the clamp cut
+            // off, so the output leaves saturation as soon as the error turns.
+            // Without integral action there is nothing to wind up, and backing
+            // off would leave a P-only loop with a permanent offset.
+            if self.ki > 0.0 {
+                self.integral -= unclamped - self.last_output;
+            }
+            self.prev_pv = Some(pv);
+            self.last_time = Some(now);
+        }
+        
+        bus.write(self.output, SignalValue::Float(self.last_output))?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "PID"
+    }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(PidState {
+            integral: self.integral,
+            prev_pv: self.prev_pv,
+            started: self.last_time.is_some(),
+            last_output: self.last_output,
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, clock: &dyn Clock) -> Result<()> {
+        let state: PidState = serde_json::from_value(state)
+            .map_err(|e| PlcError::StateError(format!("PID '{}': {}", self.name, e)))?;
+        // The next sample period starts at the restore, so the time the
+        // controller was not running is not integrated
+        self.integral = state.integral;
+        self.prev_pv = state.prev_pv;
+        self.last_time = state.started.then(|| clock.now());
+        self.last_output = state.last_output;
+        Ok(())
+    }
+}
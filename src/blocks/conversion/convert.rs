@@ -0,0 +1,135 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId, SignalType, SignalValue}};
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use std::collections::HashMap;
+
+/// Source and target type of a conversion block named `<FROM>_TO_<TO>`,
+/// such as `INT_TO_REAL` or `UINT_TO_DINT`, or `None` if the name is not
+/// one. Strings do not convert, and BOOL only to and from integers.
+pub fn conversion_types(block_type: &str) -> Option<(SignalType, SignalType)> {
+    let (from, to) = block_type.split_once("_TO_")?;
+    let (from, to) = (SignalType::parse(from)?, SignalType::parse(to)?);
+    let convertible = match (from, to) {
+        _ if from == to => false,
+        (SignalType::String, _) | (_, SignalType::String) => false,
+        (SignalType::Bool, other) | (other, SignalType::Bool) => other.is_integer(),
+        _ => true,
+    };
+    convertible.then_some((from, to))
+}
+
+/// How a conversion from a real to an integer or time type rounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// To the nearest whole number, halves away from zero
+    Nearest,
+    /// To the nearest whole number, halves to the even one
+    Even,
+    /// Towards zero
+    Truncate,
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceil,
+}
+
+impl Rounding {
+    pub fn parse(name: &str) -> Option<Self> {
+        let rounding = match name {
+            "nearest" => Rounding::Nearest,
+            "even" => Rounding::Even,
+            "truncate" => Rounding::Truncate,
+            "floor" => Rounding::Floor,
+            "ceil" => Rounding::Ceil,
+            _ => return None,
+        };
+        Some(rounding)
+    }
+    
+    pub fn apply(self, value: f64) -> f64 {
+        match self {
+            Rounding::Nearest => value.round(),
+            Rounding::Even => value.round_ties_even(),
+            Rounding::Truncate => value.trunc(),
+            Rounding::Floor => value.floor(),
+            Rounding::Ceil => value.ceil(),
+        }
+    }
+}
+
+/// Explicit type conversion, the way to mix types when `strict_types` is on.
+///
+/// Values convert as with `SignalValue::convert`, except that reals going to
+/// an integer or time type are first rounded as the `rounding` parameter
+/// says: `nearest` (the default), `even`, `truncate`, `floor` or `ceil`.
+/// Values out of range of the target type fail.
+pub struct ConvertBlock {
+    name: String,
+    block_type: String,
+    to: SignalType,
+    rounding: Rounding,
+    input: SignalId,
+    output: SignalId,
+}
+
+impl ConvertBlock {
+    pub fn new(
+        name: String,
+        block_type: &str,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let (from, to) = conversion_types(block_type)
+            .ok_or_else(|| PlcError::ConfigError(format!("Unknown block type: {}", block_type)))?;
+        
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| PlcError::ConfigError(format!("{} requires 'in' input", block_type)))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError(format!("{} requires 'out' output", block_type)))?);
+        
+        let rounding = match params.get("rounding") {
+            None => Rounding::Nearest,
+            Some(_) if !from.is_float() || to.integer_range().is_none() => return Err(PlcError::ConfigError(format!(
+                "{} does not round, 'rounding' only applies to conversions from real to integer", block_type
+            ))),
+            Some(value) => value.as_str().and_then(Rounding::parse).ok_or_else(|| PlcError::ConfigError(format!(
+                "{} 'rounding' must be nearest, even, truncate, floor or ceil", block_type
+            )))?,
+        };
+        
+        Ok(Self { name, block_type: block_type.to_string(), to, rounding, input, output })
+    }
+}
+
+impl Block for ConvertBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let value = match bus.read(self.input)? {
+            SignalValue::Float(f) if self.to.integer_range().is_some() => SignalValue::Float(self.rounding.apply(f)),
+            SignalValue::Real(f) if self.to.integer_range().is_some() => {
+                SignalValue::Float(self.rounding.apply(f as f64))
+            }
+            value => value,
+        };
+        
+        let converted = value.convert(self.to).map_err(|e| match e {
+            PlcError::ConversionError(message) => PlcError::ConversionError(format!(
+                "{} '{}': {}", self.block_type, self.name, message
+            )),
+            e => e,
+        })?;
+        
+        bus.write(self.output, converted)?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        &self.block_type
+    }
+}
@@ -0,0 +1,5 @@
+mod convert;
+mod bcd;
+
+pub use convert::{conversion_types, ConvertBlock, Rounding};
+pub use bcd::{BcdBlock, BcdDirection};
@@ -1,48 +1,54 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Up/Down Counter with preset value
 pub struct Counter {
     name: String,
-    count_up: String,
-    count_down: String,
-    reset: String,
-    preset_input: Option<String>,
-    output: String,
-    done_output: Option<String>,
+    count_up: SignalId,
+    count_down: SignalId,
+    reset: SignalId,
+    preset_input: Option<SignalId>,
+    output: SignalId,
+    done_output: Option<SignalId>,
     preset: i32,
     count: i32,
     prev_up: bool,
     prev_down: bool,
 }
 
+#[derive(Serialize, Deserialize)]
+struct CounterState {
+    count: i32,
+    prev_up: bool,
+    prev_down: bool,
+}
+
 impl Counter {
     pub fn new(
         name: String,
         inputs: &HashMap<String, String>,
         outputs: &HashMap<String, String>,
-        params: &HashMap<String, serde_yaml::Value>
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
     ) -> Result<Self> {
-        let count_up = inputs.get("cu")
-            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'cu' input".to_string()))?
-            .clone();
+        let count_up = bus.register(inputs.get("cu")
+            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'cu' input".to_string()))?);
             
-        let count_down = inputs.get("cd")
-            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'cd' input".to_string()))?
-            .clone();
+        let count_down = bus.register(inputs.get("cd")
+            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'cd' input".to_string()))?);
             
-        let reset = inputs.get("r")
-            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'r' input".to_string()))?
-            .clone();
+        let reset = bus.register(inputs.get("r")
+            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'r' input".to_string()))?);
             
-        let preset_input = inputs.get("pv").cloned();
+        let preset_input = inputs.get("pv").map(|name| bus.register(name));
         
-        let output = outputs.get("cv")
-            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'cv' output".to_string()))?
-            .clone();
+        let output = bus.register(outputs.get("cv")
+            .ok_or_else(|| crate::PlcError::ConfigError("COUNTER requires 'cv' output".to_string()))?);
             
-        let done_output = outputs.get("q").cloned();
+        let done_output = outputs.get("q").map(|name| bus.register(name));
         
         let preset = params.get("preset")
             .and_then(|v| v.as_i64())
@@ -66,27 +72,27 @@ impl Counter {
 }
 
 impl Block for Counter {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        // Get current preset value if input is connected
+        if let Some(pv) = self.preset_input {
+            if let Ok(preset_value) = bus.read_int(pv) {
+                self.preset = preset_value;
+            }
+        }
+        
         // Check reset first
-        if bus.get_bool(&self.reset)? {
+        if bus.read_bool(self.reset)? {
             self.count = 0;
         } else {
-            // Get current preset value if input is connected
-            if let Some(pv) = &self.preset_input {
-                if let Ok(preset_value) = bus.get_int(pv) {
-                    self.preset = preset_value;
-                }
-            }
-            
             // Check for count up edge
-            let current_up = bus.get_bool(&self.count_up)?;
+            let current_up = bus.read_bool(self.count_up)?;
             if current_up && !self.prev_up {
                 self.count += 1;
             }
             self.prev_up = current_up;
             
             // Check for count down edge
-            let current_down = bus.get_bool(&self.count_down)?;
+            let current_down = bus.read_bool(self.count_down)?;
             if current_down && !self.prev_down {
                 self.count -= 1;
             }
@@ -94,10 +100,10 @@ impl Block for Counter {
         }
         
         // Set outputs
-        bus.set(&self.output, SignalValue::Int(self.count))?;
+        bus.write(self.output, SignalValue::Int(self.count))?;
         
-        if let Some(done) = &self.done_output {
-            bus.set(done, SignalValue::Bool(self.count >= self.preset))?;
+        if let Some(done) = self.done_output {
+            bus.write(done, SignalValue::Bool(self.count >= self.preset))?;
         }
         
         Ok(())
@@ -110,4 +116,21 @@ impl Block for Counter {
     fn block_type(&self) -> &str {
         "COUNTER"
     }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(CounterState {
+            count: self.count,
+            prev_up: self.prev_up,
+            prev_down: self.prev_down,
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, _clock: &dyn Clock) -> Result<()> {
+        let state: CounterState = serde_json::from_value(state)
+            .map_err(|e| crate::PlcError::StateError(format!("COUNTER '{}': {}", self.name, e)))?;
+        self.count = state.count;
+        self.prev_up = state.prev_up;
+        self.prev_down = state.prev_down;
+        Ok(())
+    }
 }
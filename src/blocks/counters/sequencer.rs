@@ -1,5 +1,7 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Sequencer - Simple incrementing counter with wrap-around
@@ -7,42 +9,46 @@ use std::collections::HashMap;
 /// Perfect for rotating equipment, pump alternation, etc.
 pub struct Sequencer {
     name: String,
-    trigger: String,
-    reset: String,
-    index_output: String,
+    trigger: SignalId,
+    reset: SignalId,
+    index_output: SignalId,
     max: i32,
     current_index: i32,
     prev_trigger: bool,
 }
 
+#[derive(Serialize, Deserialize)]
+struct SequencerState {
+    current_index: i32,
+    prev_trigger: bool,
+}
+
 impl Sequencer {
     pub fn new(
         name: String,
         inputs: &HashMap<String, String>,
         outputs: &HashMap<String, String>,
-        params: &HashMap<String, serde_yaml::Value>
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
     ) -> Result<Self> {
-        let trigger = inputs.get("trigger")
-            .ok_or_else(|| crate::PlcError::ConfigError("SEQUENCER requires 'trigger' input".to_string()))?
-            .clone();
-            
-        let reset = inputs.get("reset")
-            .ok_or_else(|| crate::PlcError::ConfigError("SEQUENCER requires 'reset' input".to_string()))?
-            .clone();
-            
-        let index_output = outputs.get("index")
-            .ok_or_else(|| crate::PlcError::ConfigError("SEQUENCER requires 'index' output".to_string()))?
-            .clone();
-            
+        let trigger = bus.register(inputs.get("trigger")
+            .ok_or_else(|| crate::PlcError::ConfigError("SEQUENCER requires 'trigger' input".to_string()))?);
+        
+        let reset = bus.register(inputs.get("reset")
+            .ok_or_else(|| crate::PlcError::ConfigError("SEQUENCER requires 'reset' input".to_string()))?);
+        
+        let index_output = bus.register(outputs.get("index")
+            .ok_or_else(|| crate::PlcError::ConfigError("SEQUENCER requires 'index' output".to_string()))?);
+        
         let max = params.get("max")
             .and_then(|v| v.as_i64())
             .map(|v| v as i32)
             .ok_or_else(|| crate::PlcError::ConfigError("SEQUENCER requires 'max' parameter".to_string()))?;
-            
+        
         if max <= 0 {
             return Err(crate::PlcError::ConfigError("SEQUENCER 'max' must be positive".to_string()));
         }
-            
+        
         Ok(Self {
             name,
             trigger,
@@ -56,14 +62,14 @@ impl Sequencer {
 }
 
 impl Block for Sequencer {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
         // Check reset first (highest priority)
-        if bus.get_bool(&self.reset)? {
+        if bus.read_bool(self.reset)? {
             self.current_index = 0;
             self.prev_trigger = false;
         } else {
             // Check for rising edge on trigger
-            let current_trigger = bus.get_bool(&self.trigger)?;
+            let current_trigger = bus.read_bool(self.trigger)?;
             
             if current_trigger && !self.prev_trigger {
                 // Increment and wrap
@@ -74,7 +80,7 @@ impl Block for Sequencer {
         }
         
         // Always output current index
-        bus.set(&self.index_output, SignalValue::Int(self.current_index))?;
+        bus.write(self.index_output, SignalValue::Int(self.current_index))?;
         
         Ok(())
     }
@@ -86,4 +92,20 @@ impl Block for Sequencer {
     fn block_type(&self) -> &str {
         "SEQUENCER"
     }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(SequencerState {
+            current_index: self.current_index,
+            prev_trigger: self.prev_trigger,
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, _clock: &dyn Clock) -> Result<()> {
+        let state: SequencerState = serde_json::from_value(state)
+            .map_err(|e| crate::PlcError::StateError(format!("SEQUENCER '{}': {}", self.name, e)))?;
+        // A smaller `max` than when the state was saved must not leave the index out of range
+        self.current_index = state.current_index.rem_euclid(self.max);
+        self.prev_trigger = state.prev_trigger;
+        Ok(())
+    }
 }
@@ -0,0 +1,265 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId, SignalType, SignalValue}};
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use std::collections::HashMap;
+
+/// Binary arithmetic operations, applied left to right across `in1..inN`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+impl MathOp {
+    fn block_type(&self) -> &'static str {
+        match self {
+            MathOp::Add => "ADD",
+            MathOp::Sub => "SUB",
+            MathOp::Mul => "MUL",
+            MathOp::Div => "DIV",
+            MathOp::Mod => "MOD",
+        }
+    }
+}
+
+/// Single-input arithmetic operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Abs,
+    Neg,
+}
+
+impl UnaryOp {
+    fn block_type(&self) -> &'static str {
+        match self {
+            UnaryOp::Abs => "ABS",
+            UnaryOp::Neg => "NEG",
+        }
+    }
+}
+
+/// Numeric operand after promotion. Integers keep the wider of their two
+/// types until they meet a real, at which point the whole expression
+/// continues in that real type, or in FLOAT once it meets one.
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Int(i128, SignalType),
+    Float(f64, SignalType),
+}
+
+impl Number {
+    fn from_signal(value: &SignalValue) -> Result<Self> {
+        match value {
+            SignalValue::Float(f) => Ok(Number::Float(*f, SignalType::Float)),
+            SignalValue::Real(f) => Ok(Number::Float(*f as f64, SignalType::Real)),
+            other if other.signal_type().is_integer() => Ok(Number::Int(other.as_i128().unwrap_or_default(), other.signal_type())),
+            other => Err(PlcError::TypeMismatch {
+                expected: "numeric".to_string(),
+                actual: other.type_name().to_string(),
+            }),
+        }
+    }
+    
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(i, _) => i as f64,
+            Number::Float(f, _) => f,
+        }
+    }
+    
+    /// Integer of type `signal_type`, or `None` if it is out of range
+    fn int(value: Option<i128>, signal_type: SignalType) -> Option<Number> {
+        let (min, max) = signal_type.integer_range()?;
+        value.filter(|i| (min..=max).contains(i)).map(|i| Number::Int(i, signal_type))
+    }
+    
+    fn into_signal(self) -> SignalValue {
+        match self {
+            Number::Int(i, signal_type) => SignalValue::from_integer(i, signal_type).unwrap_or(SignalValue::LInt(i as i64)),
+            Number::Float(f, SignalType::Real) => SignalValue::Real(f as f32),
+            Number::Float(f, _) => SignalValue::Float(f),
+        }
+    }
+}
+
+/// Integer type that holds every value of `a` and of `b`, or LINT if none of
+/// them does
+fn wider(a: SignalType, b: SignalType) -> SignalType {
+    let contains = |outer: SignalType, inner: SignalType| match (outer.integer_range(), inner.integer_range()) {
+        (Some((outer_min, outer_max)), Some((inner_min, inner_max))) => outer_min <= inner_min && inner_max <= outer_max,
+        _ => false,
+    };
+    [a, b, SignalType::Int16, SignalType::Int, SignalType::LInt].into_iter()
+        .find(|&candidate| contains(candidate, a) && contains(candidate, b))
+        .unwrap_or(SignalType::LInt)
+}
+
+fn apply(op: MathOp, block: &str, lhs: Number, rhs: Number) -> Result<Number> {
+    match (lhs, rhs) {
+        (Number::Int(a, a_type), Number::Int(b, b_type)) => {
+            if b == 0 && matches!(op, MathOp::Div | MathOp::Mod) {
+                return Err(PlcError::ExecutionError(format!(
+                    "{} '{}': division by zero", op.block_type(), block
+                )));
+            }
+            let result = match op {
+                MathOp::Add => a.checked_add(b),
+                MathOp::Sub => a.checked_sub(b),
+                MathOp::Mul => a.checked_mul(b),
+                MathOp::Div => a.checked_div(b),
+                MathOp::Mod => a.checked_rem(b),
+            };
+            Number::int(result, wider(a_type, b_type)).ok_or_else(|| PlcError::ExecutionError(format!(
+                "{} '{}': integer overflow", op.block_type(), block
+            )))
+        }
+        (a, b) => {
+            let float_type = match (a, b) {
+                (Number::Float(_, SignalType::Float), _) | (_, Number::Float(_, SignalType::Float)) => SignalType::Float,
+                _ => SignalType::Real,
+            };
+            let (a, b) = (a.as_f64(), b.as_f64());
+            if b == 0.0 && matches!(op, MathOp::Div | MathOp::Mod) {
+                return Err(PlcError::ExecutionError(format!(
+                    "{} '{}': division by zero", op.block_type(), block
+                )));
+            }
+            let result = match op {
+                MathOp::Add => a + b,
+                MathOp::Sub => a - b,
+                MathOp::Mul => a * b,
+                MathOp::Div => a / b,
+                MathOp::Mod => a % b,
+            };
+            Ok(Number::Float(result, float_type))
+        }
+    }
+}
+
+/// Collect `in*` inputs ordered by their numeric suffix (in1, in2, ..., in10),
+/// since SUB, DIV and MOD are not commutative.
+fn ordered_inputs(inputs: &HashMap<String, String>) -> Vec<String> {
+    let mut ports: Vec<(&String, &String)> = inputs.iter()
+        .filter(|(k, _)| k.starts_with("in"))
+        .collect();
+    ports.sort_by_key(|(k, _)| (k[2..].parse::<u32>().unwrap_or(u32::MAX), k.to_string()));
+    ports.into_iter().map(|(_, v)| v.clone()).collect()
+}
+
+/// N-ary arithmetic block (ADD, SUB, MUL, DIV, MOD)
+pub struct MathBlock {
+    name: String,
+    op: MathOp,
+    inputs: Vec<SignalId>,
+    output: SignalId,
+}
+
+impl MathBlock {
+    pub fn new(
+        name: String,
+        op: MathOp,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let inputs: Vec<SignalId> = ordered_inputs(inputs)
+            .iter()
+            .map(|name| bus.register(name))
+            .collect();
+        
+        if inputs.len() < 2 {
+            return Err(PlcError::ConfigError(format!(
+                "{} requires at least 'in1' and 'in2' inputs", op.block_type()
+            )));
+        }
+        
+        if op == MathOp::Mod && inputs.len() != 2 {
+            return Err(PlcError::ConfigError(
+                "MOD requires exactly 'in1' and 'in2' inputs".to_string()
+            ));
+        }
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError(format!("{} requires 'out' output", op.block_type())))?);
+        
+        Ok(Self { name, op, inputs, output })
+    }
+}
+
+impl Block for MathBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let mut result = Number::from_signal(&bus.read(self.inputs[0])?)?;
+        
+        for &input in &self.inputs[1..] {
+            let operand = Number::from_signal(&bus.read(input)?)?;
+            result = apply(self.op, &self.name, result, operand)?;
+        }
+        
+        bus.write(self.output, result.into_signal())?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        self.op.block_type()
+    }
+}
+
+/// Single-input arithmetic block (ABS, NEG)
+pub struct UnaryMathBlock {
+    name: String,
+    op: UnaryOp,
+    input: SignalId,
+    output: SignalId,
+}
+
+impl UnaryMathBlock {
+    pub fn new(
+        name: String,
+        op: UnaryOp,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| PlcError::ConfigError(format!("{} requires 'in' input", op.block_type())))?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError(format!("{} requires 'out' output", op.block_type())))?);
+        
+        Ok(Self { name, op, input, output })
+    }
+}
+
+impl Block for UnaryMathBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let value = Number::from_signal(&bus.read(self.input)?)?;
+        
+        let result = match (self.op, value) {
+            (UnaryOp::Abs, Number::Int(i, signal_type)) => Number::int(Some(i.abs()), signal_type),
+            (UnaryOp::Neg, Number::Int(i, signal_type)) => Number::int(Some(-i), signal_type),
+            (UnaryOp::Abs, Number::Float(f, float_type)) => Some(Number::Float(f.abs(), float_type)),
+            (UnaryOp::Neg, Number::Float(f, float_type)) => Some(Number::Float(-f, float_type)),
+        }
+        .ok_or_else(|| PlcError::ExecutionError(format!(
+            "{} '{}': integer overflow", self.op.block_type(), self.name
+        )))?;
+        
+        bus.write(self.output, result.into_signal())?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        self.op.block_type()
+    }
+}
@@ -0,0 +1,3 @@
+mod arithmetic;
+
+pub use arithmetic::{MathBlock, MathOp, UnaryMathBlock, UnaryOp};
@@ -3,34 +3,45 @@ pub mod basic;
 pub mod timers;
 pub mod triggers;
 pub mod counters;
+pub mod math;
+pub mod control;
+pub mod conversion;
+pub mod bits;
+pub mod selection;
+pub mod analog;
+pub mod ports;
 
-use crate::{Result, PlcError};
+use crate::{Result, PlcError, signal::SignalBus};
 use traits::Block;
 
 // Re-export commonly used items
 pub use traits::Block as BlockTrait;
 pub use traits::BlockConfig;
 
-/// Factory function to create blocks from configuration
-pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
+/// Factory function to create blocks from configuration. Signal names are
+/// resolved to handles on `bus`, which is the bus the block must execute against.
+pub fn create_block(config: &BlockConfig, bus: &SignalBus) -> Result<Box<dyn Block>> {
     match config.block_type.as_str() {
         // Logic blocks
         "AND" => Ok(Box::new(basic::AndBlock::new(
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         "OR" => Ok(Box::new(basic::OrBlock::new(
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         "NOT" => Ok(Box::new(basic::NotBlock::new(
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         // Comparison blocks
@@ -38,18 +49,21 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         "GT" => Ok(Box::new(basic::GtBlock::new(
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         "LT" => Ok(Box::new(basic::LtBlock::new(
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         // Trigger blocks
@@ -57,18 +71,21 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         "F_TRIG" => Ok(Box::new(triggers::FTrig::new(
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         "SR_LATCH" => Ok(Box::new(triggers::SRLatch::new(
             config.name.clone(),
             &config.inputs,
             &config.outputs,
+            bus,
         )?)),
         
         // Timer blocks
@@ -77,6 +94,7 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             &config.inputs,
             &config.outputs,
             &config.params,
+            bus,
         )?)),
         
         "TOF" => Ok(Box::new(timers::TOF::new(
@@ -84,6 +102,7 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             &config.inputs,
             &config.outputs,
             &config.params,
+            bus,
         )?)),
         
         "TP" => Ok(Box::new(timers::TP::new(
@@ -91,6 +110,7 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             &config.inputs,
             &config.outputs,
             &config.params,
+            bus,
         )?)),
         
         // Counter blocks
@@ -99,6 +119,7 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             &config.inputs,
             &config.outputs,
             &config.params,
+            bus,
         )?)),
         
         "SEQUENCER" => Ok(Box::new(counters::Sequencer::new(
@@ -106,6 +127,73 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             &config.inputs,
             &config.outputs,
             &config.params,
+            bus,
+        )?)),
+        
+        // Math blocks
+        "ADD" => Ok(Box::new(math::MathBlock::new(
+            config.name.clone(),
+            math::MathOp::Add,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "SUB" => Ok(Box::new(math::MathBlock::new(
+            config.name.clone(),
+            math::MathOp::Sub,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "MUL" => Ok(Box::new(math::MathBlock::new(
+            config.name.clone(),
+            math::MathOp::Mul,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "DIV" => Ok(Box::new(math::MathBlock::new(
+            config.name.clone(),
+            math::MathOp::Div,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "MOD" => Ok(Box::new(math::MathBlock::new(
+            config.name.clone(),
+            math::MathOp::Mod,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "ABS" => Ok(Box::new(math::UnaryMathBlock::new(
+            config.name.clone(),
+            math::UnaryOp::Abs,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "NEG" => Ok(Box::new(math::UnaryMathBlock::new(
+            config.name.clone(),
+            math::UnaryOp::Neg,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        // Control blocks
+        "PID" => Ok(Box::new(control::PID::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            &config.params,
+            bus,
         )?)),
         
         // Utility blocks
@@ -113,6 +201,159 @@ pub fn create_block(config: &BlockConfig) -> Result<Box<dyn Block>> {
             config.name.clone(),
             &config.outputs,
             &config.params,
+            bus,
+        )?)),
+        
+        "UNIT_DELAY" => Ok(Box::new(basic::UnitDelay::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            &config.params,
+            bus,
+        )?)),
+        
+        // Selection blocks
+        "SEL" => Ok(Box::new(selection::SelBlock::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "MUX" => Ok(Box::new(selection::MuxBlock::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "MIN" => Ok(Box::new(selection::ExtremeBlock::new(
+            config.name.clone(),
+            selection::Extreme::Min,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "MAX" => Ok(Box::new(selection::ExtremeBlock::new(
+            config.name.clone(),
+            selection::Extreme::Max,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "LIMIT" => Ok(Box::new(selection::LimitBlock::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        // Analog blocks
+        "SCALE" => Ok(Box::new(analog::ScaleBlock::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            &config.params,
+            bus,
+        )?)),
+        
+        // Conversion blocks, <FROM>_TO_<TO>
+        block_type if conversion::conversion_types(block_type).is_some() => Ok(Box::new(conversion::ConvertBlock::new(
+            config.name.clone(),
+            block_type,
+            &config.inputs,
+            &config.outputs,
+            &config.params,
+            bus,
+        )?)),
+        
+        "BCD_TO_INT" => Ok(Box::new(conversion::BcdBlock::new(
+            config.name.clone(),
+            conversion::BcdDirection::Decode,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "INT_TO_BCD" => Ok(Box::new(conversion::BcdBlock::new(
+            config.name.clone(),
+            conversion::BcdDirection::Encode,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        // Bit blocks
+        "BAND" => Ok(Box::new(bits::BitwiseBlock::new(
+            config.name.clone(),
+            bits::BitOp::And,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "BOR" => Ok(Box::new(bits::BitwiseBlock::new(
+            config.name.clone(),
+            bits::BitOp::Or,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "BXOR" => Ok(Box::new(bits::BitwiseBlock::new(
+            config.name.clone(),
+            bits::BitOp::Xor,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "SHL" => Ok(Box::new(bits::ShiftBlock::new(
+            config.name.clone(),
+            bits::ShiftOp::Shl,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "SHR" => Ok(Box::new(bits::ShiftBlock::new(
+            config.name.clone(),
+            bits::ShiftOp::Shr,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "ROL" => Ok(Box::new(bits::ShiftBlock::new(
+            config.name.clone(),
+            bits::ShiftOp::Rol,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "ROR" => Ok(Box::new(bits::ShiftBlock::new(
+            config.name.clone(),
+            bits::ShiftOp::Ror,
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "PACK" => Ok(Box::new(bits::PackBlock::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            bus,
+        )?)),
+        
+        "UNPACK" => Ok(Box::new(bits::UnpackBlock::new(
+            config.name.clone(),
+            &config.inputs,
+            &config.outputs,
+            bus,
         )?)),
         
         _ => Err(PlcError::ConfigError(format!(
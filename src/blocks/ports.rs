@@ -0,0 +1,174 @@
+use crate::signal::SignalType;
+use super::conversion::conversion_types;
+
+/// Signal types a block port accepts or produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortType {
+    Bool,
+    Int,
+    Float,
+    /// Int or float
+    Numeric,
+    /// Any integer or bit string type, which the block works in
+    AnyInt,
+    /// Any integer or real type, which the block reads as it is
+    AnyNum,
+    /// REAL or FLOAT, which the block writes its result as
+    AnyReal,
+    String,
+    Any,
+    /// Exactly one type, for the conversion blocks
+    Exact(SignalType),
+}
+
+impl PortType {
+    /// Whether a signal declared with `signal_type` can be connected to this
+    /// port. Int ports take any integer or bit string type, float ports REAL
+    /// and LREAL.
+    pub fn accepts(&self, signal_type: &str) -> bool {
+        let Some(signal_type) = SignalType::parse(signal_type) else {
+            return *self == PortType::Any;
+        };
+        match self {
+            PortType::Bool => signal_type == SignalType::Bool,
+            PortType::Int => signal_type.is_integer(),
+            PortType::Float => signal_type.is_float(),
+            PortType::Numeric => signal_type.is_numeric(),
+            PortType::AnyInt => signal_type.is_integer(),
+            PortType::AnyNum => signal_type.is_numeric(),
+            PortType::AnyReal => signal_type.is_float(),
+            PortType::String => signal_type == SignalType::String,
+            PortType::Any => true,
+            PortType::Exact(exact) => signal_type == *exact,
+        }
+    }
+    
+    /// Like `accepts`, for `strict_types`: bool, int and float ports take
+    /// only that very type, and numeric ports outside a generic group (see
+    /// `is_generic_port`) only float, which they are read as.
+    pub fn accepts_strict(&self, signal_type: &str, generic: bool) -> bool {
+        let Some(signal_type) = SignalType::parse(signal_type) else {
+            return *self == PortType::Any;
+        };
+        match self {
+            PortType::Bool => signal_type == SignalType::Bool,
+            PortType::Int => signal_type == SignalType::Int,
+            PortType::Float => signal_type == SignalType::Float,
+            PortType::Numeric if !generic => signal_type == SignalType::Float,
+            other => other.accepts(signal_type.name()),
+        }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            PortType::Bool => "bool",
+            PortType::Int => "int",
+            PortType::Float => "float",
+            PortType::Numeric => "numeric",
+            PortType::AnyInt => "integer",
+            PortType::AnyNum => "numeric",
+            PortType::AnyReal => "real",
+            PortType::String => "string",
+            PortType::Any => "any",
+            PortType::Exact(exact) => exact.name(),
+        }
+    }
+}
+
+/// Numbered input ports (`in1`, `in2`, ...) used by N-ary blocks
+fn is_numbered_input(port: &str) -> bool {
+    port.strip_prefix("in")
+        .map(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or(false)
+}
+
+/// Bit number of a `bit0` ... `bit63` port of PACK and UNPACK
+pub fn bit_index(port: &str) -> Option<u32> {
+    port.strip_prefix("bit")
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|n| n.parse().ok())
+        .filter(|&n| n < 64)
+}
+
+/// Type of a block's input port, or `None` if the block type has no such port
+pub fn input_port_type(block_type: &str, port: &str) -> Option<PortType> {
+    match (block_type, port) {
+        ("AND" | "OR", p) if is_numbered_input(p) => Some(PortType::Bool),
+        ("NOT", "in") => Some(PortType::Bool),
+        ("EQ", "in1" | "in2") => Some(PortType::Any),
+        ("GT" | "LT", "in1" | "in2") => Some(PortType::Numeric),
+        ("R_TRIG" | "F_TRIG", "clk") => Some(PortType::Bool),
+        ("SR_LATCH", "set" | "reset") => Some(PortType::Bool),
+        ("TON" | "TOF" | "TP", "in") => Some(PortType::Bool),
+        ("COUNTER", "cu" | "cd" | "r") => Some(PortType::Bool),
+        ("COUNTER", "pv") => Some(PortType::Int),
+        ("SEQUENCER", "trigger" | "reset") => Some(PortType::Bool),
+        ("ADD" | "SUB" | "MUL" | "DIV" | "MOD", p) if is_numbered_input(p) => Some(PortType::Numeric),
+        ("ABS" | "NEG", "in") => Some(PortType::Numeric),
+        ("PID", "sp" | "pv" | "man") => Some(PortType::Numeric),
+        ("PID", "auto") => Some(PortType::Bool),
+        ("UNIT_DELAY", "in") => Some(PortType::Any),
+        ("BAND" | "BOR" | "BXOR", p) if is_numbered_input(p) => Some(PortType::AnyInt),
+        ("SHL" | "SHR" | "ROL" | "ROR", "in" | "n") => Some(PortType::AnyInt),
+        ("PACK", p) if bit_index(p).is_some() => Some(PortType::Bool),
+        ("UNPACK" | "BCD_TO_INT" | "INT_TO_BCD", "in") => Some(PortType::AnyInt),
+        ("SCALE", "in") => Some(PortType::AnyNum),
+        ("LIMIT", "mn" | "in" | "mx") => Some(PortType::Numeric),
+        ("MIN" | "MAX", p) if is_numbered_input(p) => Some(PortType::Numeric),
+        ("SEL", "g") => Some(PortType::Bool),
+        ("SEL", "in0" | "in1") => Some(PortType::Any),
+        ("MUX", "k") => Some(PortType::AnyInt),
+        ("MUX", p) if is_numbered_input(p) => Some(PortType::Any),
+        (block_type, "in") => conversion_types(block_type).map(|(from, _)| PortType::Exact(from)),
+        _ => None,
+    }
+}
+
+/// Optional output of every block: true after the block executed
+/// successfully, false after it failed or while it is skipped
+pub const ENO_PORT: &str = "eno";
+
+/// Type of a block's output port, or `None` if the block type has no such port
+pub fn output_port_type(block_type: &str, port: &str) -> Option<PortType> {
+    match (block_type, port) {
+        ("AND" | "OR" | "NOT" | "EQ" | "GT" | "LT", "out") => Some(PortType::Bool),
+        ("R_TRIG" | "F_TRIG" | "SR_LATCH", "q") => Some(PortType::Bool),
+        ("TON" | "TOF" | "TP", "q") => Some(PortType::Bool),
+        ("TON" | "TOF" | "TP", "et") => Some(PortType::Int),
+        ("COUNTER", "cv") => Some(PortType::Int),
+        ("COUNTER", "q") => Some(PortType::Bool),
+        ("SEQUENCER", "index") => Some(PortType::Int),
+        ("ADD" | "SUB" | "MUL" | "DIV" | "MOD" | "ABS" | "NEG", "out") => Some(PortType::Numeric),
+        ("PID", "out") => Some(PortType::Float),
+        ("CONST" | "UNIT_DELAY", "out") => Some(PortType::Any),
+        ("BAND" | "BOR" | "BXOR" | "SHL" | "SHR" | "ROL" | "ROR", "out") => Some(PortType::AnyInt),
+        ("PACK" | "BCD_TO_INT" | "INT_TO_BCD", "out") => Some(PortType::AnyInt),
+        ("UNPACK", p) if bit_index(p).is_some() => Some(PortType::Bool),
+        ("SCALE", "out") => Some(PortType::AnyReal),
+        ("SCALE", "out_of_range") => Some(PortType::Bool),
+        ("LIMIT" | "MIN" | "MAX", "out") => Some(PortType::Numeric),
+        ("SEL" | "MUX", "out") => Some(PortType::Any),
+        (_, ENO_PORT) => Some(PortType::Bool),
+        (block_type, "out") => conversion_types(block_type).map(|(_, to)| PortType::Exact(to)),
+        _ => None,
+    }
+}
+
+/// Whether a port belongs to the block's generic group: the operands and
+/// result of arithmetic, bitwise operations and MIN, MAX and LIMIT, the two
+/// sides of a comparison, the choices and result of SEL and MUX, and both
+/// ends of a unit delay. These work in whatever type their signals have, so
+/// under `strict_types` every signal of the group must have the same one.
+pub fn is_generic_port(block_type: &str, port: &str) -> bool {
+    match (block_type, port) {
+        ("EQ" | "GT" | "LT", "in1" | "in2") => true,
+        ("ADD" | "SUB" | "MUL" | "DIV" | "MOD", p) => is_numbered_input(p) || p == "out",
+        ("BAND" | "BOR" | "BXOR", p) => is_numbered_input(p) || p == "out",
+        ("SHL" | "SHR" | "ROL" | "ROR", "in" | "out") => true,
+        ("LIMIT", "mn" | "in" | "mx" | "out") => true,
+        ("MIN" | "MAX" | "MUX", p) => is_numbered_input(p) || p == "out",
+        ("SEL", "in0" | "in1" | "out") => true,
+        ("ABS" | "NEG" | "UNIT_DELAY", "in" | "out") => true,
+        _ => false,
+    }
+}
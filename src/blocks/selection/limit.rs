@@ -0,0 +1,144 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId}};
+use crate::blocks::basic::compare;
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use super::select::numbered_inputs;
+
+/// Which end of its inputs MIN and MAX pass on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extreme {
+    Min,
+    Max,
+}
+
+impl Extreme {
+    fn block_type(&self) -> &'static str {
+        match self {
+            Extreme::Min => "MIN",
+            Extreme::Max => "MAX",
+        }
+    }
+}
+
+/// Smallest (MIN) or largest (MAX) of `in1..inN`. Values compare like GT and
+/// LT do; a NaN input never wins.
+pub struct ExtremeBlock {
+    name: String,
+    extreme: Extreme,
+    inputs: Vec<SignalId>,
+    output: SignalId,
+}
+
+impl ExtremeBlock {
+    pub fn new(
+        name: String,
+        extreme: Extreme,
+        inputs: &HashMap<String, String>,
+        outputs: &HashMap<String, String>,
+        bus: &SignalBus
+    ) -> Result<Self> {
+        let inputs: Vec<SignalId> = numbered_inputs(inputs).into_iter()
+            .map(|(_, signal)| bus.register(signal))
+            .collect();
+        
+        if inputs.len() < 2 {
+            return Err(PlcError::ConfigError(format!(
+                "{} requires at least 'in1' and 'in2' inputs", extreme.block_type()
+            )));
+        }
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError(format!("{} requires 'out' output", extreme.block_type())))?);
+        
+        Ok(Self { name, extreme, inputs, output })
+    }
+}
+
+impl Block for ExtremeBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let strict = bus.is_strict();
+        let wins = match self.extreme {
+            Extreme::Min => Ordering::Less,
+            Extreme::Max => Ordering::Greater,
+        };
+        
+        let mut result = bus.read(self.inputs[0])?;
+        for &input in &self.inputs[1..] {
+            let value = bus.read(input)?;
+            // Only a NaN does not compare equal to itself
+            let result_is_nan = compare(&result, &result, strict)?.is_none();
+            if result_is_nan || compare(&value, &result, strict)? == Some(wins) {
+                result = value;
+            }
+        }
+        
+        bus.write(self.output, result)?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        self.extreme.block_type()
+    }
+}
+
+/// `in` held between `mn` and `mx`
+pub struct LimitBlock {
+    name: String,
+    min: SignalId,
+    input: SignalId,
+    max: SignalId,
+    output: SignalId,
+}
+
+impl LimitBlock {
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let input = |port: &str| inputs.get(port)
+            .map(|signal| bus.register(signal))
+            .ok_or_else(|| PlcError::ConfigError(format!("LIMIT requires '{}' input", port)));
+        
+        let (min, input, max) = (input("mn")?, input("in")?, input("mx")?);
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError("LIMIT requires 'out' output".to_string()))?);
+        
+        Ok(Self { name, min, input, max, output })
+    }
+}
+
+impl Block for LimitBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let strict = bus.is_strict();
+        let (min, value, max) = (bus.read(self.min)?, bus.read(self.input)?, bus.read(self.max)?);
+        
+        if compare(&min, &max, strict)? == Some(Ordering::Greater) {
+            return Err(PlcError::ExecutionError(format!(
+                "LIMIT '{}': mn is greater than mx", self.name
+            )));
+        }
+        
+        let result = if compare(&value, &min, strict)? == Some(Ordering::Less) {
+            min
+        } else if compare(&value, &max, strict)? == Some(Ordering::Greater) {
+            max
+        } else {
+            value
+        };
+        
+        bus.write(self.output, result)?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "LIMIT"
+    }
+}
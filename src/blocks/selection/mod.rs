@@ -0,0 +1,5 @@
+mod select;
+mod limit;
+
+pub use select::{MuxBlock, SelBlock};
+pub use limit::{ExtremeBlock, Extreme, LimitBlock};
@@ -0,0 +1,108 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId}};
+use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use std::collections::HashMap;
+
+/// `in<N>` inputs by their number, lowest first
+pub(super) fn numbered_inputs(inputs: &HashMap<String, String>) -> Vec<(u32, &String)> {
+    let mut ports: Vec<(u32, &String)> = inputs.iter()
+        .filter_map(|(port, signal)| Some((port.strip_prefix("in")?.parse().ok()?, signal)))
+        .collect();
+    ports.sort();
+    ports
+}
+
+/// Binary selection: `out` is `in0` while `g` is false and `in1` while it
+/// is true. Inputs may have any type.
+pub struct SelBlock {
+    name: String,
+    gate: SignalId,
+    inputs: [SignalId; 2],
+    output: SignalId,
+}
+
+impl SelBlock {
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let input = |port: &str| inputs.get(port)
+            .map(|signal| bus.register(signal))
+            .ok_or_else(|| PlcError::ConfigError(format!("SEL requires '{}' input", port)));
+        
+        let gate = input("g")?;
+        let inputs = [input("in0")?, input("in1")?];
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError("SEL requires 'out' output".to_string()))?);
+        
+        Ok(Self { name, gate, inputs, output })
+    }
+}
+
+impl Block for SelBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let selected = self.inputs[bus.read_bool(self.gate)? as usize];
+        bus.write(self.output, bus.read(selected)?)?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "SEL"
+    }
+}
+
+/// N-way selection: `out` is the input `in<k>` numbered by the integer on
+/// `k`, from `in0` up. An index without an input is an error, so the output
+/// keeps its last value.
+pub struct MuxBlock {
+    name: String,
+    index: SignalId,
+    inputs: Vec<SignalId>,
+    output: SignalId,
+}
+
+impl MuxBlock {
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let index = bus.register(inputs.get("k")
+            .ok_or_else(|| PlcError::ConfigError("MUX requires 'k' input".to_string()))?);
+        
+        let numbered = numbered_inputs(inputs);
+        if numbered.is_empty() || numbered.iter().enumerate().any(|(position, &(n, _))| n as usize != position) {
+            return Err(PlcError::ConfigError("MUX requires inputs 'in0' to 'inN' without gaps".to_string()));
+        }
+        let inputs = numbered.into_iter().map(|(_, signal)| bus.register(signal)).collect();
+        
+        let output = bus.register(outputs.get("out")
+            .ok_or_else(|| PlcError::ConfigError("MUX requires 'out' output".to_string()))?);
+        
+        Ok(Self { name, index, inputs, output })
+    }
+}
+
+impl Block for MuxBlock {
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let k = bus.read(self.index)?;
+        let selected = k.as_i64()
+            .and_then(|k| usize::try_from(k).ok())
+            .and_then(|k| self.inputs.get(k))
+            .ok_or_else(|| PlcError::ExecutionError(format!(
+                "MUX '{}': index {} is outside in0 to in{}",
+                self.name,
+                k.as_i64().map(|k| k.to_string()).unwrap_or_else(|| k.type_name().to_string()),
+                self.inputs.len() - 1
+            )))?;
+        
+        bus.write(self.output, bus.read(*selected)?)?;
+        Ok(())
+    }
+    
+    fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn block_type(&self) -> &str {
+        "MUX"
+    }
+}
@@ -1,16 +1,28 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::Duration;
 
 /// Timer Off Delay - output turns off after input has been false for preset time
 pub struct TOF {
     name: String,
-    input: String,
-    output: String,
-    elapsed_output: Option<String>,
+    input: SignalId,
+    output: SignalId,
+    elapsed_output: Option<SignalId>,
     preset_ms: u64,
-    start_time: Option<Instant>,
+    start_time: Option<Duration>,
+    /// Elapsed time carried over from a restored state, counted before `start_time`
+    carried_ms: u64,
+    elapsed_ms: u64,
+    prev_input: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TofState {
+    /// Whether the timer is running
+    timing: bool,
     elapsed_ms: u64,
     prev_input: bool,
 }
@@ -20,22 +32,21 @@ impl TOF {
         name: String, 
         inputs: &HashMap<String, String>, 
         outputs: &HashMap<String, String>,
-        params: &HashMap<String, serde_yaml::Value>
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
     ) -> Result<Self> {
-        let input = inputs.get("in")
-            .ok_or_else(|| crate::PlcError::ConfigError("TOF requires 'in' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("q")
-            .ok_or_else(|| crate::PlcError::ConfigError("TOF requires 'q' output".to_string()))?
-            .clone();
-            
-        let elapsed_output = outputs.get("et").cloned();
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| crate::PlcError::ConfigError("TOF requires 'in' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("q")
+            .ok_or_else(|| crate::PlcError::ConfigError("TOF requires 'q' output".to_string()))?);
+        
+        let elapsed_output = outputs.get("et").map(|name| bus.register(name));
         
         let preset_ms = params.get("preset_ms")
             .and_then(|v| v.as_u64())
             .ok_or_else(|| crate::PlcError::ConfigError("TOF requires 'preset_ms' parameter".to_string()))?;
-            
+        
         Ok(Self {
             name,
             input,
@@ -43,6 +54,7 @@ impl TOF {
             elapsed_output,
             preset_ms,
             start_time: None,
+            carried_ms: 0,
             elapsed_ms: 0,
             prev_input: true,
         })
@@ -50,30 +62,32 @@ impl TOF {
 }
 
 impl Block for TOF {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let current_input = bus.get_bool(&self.input)?;
+    fn execute(&mut self, bus: &SignalBus, clock: &dyn Clock) -> Result<()> {
+        let current_input = bus.read_bool(self.input)?;
         
         if !current_input && self.prev_input {
             // Falling edge - start timing
-            self.start_time = Some(Instant::now());
+            self.start_time = Some(clock.now());
+            self.carried_ms = 0;
             self.elapsed_ms = 0;
         } else if current_input {
             // Input is true - reset
             self.start_time = None;
+            self.carried_ms = 0;
             self.elapsed_ms = 0;
-        } else if !current_input && self.start_time.is_some() {
+        } else if let Some(start) = self.start_time {
             // Input remains false - update elapsed time
-            self.elapsed_ms = self.start_time.unwrap().elapsed().as_millis() as u64;
+            self.elapsed_ms = self.carried_ms + clock.now().saturating_sub(start).as_millis() as u64;
         }
         
         self.prev_input = current_input;
         
         // Set outputs - output stays on until timer expires
         let done = current_input || (self.elapsed_ms < self.preset_ms);
-        bus.set(&self.output, SignalValue::Bool(done))?;
+        bus.write(self.output, SignalValue::Bool(done))?;
         
-        if let Some(et_output) = &self.elapsed_output {
-            bus.set(et_output, SignalValue::Int(self.elapsed_ms as i32))?;
+        if let Some(et_output) = self.elapsed_output {
+            bus.write(et_output, SignalValue::Int(self.elapsed_ms as i32))?;
         }
         
         Ok(())
@@ -86,4 +100,23 @@ impl Block for TOF {
     fn block_type(&self) -> &str {
         "TOF"
     }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(TofState {
+            timing: self.start_time.is_some(),
+            elapsed_ms: self.elapsed_ms,
+            prev_input: self.prev_input,
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, clock: &dyn Clock) -> Result<()> {
+        let state: TofState = serde_json::from_value(state)
+            .map_err(|e| crate::PlcError::StateError(format!("TOF '{}': {}", self.name, e)))?;
+        // A running timer continues from the elapsed time it was saved with
+        self.start_time = state.timing.then(|| clock.now());
+        self.carried_ms = if state.timing { state.elapsed_ms } else { 0 };
+        self.elapsed_ms = state.elapsed_ms;
+        self.prev_input = state.prev_input;
+        Ok(())
+    }
 }
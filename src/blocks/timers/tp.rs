@@ -1,16 +1,29 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::Duration;
 
 /// Timer Pulse - generates a pulse of preset duration on rising edge of input
 pub struct TP {
     name: String,
-    input: String,
-    output: String,
-    elapsed_output: Option<String>,
+    input: SignalId,
+    output: SignalId,
+    elapsed_output: Option<SignalId>,
     preset_ms: u64,
-    start_time: Option<Instant>,
+    start_time: Option<Duration>,
+    /// Elapsed time carried over from a restored state, counted before `start_time`
+    carried_ms: u64,
+    elapsed_ms: u64,
+    prev_input: bool,
+    pulse_active: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TpState {
+    /// Whether the timer is running
+    timing: bool,
     elapsed_ms: u64,
     prev_input: bool,
     pulse_active: bool,
@@ -21,22 +34,21 @@ impl TP {
         name: String, 
         inputs: &HashMap<String, String>, 
         outputs: &HashMap<String, String>,
-        params: &HashMap<String, serde_yaml::Value>
+        params: &HashMap<String, serde_yaml::Value>,
+        bus: &SignalBus
     ) -> Result<Self> {
-        let input = inputs.get("in")
-            .ok_or_else(|| crate::PlcError::ConfigError("TP requires 'in' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("q")
-            .ok_or_else(|| crate::PlcError::ConfigError("TP requires 'q' output".to_string()))?
-            .clone();
-            
-        let elapsed_output = outputs.get("et").cloned();
+        let input = bus.register(inputs.get("in")
+            .ok_or_else(|| crate::PlcError::ConfigError("TP requires 'in' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("q")
+            .ok_or_else(|| crate::PlcError::ConfigError("TP requires 'q' output".to_string()))?);
+        
+        let elapsed_output = outputs.get("et").map(|name| bus.register(name));
         
         let preset_ms = params.get("preset_ms")
             .and_then(|v| v.as_u64())
             .ok_or_else(|| crate::PlcError::ConfigError("TP requires 'preset_ms' parameter".to_string()))?;
-            
+        
         Ok(Self {
             name,
             input,
@@ -44,6 +56,7 @@ impl TP {
             elapsed_output,
             preset_ms,
             start_time: None,
+            carried_ms: 0,
             elapsed_ms: 0,
             prev_input: false,
             pulse_active: false,
@@ -52,35 +65,38 @@ impl TP {
 }
 
 impl Block for TP {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let current_input = bus.get_bool(&self.input)?;
+    fn execute(&mut self, bus: &SignalBus, clock: &dyn Clock) -> Result<()> {
+        let current_input = bus.read_bool(self.input)?;
         
         // Detect rising edge
         if current_input && !self.prev_input && !self.pulse_active {
             // Start pulse
-            self.start_time = Some(Instant::now());
+            self.start_time = Some(clock.now());
+            self.carried_ms = 0;
             self.elapsed_ms = 0;
             self.pulse_active = true;
         }
         
         // Update timing if pulse is active
-        if self.pulse_active && self.start_time.is_some() {
-            self.elapsed_ms = self.start_time.unwrap().elapsed().as_millis() as u64;
-            
-            // Check if pulse duration exceeded
-            if self.elapsed_ms >= self.preset_ms {
-                self.pulse_active = false;
-                self.start_time = None;
+        if self.pulse_active {
+            if let Some(start) = self.start_time {
+                self.elapsed_ms = self.carried_ms + clock.now().saturating_sub(start).as_millis() as u64;
+                
+                // Check if pulse duration exceeded
+                if self.elapsed_ms >= self.preset_ms {
+                    self.pulse_active = false;
+                    self.start_time = None;
+                }
             }
         }
         
         self.prev_input = current_input;
         
         // Set outputs
-        bus.set(&self.output, SignalValue::Bool(self.pulse_active))?;
+        bus.write(self.output, SignalValue::Bool(self.pulse_active))?;
         
-        if let Some(et_output) = &self.elapsed_output {
-            bus.set(et_output, SignalValue::Int(self.elapsed_ms as i32))?;
+        if let Some(et_output) = self.elapsed_output {
+            bus.write(et_output, SignalValue::Int(self.elapsed_ms as i32))?;
         }
         
         Ok(())
@@ -93,4 +109,25 @@ impl Block for TP {
     fn block_type(&self) -> &str {
         "TP"
     }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(TpState {
+            timing: self.start_time.is_some(),
+            elapsed_ms: self.elapsed_ms,
+            prev_input: self.prev_input,
+            pulse_active: self.pulse_active,
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, clock: &dyn Clock) -> Result<()> {
+        let state: TpState = serde_json::from_value(state)
+            .map_err(|e| crate::PlcError::StateError(format!("TP '{}': {}", self.name, e)))?;
+        // A running timer continues from the elapsed time it was saved with
+        self.start_time = state.timing.then(|| clock.now());
+        self.carried_ms = if state.timing { state.elapsed_ms } else { 0 };
+        self.elapsed_ms = state.elapsed_ms;
+        self.prev_input = state.prev_input;
+        self.pulse_active = state.pulse_active;
+        Ok(())
+    }
 }
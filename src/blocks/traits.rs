@@ -1,11 +1,24 @@
-use crate::{Result, signal::SignalBus};
+use crate::{Result, signal::SignalBus, engine::Clock};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub trait Block: Send + Sync {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()>;
+    fn execute(&mut self, bus: &SignalBus, clock: &dyn Clock) -> Result<()>;
     fn name(&self) -> &str;
     fn block_type(&self) -> &str;
+    
+    /// Internal state, or `None` if the block has none. Saved for warm
+    /// restarts of `retain: true` blocks and in engine snapshots.
+    fn save_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+    
+    /// Put back state produced by `save_state`. `clock` is the clock the
+    /// block executes with from now on, so time-based state carries on from
+    /// the moment of the restore.
+    fn restore_state(&mut self, _state: serde_json::Value, _clock: &dyn Clock) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,4 +30,7 @@ pub struct BlockConfig {
     pub outputs: HashMap<String, String>,
     #[serde(default)]
     pub params: HashMap<String, serde_yaml::Value>,
+    /// Persist the block's internal state across restarts
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub retain: bool,
 }
@@ -1,25 +1,30 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Falling edge trigger - outputs true for one scan when input transitions from true to false
 pub struct FTrig {
     name: String,
-    input: String,
-    output: String,
+    input: SignalId,
+    output: SignalId,
+    prev_state: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FTrigState {
     prev_state: bool,
 }
 
 impl FTrig {
-    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let input = inputs.get("clk")
-            .ok_or_else(|| crate::PlcError::ConfigError("F_TRIG requires 'clk' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("q")
-            .ok_or_else(|| crate::PlcError::ConfigError("F_TRIG requires 'q' output".to_string()))?
-            .clone();
-            
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let input = bus.register(inputs.get("clk")
+            .ok_or_else(|| crate::PlcError::ConfigError("F_TRIG requires 'clk' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("q")
+            .ok_or_else(|| crate::PlcError::ConfigError("F_TRIG requires 'q' output".to_string()))?);
+        
         Ok(Self {
             name,
             input,
@@ -30,12 +35,12 @@ impl FTrig {
 }
 
 impl Block for FTrig {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let current = bus.get_bool(&self.input)?;
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let current = bus.read_bool(self.input)?;
         let falling_edge = !current && self.prev_state;
         self.prev_state = current;
         
-        bus.set(&self.output, SignalValue::Bool(falling_edge))?;
+        bus.write(self.output, SignalValue::Bool(falling_edge))?;
         Ok(())
     }
     
@@ -46,4 +51,17 @@ impl Block for FTrig {
     fn block_type(&self) -> &str {
         "F_TRIG"
     }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(FTrigState {
+            prev_state: self.prev_state,
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, _clock: &dyn Clock) -> Result<()> {
+        let state: FTrigState = serde_json::from_value(state)
+            .map_err(|e| crate::PlcError::StateError(format!("F_TRIG '{}': {}", self.name, e)))?;
+        self.prev_state = state.prev_state;
+        Ok(())
+    }
 }
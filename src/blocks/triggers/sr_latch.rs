@@ -1,29 +1,33 @@
-use crate::{Result, signal::{SignalBus, SignalValue}};
+use crate::{Result, signal::{SignalBus, SignalId, SignalValue}};
 use crate::blocks::traits::Block;
+use crate::engine::Clock;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub struct SRLatch {
     name: String,
-    set_input: String,
-    reset_input: String,
-    output: String,
+    set_input: SignalId,
+    reset_input: SignalId,
+    output: SignalId,
+    state: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SRLatchState {
     state: bool,
 }
 
 impl SRLatch {
-    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>) -> Result<Self> {
-        let set_input = inputs.get("set")
-            .ok_or_else(|| crate::PlcError::ConfigError("SR_LATCH requires 'set' input".to_string()))?
-            .clone();
-            
-        let reset_input = inputs.get("reset")
-            .ok_or_else(|| crate::PlcError::ConfigError("SR_LATCH requires 'reset' input".to_string()))?
-            .clone();
-            
-        let output = outputs.get("q")
-            .ok_or_else(|| crate::PlcError::ConfigError("SR_LATCH requires 'q' output".to_string()))?
-            .clone();
-            
+    pub fn new(name: String, inputs: &HashMap<String, String>, outputs: &HashMap<String, String>, bus: &SignalBus) -> Result<Self> {
+        let set_input = bus.register(inputs.get("set")
+            .ok_or_else(|| crate::PlcError::ConfigError("SR_LATCH requires 'set' input".to_string()))?);
+        
+        let reset_input = bus.register(inputs.get("reset")
+            .ok_or_else(|| crate::PlcError::ConfigError("SR_LATCH requires 'reset' input".to_string()))?);
+        
+        let output = bus.register(outputs.get("q")
+            .ok_or_else(|| crate::PlcError::ConfigError("SR_LATCH requires 'q' output".to_string()))?);
+        
         Ok(Self {
             name,
             set_input,
@@ -35,9 +39,9 @@ impl SRLatch {
 }
 
 impl Block for SRLatch {
-    fn execute(&mut self, bus: &SignalBus) -> Result<()> {
-        let set = bus.get_bool(&self.set_input)?;
-        let reset = bus.get_bool(&self.reset_input)?;
+    fn execute(&mut self, bus: &SignalBus, _clock: &dyn Clock) -> Result<()> {
+        let set = bus.read_bool(self.set_input)?;
+        let reset = bus.read_bool(self.reset_input)?;
         
         // Reset has priority
         if reset {
@@ -46,7 +50,7 @@ impl Block for SRLatch {
             self.state = true;
         }
         
-        bus.set(&self.output, SignalValue::Bool(self.state))?;
+        bus.write(self.output, SignalValue::Bool(self.state))?;
         Ok(())
     }
     
@@ -57,4 +61,17 @@ impl Block for SRLatch {
     fn block_type(&self) -> &str {
         "SR_LATCH"
     }
+    
+    fn save_state(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(SRLatchState {
+            state: self.state,
+        }).ok()
+    }
+    
+    fn restore_state(&mut self, state: serde_json::Value, _clock: &dyn Clock) -> Result<()> {
+        let state: SRLatchState = serde_json::from_value(state)
+            .map_err(|e| crate::PlcError::StateError(format!("SR_LATCH '{}': {}", self.name, e)))?;
+        self.state = state.state;
+        Ok(())
+    }
 }
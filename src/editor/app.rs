@@ -262,6 +262,12 @@ impl eframe::App for PlcEditorApp {
                             PlcNodeResponse::SetConstantValue(_value) => {
                                 self.modified = true;
                             }
+                            PlcNodeResponse::SetRounding(_rounding) => {
+                                self.modified = true;
+                            }
+                            PlcNodeResponse::SetScaleRange(..) => {
+                                self.modified = true;
+                            }
                         }
                     }
                     NodeResponse::ConnectEventEnded { .. } => {
@@ -53,6 +53,7 @@ impl YamlExporter {
                 name: signal_name,
                 signal_type: signal_type.to_string(),
                 initial: serde_yaml::Value::Null,
+                ..Default::default()
             });
         }
         
@@ -73,6 +74,7 @@ impl YamlExporter {
                             name: signal_name.clone(),
                             signal_type: signal_type.to_string(),
                             initial: serde_yaml::Value::Null,
+                            ..Default::default()
                         });
                     }
                 }
@@ -95,6 +97,7 @@ impl YamlExporter {
             signals,
             blocks,
             scan_time_ms: 100, // Default scan time
+            ..Default::default()
         }
     }
     
@@ -171,6 +174,24 @@ impl YamlExporter {
                 p.insert("kd".to_string(), serde_yaml::Value::from(*kd));
                 ("PID".to_string(), p)
             }
+            PlcNodeData::Convert { block_type }
+            | PlcNodeData::Bitwise { block_type }
+            | PlcNodeData::Select { block_type } => (block_type.clone(), HashMap::new()),
+            PlcNodeData::RealToInt { rounding } => {
+                let mut p = HashMap::new();
+                p.insert("rounding".to_string(), serde_yaml::Value::from(rounding.clone()));
+                ("REAL_TO_INT".to_string(), p)
+            }
+            PlcNodeData::Pack { bits: _ } => ("PACK".to_string(), HashMap::new()),
+            PlcNodeData::Unpack { bits: _ } => ("UNPACK".to_string(), HashMap::new()),
+            PlcNodeData::Scale { raw_min, raw_max, eu_min, eu_max } => {
+                let mut p = HashMap::new();
+                p.insert("raw_min".to_string(), serde_yaml::Value::from(*raw_min));
+                p.insert("raw_max".to_string(), serde_yaml::Value::from(*raw_max));
+                p.insert("eu_min".to_string(), serde_yaml::Value::from(*eu_min));
+                p.insert("eu_max".to_string(), serde_yaml::Value::from(*eu_max));
+                ("SCALE".to_string(), p)
+            }
             PlcNodeData::Input { signal_name: _, .. } => {
                 // Input nodes don't generate blocks, they just reference signals
                 return None;
@@ -202,6 +223,7 @@ impl YamlExporter {
             inputs,
             outputs,
             params,
+            retain: false,
         })
     }
 }
@@ -42,7 +42,7 @@ impl NodeDataTrait for PlcNodeData {
     fn bottom_ui(
         &self,
         ui: &mut egui::Ui,
-        _node_id: NodeId,
+        node_id: NodeId,
         _graph: &Graph<Self, Self::DataType, Self::ValueType>,
         _user_state: &mut Self::UserState,
     ) -> Vec<NodeResponse<Self::Response, Self>>
@@ -101,6 +101,40 @@ impl NodeDataTrait for PlcNodeData {
                     responses.push(NodeResponse::User(PlcNodeResponse::SetPIDParams(new_kp, new_ki, new_kd)));
                 }
             }
+            PlcNodeData::RealToInt { rounding } => {
+                ui.horizontal(|ui| {
+                    ui.label("Rounding:");
+                    let mut selected = rounding.clone();
+                    egui::ComboBox::from_id_source(("rounding", node_id))
+                        .selected_text(selected.clone())
+                        .show_ui(ui, |ui| {
+                            for mode in ["nearest", "even", "truncate", "floor", "ceil"] {
+                                ui.selectable_value(&mut selected, mode.to_string(), mode);
+                            }
+                        });
+                    if selected != *rounding {
+                        responses.push(NodeResponse::User(PlcNodeResponse::SetRounding(selected)));
+                    }
+                });
+            }
+            PlcNodeData::Scale { raw_min, raw_max, eu_min, eu_max } => {
+                let mut range = [*raw_min, *raw_max, *eu_min, *eu_max];
+                let mut changed = false;
+                
+                for (label, value) in ["Raw min:", "Raw max:", "EU min:", "EU max:"].into_iter().zip(range.iter_mut()) {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        if ui.add(egui::DragValue::new(value).speed(0.1)).changed() {
+                            changed = true;
+                        }
+                    });
+                }
+                
+                if changed {
+                    let [raw_min, raw_max, eu_min, eu_max] = range;
+                    responses.push(NodeResponse::User(PlcNodeResponse::SetScaleRange(raw_min, raw_max, eu_min, eu_max)));
+                }
+            }
             PlcNodeData::Constant { value } => {
                 match value {
                     PlcValueType::Bool(b) => {
@@ -137,6 +171,8 @@ pub enum PlcNodeResponse {
     SetCounterPreset(i32),
     SetPIDParams(f64, f64, f64),
     SetConstantValue(PlcValueType),
+    SetRounding(String),
+    SetScaleRange(f64, f64, f64, f64),
 }
 
 impl UserResponseTrait for PlcNodeResponse {}
@@ -63,6 +63,21 @@ pub enum PlcNodeData {
     // Control
     PID { kp: f64, ki: f64, kd: f64 },
     
+    // Conversion
+    Convert { block_type: String },
+    RealToInt { rounding: String },
+    
+    // Bits
+    Bitwise { block_type: String },
+    Pack { bits: usize },
+    Unpack { bits: usize },
+    
+    // Selection
+    Select { block_type: String },
+    
+    // Analog
+    Scale { raw_min: f64, raw_max: f64, eu_min: f64, eu_max: f64 },
+    
     // I/O
     Input { signal_name: String, data_type: PlcDataType },
     Output { signal_name: String },
@@ -86,6 +101,14 @@ impl PlcNodeData {
                 egui::Color32::from_rgb(150, 200, 200), // Math - cyan
             PlcNodeData::PID { .. } => 
                 egui::Color32::from_rgb(200, 180, 150), // Control - orange
+            PlcNodeData::Convert { .. } | PlcNodeData::RealToInt { .. } => 
+                egui::Color32::from_rgb(170, 170, 220), // Conversion - lavender
+            PlcNodeData::Bitwise { .. } | PlcNodeData::Pack { .. } | PlcNodeData::Unpack { .. } => 
+                egui::Color32::from_rgb(160, 190, 170), // Bits - sage
+            PlcNodeData::Select { .. } => 
+                egui::Color32::from_rgb(190, 170, 200), // Selection - mauve
+            PlcNodeData::Scale { .. } => 
+                egui::Color32::from_rgb(210, 190, 160), // Analog - tan
             PlcNodeData::Input { .. } => 
                 egui::Color32::from_rgb(150, 250, 150), // Input - bright green
             PlcNodeData::Output { .. } => 
@@ -13,6 +13,10 @@ pub enum PlcNodeTemplateCategory {
     Counters,
     Math,
     Control,
+    Conversion,
+    Bits,
+    Selection,
+    Analog,
     IO,
 }
 
@@ -26,6 +30,10 @@ impl PlcNodeTemplateCategory {
             PlcNodeTemplateCategory::Counters => "Counters",
             PlcNodeTemplateCategory::Math => "Math",
             PlcNodeTemplateCategory::Control => "Control",
+            PlcNodeTemplateCategory::Conversion => "Conversion",
+            PlcNodeTemplateCategory::Bits => "Bits",
+            PlcNodeTemplateCategory::Selection => "Selection",
+            PlcNodeTemplateCategory::Analog => "Analog",
             PlcNodeTemplateCategory::IO => "I/O",
         }
     }
@@ -40,9 +48,15 @@ pub struct PlcNodeTemplate {
     pub outputs: Vec<(&'static str, PlcDataType)>,
 }
 
+/// Ports of the 16-bit PACK and UNPACK templates
+const WORD_BITS: [&str; 16] = [
+    "bit0", "bit1", "bit2", "bit3", "bit4", "bit5", "bit6", "bit7",
+    "bit8", "bit9", "bit10", "bit11", "bit12", "bit13", "bit14", "bit15",
+];
+
 impl PlcNodeTemplate {
     pub fn all_templates() -> PlcNodeTemplates {
-        PlcNodeTemplates(vec![
+        let mut templates = vec![
             // Logic
             Self {
                 name: "AND".to_string(),
@@ -75,6 +89,20 @@ impl PlcNodeTemplate {
                 outputs: vec![("q", PlcDataType::Bool), ("et", PlcDataType::Int)],
             },
             
+            // Control
+            Self {
+                name: "PID".to_string(),
+                category: PlcNodeTemplateCategory::Control,
+                node_data: PlcNodeData::PID { kp: 1.0, ki: 0.1, kd: 0.0 },
+                inputs: vec![
+                    ("sp", PlcDataType::Float),
+                    ("pv", PlcDataType::Float),
+                    ("auto", PlcDataType::Bool),
+                    ("man", PlcDataType::Float),
+                ],
+                outputs: vec![("out", PlcDataType::Float)],
+            },
+            
             // I/O
             Self {
                 name: "Bool Input".to_string(),
@@ -94,8 +122,127 @@ impl PlcNodeTemplate {
                 outputs: vec![("value", PlcDataType::Bool)],
             },
             
+            // Conversion
+            Self {
+                name: "INT_TO_REAL".to_string(),
+                category: PlcNodeTemplateCategory::Conversion,
+                node_data: PlcNodeData::Convert { block_type: "INT_TO_REAL".to_string() },
+                inputs: vec![("in", PlcDataType::Int)],
+                outputs: vec![("out", PlcDataType::Float)],
+            },
+            Self {
+                name: "REAL_TO_INT".to_string(),
+                category: PlcNodeTemplateCategory::Conversion,
+                node_data: PlcNodeData::RealToInt { rounding: "nearest".to_string() },
+                inputs: vec![("in", PlcDataType::Float)],
+                outputs: vec![("out", PlcDataType::Int)],
+            },
+            Self {
+                name: "BOOL_TO_INT".to_string(),
+                category: PlcNodeTemplateCategory::Conversion,
+                node_data: PlcNodeData::Convert { block_type: "BOOL_TO_INT".to_string() },
+                inputs: vec![("in", PlcDataType::Bool)],
+                outputs: vec![("out", PlcDataType::Int)],
+            },
+            
+            // Bits
+            Self {
+                name: "PACK".to_string(),
+                category: PlcNodeTemplateCategory::Bits,
+                node_data: PlcNodeData::Pack { bits: WORD_BITS.len() },
+                inputs: WORD_BITS.iter().map(|&bit| (bit, PlcDataType::Bool)).collect(),
+                outputs: vec![("out", PlcDataType::Int)],
+            },
+            Self {
+                name: "UNPACK".to_string(),
+                category: PlcNodeTemplateCategory::Bits,
+                node_data: PlcNodeData::Unpack { bits: WORD_BITS.len() },
+                inputs: vec![("in", PlcDataType::Int)],
+                outputs: WORD_BITS.iter().map(|&bit| (bit, PlcDataType::Bool)).collect(),
+            },
+            
+            // Selection
+            Self {
+                name: "SEL".to_string(),
+                category: PlcNodeTemplateCategory::Selection,
+                node_data: PlcNodeData::Select { block_type: "SEL".to_string() },
+                inputs: vec![("g", PlcDataType::Bool), ("in0", PlcDataType::Float), ("in1", PlcDataType::Float)],
+                outputs: vec![("out", PlcDataType::Float)],
+            },
+            Self {
+                name: "MUX".to_string(),
+                category: PlcNodeTemplateCategory::Selection,
+                node_data: PlcNodeData::Select { block_type: "MUX".to_string() },
+                inputs: vec![
+                    ("k", PlcDataType::Int),
+                    ("in0", PlcDataType::Float),
+                    ("in1", PlcDataType::Float),
+                    ("in2", PlcDataType::Float),
+                    ("in3", PlcDataType::Float),
+                ],
+                outputs: vec![("out", PlcDataType::Float)],
+            },
+            Self {
+                name: "LIMIT".to_string(),
+                category: PlcNodeTemplateCategory::Selection,
+                node_data: PlcNodeData::Select { block_type: "LIMIT".to_string() },
+                inputs: vec![("mn", PlcDataType::Float), ("in", PlcDataType::Float), ("mx", PlcDataType::Float)],
+                outputs: vec![("out", PlcDataType::Float)],
+            },
+            
+            // Analog
+            Self {
+                name: "SCALE".to_string(),
+                category: PlcNodeTemplateCategory::Analog,
+                node_data: PlcNodeData::Scale { raw_min: 0.0, raw_max: 27648.0, eu_min: 0.0, eu_max: 100.0 },
+                inputs: vec![("in", PlcDataType::Int)],
+                outputs: vec![("out", PlcDataType::Float), ("out_of_range", PlcDataType::Bool)],
+            },
+            
             // Add more templates as needed...
-        ])
+        ];
+        
+        // Integer to integer conversions and bitwise operations only differ in their block type
+        for block_type in ["BCD_TO_INT", "INT_TO_BCD"] {
+            templates.push(Self {
+                name: block_type.to_string(),
+                category: PlcNodeTemplateCategory::Conversion,
+                node_data: PlcNodeData::Convert { block_type: block_type.to_string() },
+                inputs: vec![("in", PlcDataType::Int)],
+                outputs: vec![("out", PlcDataType::Int)],
+            });
+        }
+        for block_type in ["BAND", "BOR", "BXOR"] {
+            templates.push(Self {
+                name: block_type.to_string(),
+                category: PlcNodeTemplateCategory::Bits,
+                node_data: PlcNodeData::Bitwise { block_type: block_type.to_string() },
+                inputs: vec![("in1", PlcDataType::Int), ("in2", PlcDataType::Int)],
+                outputs: vec![("out", PlcDataType::Int)],
+            });
+        }
+        for block_type in ["SHL", "SHR", "ROL", "ROR"] {
+            templates.push(Self {
+                name: block_type.to_string(),
+                category: PlcNodeTemplateCategory::Bits,
+                node_data: PlcNodeData::Bitwise { block_type: block_type.to_string() },
+                inputs: vec![("in", PlcDataType::Int), ("n", PlcDataType::Int)],
+                outputs: vec![("out", PlcDataType::Int)],
+            });
+        }
+        
+        // MIN and MAX take any number of inputs; the palette starts them with two
+        for block_type in ["MIN", "MAX"] {
+            templates.push(Self {
+                name: block_type.to_string(),
+                category: PlcNodeTemplateCategory::Selection,
+                node_data: PlcNodeData::Select { block_type: block_type.to_string() },
+                inputs: vec![("in1", PlcDataType::Float), ("in2", PlcDataType::Float)],
+                outputs: vec![("out", PlcDataType::Float)],
+            });
+        }
+        
+        PlcNodeTemplates(templates)
     }
 }
 
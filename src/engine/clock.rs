@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time source for the scan engine and all time-dependent blocks.
+///
+/// `now()` returns monotonic time elapsed since the clock's own epoch, so
+/// blocks only ever compare two readings from the same clock.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Duration;
+
+    /// The clock itself if it is simulated, so the engine can advance it
+    fn as_simulated(&self) -> Option<&SimulatedClock> {
+        None
+    }
+}
+
+/// Wall-clock time source backed by `std::time::Instant`
+pub struct RealTimeClock {
+    epoch: Instant,
+}
+
+impl RealTimeClock {
+    pub fn new() -> Self {
+        Self { epoch: Instant::now() }
+    }
+}
+
+impl Default for RealTimeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for RealTimeClock {
+    fn now(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+}
+
+/// Manually advanced time source for deterministic simulation and tests.
+///
+/// Clones share the same underlying time, so a test can keep a handle and
+/// advance the clock that was handed to the engine.
+#[derive(Clone, Default)]
+pub struct SimulatedClock {
+    now_ns: Arc<AtomicU64>,
+}
+
+impl SimulatedClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now_ns.fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.now_ns.store(now.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.now_ns.load(Ordering::SeqCst))
+    }
+
+    fn as_simulated(&self) -> Option<&SimulatedClock> {
+        Some(self)
+    }
+}
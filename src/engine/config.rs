@@ -1,70 +1,185 @@
 use serde::{Deserialize, Serialize};
-use crate::{Result, PlcError, signal::SignalValue};
+use crate::{Result, PlcError, signal::{SignalType, SignalValue}};
+use crate::api::ApiConfig;
+use crate::modbus::{ModbusDeviceConfig, ModbusServerConfig};
+use super::faults::FaultPolicy;
+use super::retain::RetainConfig;
+use super::structured::{ArrayBounds, StructType};
+use super::validation::SourceMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SignalConfig {
     pub name: String,
+    /// One of the `SignalType` names, such as `bool`, `uint`, `lint`,
+    /// `real`, `time` or `date_and_time`, or a struct type from `types`
     #[serde(rename = "type")]
     pub signal_type: String,
+    /// Makes the signal an array of `signal_type`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array: Option<ArrayBounds>,
     #[serde(default)]
     pub initial: serde_yaml::Value,
+    /// Value the signal is driven to when a fault calls for safe states
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safe_value: Option<serde_yaml::Value>,
+    /// Keep the value across restarts instead of starting from `initial`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub retain: bool,
 }
 
 impl SignalConfig {
+    /// The elementary signals this one stands for: itself, or the elements
+    /// of an array or struct
+    pub fn elements(&self, types: &[StructType]) -> Result<Vec<SignalConfig>> {
+        super::structured::expand(self, types)
+    }
+    
     pub fn to_signal_value(&self) -> Result<SignalValue> {
-        match self.signal_type.as_str() {
-            "bool" => {
-                let val = self.initial.as_bool().unwrap_or(false);
-                Ok(SignalValue::Bool(val))
-            }
-            "int" => {
-                let val = self.initial.as_i64().unwrap_or(0) as i32;
-                Ok(SignalValue::Int(val))
-            }
-            "float" => {
-                let val = self.initial.as_f64().unwrap_or(0.0);
-                Ok(SignalValue::Float(val))
-            }
-            "string" => {
-                let val = self.initial.as_str().unwrap_or("").to_string();
-                Ok(SignalValue::String(val))
-            }
-            _ => Err(PlcError::ConfigError(format!(
-                "Unknown signal type: {}",
-                self.signal_type
-            ))),
-        }
+        self.convert(&self.initial)
+    }
+    
+    pub fn to_safe_value(&self) -> Result<Option<SignalValue>> {
+        self.safe_value.as_ref().map(|value| self.convert(value)).transpose()
+    }
+    
+    /// Declared type of the signal
+    pub fn data_type(&self) -> Result<SignalType> {
+        SignalType::parse(&self.signal_type).ok_or_else(|| PlcError::ConfigError(format!(
+            "Unknown signal type: {}",
+            self.signal_type
+        )))
+    }
+    
+    fn convert(&self, value: &serde_yaml::Value) -> Result<SignalValue> {
+        SignalValue::from_yaml(value, self.data_type()?)
     }
 }
 
+/// A cyclic task running its programs every `interval_ms`.
+///
+/// Tasks due at the same time run in priority order, lowest number first.
+/// They share one thread and do not preempt each other.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskConfig {
+    pub name: String,
+    pub interval_ms: u64,
+    #[serde(default)]
+    pub priority: u32,
+    /// Programs in the order they execute
+    #[serde(default)]
+    pub programs: Vec<String>,
+}
+
+/// A named set of blocks, executed in data-flow order by the task it is
+/// assigned to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramConfig {
+    pub name: String,
+    #[serde(default)]
+    pub blocks: Vec<String>,
+    /// Reaction to a block of this program failing
+    #[serde(default)]
+    pub on_fault: FaultPolicy,
+}
+
+/// Scan watchdog. When it trips, every signal with a `safe_value` is driven
+/// to it and the engine stops.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    /// Longest a task may take to execute, which catches a hung block
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Trip when a task overruns this many times in a row
+    #[serde(default)]
+    pub max_overruns: Option<u32>,
+}
+
+fn default_scan_time_ms() -> u64 {
+    100
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlcConfig {
+    /// Struct types for signals
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<StructType>,
+    /// Check types strictly: block ports only take signals of their exact
+    /// type, and values are never converted implicitly, only by conversion
+    /// blocks such as INT_TO_REAL
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_types: bool,
     #[serde(default)]
     pub signals: Vec<SignalConfig>,
     #[serde(default)]
     pub blocks: Vec<crate::blocks::BlockConfig>,
-    #[serde(default)]
+    /// Interval of the `main` task, which runs blocks not assigned to a program
+    #[serde(default = "default_scan_time_ms")]
     pub scan_time_ms: u64,
+    #[serde(default)]
+    pub tasks: Vec<TaskConfig>,
+    #[serde(default)]
+    pub programs: Vec<ProgramConfig>,
+    /// Reaction to a block of the `main` program failing
+    #[serde(default)]
+    pub on_fault: FaultPolicy,
+    #[serde(default)]
+    pub watchdog: Option<WatchdogConfig>,
+    /// File holding retentive signals and block states
+    #[serde(default)]
+    pub retain: Option<RetainConfig>,
+    #[serde(default)]
+    pub modbus_server: Option<ModbusServerConfig>,
+    #[serde(default)]
+    pub modbus_devices: Vec<ModbusDeviceConfig>,
+    /// HTTP/JSON API
+    #[serde(default)]
+    pub api: Option<ApiConfig>,
+    /// Line numbers for diagnostics, filled in by `from_yaml`
+    #[serde(skip)]
+    pub source_map: SourceMap,
 }
 
 impl Default for PlcConfig {
     fn default() -> Self {
         Self {
+            types: Vec::new(),
+            strict_types: false,
             signals: Vec::new(),
             blocks: Vec::new(),
-            scan_time_ms: 100, // Default 100ms scan time
+            scan_time_ms: default_scan_time_ms(),
+            tasks: Vec::new(),
+            programs: Vec::new(),
+            on_fault: FaultPolicy::default(),
+            watchdog: None,
+            retain: None,
+            modbus_server: None,
+            modbus_devices: Vec::new(),
+            api: None,
+            source_map: SourceMap::default(),
         }
     }
 }
 
 impl PlcConfig {
     pub fn from_yaml(yaml_str: &str) -> Result<Self> {
-        serde_yaml::from_str(yaml_str)
-            .map_err(|e| PlcError::YamlError(e))
+        let mut config: Self = serde_yaml::from_str(yaml_str)
+            .map_err(PlcError::YamlError)?;
+        config.source_map = SourceMap::from_yaml(yaml_str);
+        Ok(config)
     }
     
     pub fn from_file(path: &str) -> Result<Self> {
         let contents = std::fs::read_to_string(path)?;
         Self::from_yaml(&contents)
     }
+    
+    /// Every signal with arrays and structs split into their elements, in
+    /// declaration order
+    pub fn elementary_signals(&self) -> Result<Vec<SignalConfig>> {
+        let mut signals = Vec::with_capacity(self.signals.len());
+        for signal in &self.signals {
+            signals.extend(signal.elements(&self.types)?);
+        }
+        Ok(signals)
+    }
 }
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use crate::signal::{Quality, SignalBus, SignalId, SignalValue};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::error;
+
+/// What the engine does when a block of a program fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultPolicy {
+    /// Record the fault and go on with the next block
+    #[default]
+    Continue,
+    /// Record the fault and stop executing the failed block until faults are cleared
+    SkipBlock,
+    /// Drive every signal that has a safe value to it and stop the engine
+    StopEngine,
+    /// Drive the program's outputs that have safe values to them and stop
+    /// executing the program until faults are cleared
+    SafeState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultKind {
+    BlockError,
+    /// A task execution took longer than the watchdog timeout
+    WatchdogTimeout,
+    /// A task overran more times in a row than the watchdog allows
+    RepeatedOverruns,
+}
+
+/// One entry of the diagnostic buffer
+#[derive(Debug, Clone, Serialize)]
+pub struct FaultRecord {
+    /// Increases by one for every fault recorded, so readers can ask for
+    /// what is new since they last looked
+    pub sequence: u64,
+    pub time: SystemTime,
+    pub kind: FaultKind,
+    pub task: String,
+    pub program: Option<String>,
+    pub block: Option<String>,
+    pub message: String,
+    pub reaction: FaultPolicy,
+}
+
+impl FaultRecord {
+    pub fn new(kind: FaultKind, task: &str, message: String, reaction: FaultPolicy) -> Self {
+        Self {
+            sequence: 0,
+            time: SystemTime::now(),
+            kind,
+            task: task.to_string(),
+            program: None,
+            block: None,
+            message,
+            reaction,
+        }
+    }
+    
+    pub fn at(mut self, program: &str, block: &str) -> Self {
+        self.program = Some(program.to_string());
+        self.block = Some(block.to_string());
+        self
+    }
+}
+
+/// Error status of one block
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BlockStatus {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub block_type: String,
+    pub task: String,
+    pub program: String,
+    /// Whether the block's last execution failed. A block that fails keeps
+    /// its outputs at their last good values.
+    pub failed: bool,
+    /// Taken out of the scan by the `skip_block` policy
+    pub skipped: bool,
+    pub error_count: u64,
+    pub last_error: Option<String>,
+}
+
+struct Records {
+    capacity: usize,
+    next_sequence: u64,
+    records: VecDeque<FaultRecord>,
+}
+
+/// Bounded history of faults, oldest first. Clones share the same buffer,
+/// so it can be read while the engine runs.
+#[derive(Clone)]
+pub struct DiagnosticBuffer {
+    records: Arc<Mutex<Records>>,
+}
+
+impl DiagnosticBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::new(Mutex::new(Records {
+                capacity: capacity.max(1),
+                next_sequence: 1,
+                records: VecDeque::new(),
+            })),
+        }
+    }
+    
+    // Records are plain data, so a panic elsewhere cannot leave them inconsistent
+    fn lock(&self) -> MutexGuard<'_, Records> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+    
+    /// Add a fault, dropping the oldest one if the buffer is full. Returns
+    /// the sequence number assigned to it.
+    pub fn push(&self, mut record: FaultRecord) -> u64 {
+        error!("{:?} in task '{}'{}: {} ({:?})",
+            record.kind, record.task,
+            record.block.as_ref().map(|block| format!(", block '{}'", block)).unwrap_or_default(),
+            record.message, record.reaction);
+        
+        let mut records = self.lock();
+        record.sequence = records.next_sequence;
+        records.next_sequence += 1;
+        if records.records.len() == records.capacity {
+            records.records.pop_front();
+        }
+        records.records.push_back(record);
+        records.next_sequence - 1
+    }
+    
+    pub fn entries(&self) -> Vec<FaultRecord> {
+        self.lock().records.iter().cloned().collect()
+    }
+    
+    /// Faults recorded after the one numbered `sequence`
+    pub fn since(&self, sequence: u64) -> Vec<FaultRecord> {
+        self.lock().records.iter()
+            .filter(|record| record.sequence > sequence)
+            .cloned()
+            .collect()
+    }
+    
+    pub fn clear(&self) {
+        self.lock().records.clear();
+    }
+}
+
+/// Where a block sits in the task layout, for fault records
+#[derive(Clone)]
+pub(crate) struct BlockLocation {
+    pub task: String,
+    pub program: String,
+    pub block: String,
+}
+
+/// Progress of the scan as seen by the watchdog thread
+pub(crate) struct Heartbeat {
+    epoch: Instant,
+    /// Microseconds since `epoch` plus one at which the running task
+    /// started, or zero between tasks
+    busy_since: AtomicU64,
+    /// Index of the block executing
+    block: AtomicUsize,
+    /// Set by the watchdog thread when it has recorded a timeout
+    tripped: AtomicBool,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            busy_since: AtomicU64::new(0),
+            block: AtomicUsize::new(0),
+            tripped: AtomicBool::new(false),
+        }
+    }
+    
+    pub fn task_started(&self) {
+        self.busy_since.store(self.epoch.elapsed().as_micros() as u64 + 1, Ordering::Relaxed);
+    }
+    
+    pub fn task_finished(&self) {
+        self.busy_since.store(0, Ordering::Relaxed);
+    }
+    
+    pub fn block_started(&self, block: usize) {
+        self.block.store(block, Ordering::Relaxed);
+    }
+    
+    /// Whether the watchdog thread has recorded a timeout since the last reset
+    pub fn tripped(&self) -> bool {
+        self.tripped.load(Ordering::SeqCst)
+    }
+    
+    pub fn reset(&self) {
+        self.tripped.store(false, Ordering::SeqCst);
+    }
+    
+    fn busy_for(&self) -> Option<Duration> {
+        match self.busy_since.load(Ordering::Relaxed) {
+            0 => None,
+            since => Some(self.epoch.elapsed().saturating_sub(Duration::from_micros(since - 1))),
+        }
+    }
+}
+
+/// Everything the watchdog thread needs to react to a hung block on its own
+pub(crate) struct Watchdog {
+    pub heartbeat: Arc<Heartbeat>,
+    pub timeout: Duration,
+    pub locations: Vec<BlockLocation>,
+    pub safe_states: Vec<(SignalId, SignalValue)>,
+    /// Handle onto the engine's process image
+    pub image: SignalBus,
+    pub faults: DiagnosticBuffer,
+}
+
+impl Watchdog {
+    /// Watch the heartbeat from a separate thread until the returned guard
+    /// is dropped.
+    ///
+    /// A block that never returns cannot be interrupted, so on a timeout the
+    /// thread records the fault and publishes the safe states itself. The
+    /// engine stops once the block returns, if it ever does.
+    pub fn spawn(self) -> WatchdogGuard {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let guard = WatchdogGuard(shutdown.clone());
+        let period = (self.timeout / 4).max(Duration::from_millis(1));
+        
+        std::thread::spawn(move || {
+            let heartbeat = &self.heartbeat;
+            while !shutdown.load(Ordering::SeqCst) {
+                std::thread::sleep(period);
+                
+                let Some(busy) = heartbeat.busy_for() else { continue };
+                // A block index from after a configuration change, before this thread is replaced
+                let Some(location) = self.locations.get(heartbeat.block.load(Ordering::Relaxed)) else { continue };
+                if busy <= self.timeout || heartbeat.tripped.swap(true, Ordering::SeqCst) {
+                    continue;
+                }
+                
+                self.faults.push(FaultRecord::new(
+                    FaultKind::WatchdogTimeout,
+                    &location.task,
+                    format!("block has not returned after {:?}", busy),
+                    FaultPolicy::StopEngine,
+                ).at(&location.program, &location.block));
+                
+                // The hung block is still between begin_block and end_block
+                for (signal, value) in &self.safe_states {
+                    self.image.write_with_quality(*signal, value.clone(), Quality::Good).ok();
+                }
+                self.image.publish();
+            }
+        });
+        
+        guard
+    }
+}
+
+/// Stops the watchdog thread when dropped
+pub(crate) struct WatchdogGuard(Arc<AtomicBool>);
+
+impl Drop for WatchdogGuard {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
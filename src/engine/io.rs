@@ -0,0 +1,22 @@
+use crate::{Result, signal::SignalBus};
+
+/// Exchanges signals with field devices around each scan.
+///
+/// Drivers talk to their devices in the background and keep the latest data
+/// in an image of their own, so the scan only copies values and a slow or
+/// dead device cannot stretch it.
+pub trait IoDriver: Send {
+    fn name(&self) -> &str;
+    
+    /// Start background communication. Called from within the Tokio runtime
+    /// before the first scan; calling it again has no effect.
+    fn start(&mut self);
+    
+    /// Copy the latest values read from the devices onto the bus. Runs
+    /// before logic, which runs even if this fails, so a point that cannot
+    /// be latched should be flagged rather than fail the whole call.
+    fn latch_inputs(&mut self, bus: &SignalBus) -> Result<()>;
+    
+    /// Take the values to send to the devices from the bus. Runs after logic.
+    fn write_outputs(&mut self, bus: &SignalBus) -> Result<()>;
+}
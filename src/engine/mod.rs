@@ -1,5 +1,27 @@
 mod config;
 mod scan;
+mod clock;
+mod ordering;
+mod validation;
+mod io;
+mod faults;
+mod tasks;
+mod retain;
+mod snapshot;
+mod reload;
+mod status;
+mod structured;
 
-pub use config::{PlcConfig, SignalConfig};
-pub use scan::ScanEngine;
+pub use config::{PlcConfig, SignalConfig, TaskConfig, ProgramConfig, WatchdogConfig};
+pub use scan::{ScanEngine, ReloadHandle, StopHandle};
+pub use clock::{Clock, RealTimeClock, SimulatedClock};
+pub use ordering::{execution_order, UNIT_DELAY};
+pub use validation::{Diagnostic, SourceMap};
+pub use io::IoDriver;
+pub use tasks::{plan_tasks, TaskPlan, ProgramPlan, TaskStats, MAIN_TASK};
+pub use faults::{BlockStatus, DiagnosticBuffer, FaultKind, FaultPolicy, FaultRecord};
+pub use retain::{RetainConfig, RetainStore};
+pub use snapshot::Snapshot;
+pub use reload::ChangeReport;
+pub use status::{EngineHandle, EngineStatus};
+pub use structured::{ArrayBounds, FieldConfig, StructType, MAX_ELEMENTS};
@@ -0,0 +1,125 @@
+use crate::{Result, PlcError, blocks::BlockConfig};
+use std::collections::{BTreeSet, HashMap};
+use tracing::warn;
+
+/// Block type whose output is last scan's input, which is the only way to
+/// close a feedback loop between blocks
+pub const UNIT_DELAY: &str = "UNIT_DELAY";
+
+/// Compute the order in which blocks must execute so that every block runs
+/// after the blocks producing its inputs. Returns indices into `blocks`.
+///
+/// Ties are broken by position in the configuration, so an already correctly
+/// ordered program keeps its order. A block reading its own output sees its
+/// previous scan's value and does not count as a loop. `UNIT_DELAY` blocks run
+/// first and their inputs are not dependencies, which breaks any loop through them.
+///
+/// Blocks used to run in configuration order, so a block listed before the
+/// producer of one of its inputs read the value of the previous scan. It now
+/// reads the value of the current scan. Every such pair is logged as a warning;
+/// a `UNIT_DELAY` between them keeps the old one-scan lag.
+pub fn execution_order(blocks: &[BlockConfig]) -> Result<Vec<usize>> {
+    let mut producers: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, block) in blocks.iter().enumerate() {
+        for signal in block.outputs.values() {
+            producers.entry(signal.as_str()).or_default().push(index);
+        }
+    }
+    
+    let mut dependencies: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); blocks.len()];
+    let mut dependents: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); blocks.len()];
+    
+    for (index, block) in blocks.iter().enumerate() {
+        if block.block_type == UNIT_DELAY {
+            continue;
+        }
+        
+        for signal in block.inputs.values() {
+            if let Some(sources) = producers.get(signal.as_str()) {
+                dependencies[index].extend(sources.iter().copied().filter(|&source| source != index));
+            }
+        }
+        
+        for &dependency in &dependencies[index] {
+            dependents[dependency].insert(index);
+        }
+    }
+    
+    let mut in_degree: Vec<usize> = dependencies.iter().map(BTreeSet::len).collect();
+    
+    let mut order = Vec::with_capacity(blocks.len());
+    let mut ready: BTreeSet<usize> = BTreeSet::new();
+    
+    for (index, block) in blocks.iter().enumerate() {
+        if block.block_type == UNIT_DELAY {
+            order.push(index);
+        } else if in_degree[index] == 0 {
+            ready.insert(index);
+        }
+    }
+    
+    for &delay in &order {
+        for &dependent in &dependents[delay] {
+            in_degree[dependent] -= 1;
+            if in_degree[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+    
+    while let Some(index) = ready.pop_first() {
+        order.push(index);
+        for &dependent in &dependents[index] {
+            in_degree[dependent] -= 1;
+            if in_degree[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+    
+    if order.len() < blocks.len() {
+        let cycle = find_cycle(&dependencies, &in_degree)
+            .into_iter()
+            .map(|index| blocks[index].name.clone())
+            .collect();
+        return Err(PlcError::AlgebraicLoop(cycle));
+    }
+    
+    for (producer, consumers) in dependents.iter().enumerate() {
+        for &consumer in consumers.iter().filter(|&&consumer| consumer < producer) {
+            warn!("Block '{}' now runs after '{}', which is listed after it, and reads its output from the current scan",
+                blocks[consumer].name, blocks[producer].name);
+        }
+    }
+    
+    Ok(order)
+}
+
+/// Walk backwards from an unscheduled block through its unscheduled
+/// dependencies until one repeats, then report the loop in data-flow order
+fn find_cycle(dependencies: &[BTreeSet<usize>], in_degree: &[usize]) -> Vec<usize> {
+    let unscheduled = |index: usize| in_degree[index] > 0;
+    
+    let Some(start) = (0..in_degree.len()).find(|&index| unscheduled(index)) else {
+        return Vec::new();
+    };
+    
+    let mut path = vec![start];
+    let mut current = start;
+    
+    // An unscheduled block always has at least one unscheduled dependency,
+    // so the walk cannot dead-end before it revisits a block
+    while let Some(&next) = dependencies[current].iter().find(|&&next| unscheduled(next)) {
+        if let Some(position) = path.iter().position(|&index| index == next) {
+            let mut cycle = path.split_off(position);
+            cycle.push(next);
+            cycle.reverse();
+            return cycle;
+        }
+        
+        path.push(next);
+        current = next;
+    }
+    
+    path
+}
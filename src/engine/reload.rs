@@ -0,0 +1,126 @@
+use serde::Serialize;
+use crate::engine::config::PlcConfig;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// What loading a new configuration into a running engine changes
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChangeReport {
+    pub added_signals: Vec<String>,
+    pub removed_signals: Vec<String>,
+    /// Signals whose type changed, which restart from their initial value
+    pub retyped_signals: Vec<String>,
+    pub added_blocks: Vec<String>,
+    pub removed_blocks: Vec<String>,
+    /// Blocks whose type changed, which start from a fresh state
+    pub replaced_blocks: Vec<String>,
+    /// Blocks with the same type but changed ports or parameters. They keep
+    /// their state, like unchanged blocks.
+    pub modified_blocks: Vec<String>,
+    /// Whether tasks, programs, the scan time or fault handling changed
+    pub scheduling_changed: bool,
+    /// Sections that differ but can only be changed by a restart. A change
+    /// touching any of them is rejected.
+    pub restart_required: Vec<String>,
+}
+
+/// Whether two configuration items are the same, by their serialized form
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_yaml::to_value(a).ok() == serde_yaml::to_value(b).ok()
+}
+
+impl ChangeReport {
+    /// Compare the running configuration with a new one
+    pub fn diff(old: &PlcConfig, new: &PlcConfig) -> Self {
+        let mut report = ChangeReport::default();
+        
+        // Arrays and structs are compared element by element
+        let (old_elements, new_elements) = (
+            old.elementary_signals().unwrap_or_default(),
+            new.elementary_signals().unwrap_or_default(),
+        );
+        let old_signals: HashMap<&str, &str> = old_elements.iter()
+            .map(|signal| (signal.name.as_str(), signal.signal_type.as_str()))
+            .collect();
+        for signal in &new_elements {
+            match old_signals.get(signal.name.as_str()) {
+                None => report.added_signals.push(signal.name.clone()),
+                Some(&old_type) if old_type != signal.signal_type => report.retyped_signals.push(signal.name.clone()),
+                Some(_) => {}
+            }
+        }
+        let new_names: HashSet<&str> = new_elements.iter().map(|signal| signal.name.as_str()).collect();
+        report.removed_signals = old_elements.iter()
+            .filter(|signal| !new_names.contains(signal.name.as_str()))
+            .map(|signal| signal.name.clone())
+            .collect();
+        
+        let old_blocks: HashMap<&str, &crate::blocks::BlockConfig> = old.blocks.iter()
+            .map(|block| (block.name.as_str(), block))
+            .collect();
+        for block in &new.blocks {
+            match old_blocks.get(block.name.as_str()) {
+                None => report.added_blocks.push(block.name.clone()),
+                Some(old_block) if old_block.block_type != block.block_type => report.replaced_blocks.push(block.name.clone()),
+                Some(old_block) if !same(*old_block, block) => report.modified_blocks.push(block.name.clone()),
+                Some(_) => {}
+            }
+        }
+        report.removed_blocks = old.blocks.iter()
+            .filter(|block| !new.blocks.iter().any(|other| other.name == block.name))
+            .map(|block| block.name.clone())
+            .collect();
+        
+        report.scheduling_changed = old.scan_time_ms != new.scan_time_ms
+            || old.on_fault != new.on_fault
+            || !same(&old.tasks, &new.tasks)
+            || !same(&old.programs, &new.programs)
+            || !same(&old.watchdog, &new.watchdog);
+        
+        if !same(&old.modbus_server, &new.modbus_server) {
+            report.restart_required.push("modbus_server".to_string());
+        }
+        if !same(&old.modbus_devices, &new.modbus_devices) {
+            report.restart_required.push("modbus_devices".to_string());
+        }
+        if !same(&old.api, &new.api) {
+            report.restart_required.push("api".to_string());
+        }
+        if !same(&old.retain, &new.retain) {
+            report.restart_required.push("retain".to_string());
+        }
+        
+        report
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        *self == ChangeReport::default()
+    }
+}
+
+impl fmt::Display for ChangeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        
+        let sections = [
+            ("added signals", &self.added_signals),
+            ("removed signals", &self.removed_signals),
+            ("retyped signals", &self.retyped_signals),
+            ("added blocks", &self.added_blocks),
+            ("removed blocks", &self.removed_blocks),
+            ("replaced blocks", &self.replaced_blocks),
+            ("modified blocks", &self.modified_blocks),
+            ("restart required for", &self.restart_required),
+        ];
+        let mut parts: Vec<String> = sections.iter()
+            .filter(|(_, names)| !names.is_empty())
+            .map(|(label, names)| format!("{}: {}", label, names.join(", ")))
+            .collect();
+        if self.scheduling_changed {
+            parts.push("scheduling changed".to_string());
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use crate::{Result, PlcError};
+use crate::engine::snapshot::Snapshot;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// First word of a retain file, followed by the CRC-32 of the JSON body
+const HEADER: &str = "RETAIN";
+
+fn default_interval_ms() -> u64 {
+    10_000
+}
+
+/// Where and how often retentive signals and block states are saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetainConfig {
+    pub path: String,
+    /// Saving also happens when the engine stops
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+}
+
+/// A retain file holding a snapshot of the retentive signals and blocks,
+/// replaced atomically on every save.
+///
+/// The file is a header line holding a checksum of the JSON body that
+/// follows, so a torn or corrupted file is detected on load instead of
+/// restoring garbage.
+pub struct RetainStore {
+    path: PathBuf,
+}
+
+impl RetainStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    /// Read the file, or `None` if it does not exist yet
+    pub fn load(&self) -> Result<Option<Snapshot>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        
+        let invalid = |message: &str| PlcError::StateError(format!("{}: {}", self.path.display(), message));
+        let (header, body) = contents.split_once('\n').ok_or_else(|| invalid("missing header"))?;
+        let checksum = header.strip_prefix(HEADER)
+            .and_then(|checksum| u32::from_str_radix(checksum.trim(), 16).ok())
+            .ok_or_else(|| invalid("malformed header"))?;
+        if crc32fast::hash(body.as_bytes()) != checksum {
+            return Err(invalid("checksum mismatch"));
+        }
+        
+        serde_json::from_str(body).map(Some).map_err(|e| invalid(&e.to_string()))
+    }
+    
+    /// Write to a temporary file next to the target, flush it to disk and
+    /// rename it over the target, so a power cut leaves either the old or
+    /// the new file
+    pub fn save(&self, snapshot: &Snapshot) -> Result<()> {
+        let body = serde_json::to_string_pretty(snapshot)
+            .map_err(|e| PlcError::StateError(e.to_string()))?;
+        
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+        
+        let mut file = fs::File::create(&temporary)?;
+        writeln!(file, "{} {:08x}", HEADER, crc32fast::hash(body.as_bytes()))?;
+        file.write_all(body.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temporary, &self.path)?;
+        
+        // Persist the rename itself; not every platform can open a directory
+        if let Some(directory) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            if let Ok(directory) = fs::File::open(directory) {
+                directory.sync_all().ok();
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,47 +1,543 @@
-use crate::{Result, signal::SignalBus, blocks};
+use crate::{Result, PlcError, signal::SignalBus, blocks};
+use crate::blocks::ports::ENO_PORT;
 use crate::engine::config::PlcConfig;
-use crate::signal::SignalValue;
-use tokio::time::{interval, Duration};
-use tracing::{info, error, debug};
+use crate::engine::clock::{Clock, RealTimeClock};
+use crate::engine::faults::{BlockLocation, BlockStatus, DiagnosticBuffer, FaultKind, FaultPolicy, FaultRecord, Heartbeat, Watchdog, WatchdogGuard};
+use crate::engine::io::IoDriver;
+use crate::engine::reload::ChangeReport;
+use crate::engine::retain::RetainStore;
+use crate::engine::snapshot::Snapshot;
+use crate::engine::status::EngineHandle;
+use crate::engine::tasks::{plan_tasks, TaskStats};
+use crate::modbus::ModbusClient;
+use crate::signal::{SignalId, SignalValue};
+use tokio::time::Duration;
+use tracing::{info, warn, error, debug};
+use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot, RwLock};
+
+/// Number of faults kept in the diagnostic buffer
+const DIAGNOSTIC_BUFFER_SIZE: usize = 256;
+
+struct Program {
+    name: String,
+    /// Range of `ScanEngine::blocks`
+    blocks: Range<usize>,
+    on_fault: FaultPolicy,
+    /// Outputs of the program's blocks that have a safe value
+    safe_outputs: Vec<(SignalId, SignalValue)>,
+    /// Held in its safe state by the `safe_state` policy
+    halted: bool,
+}
+
+struct Task {
+    programs: Vec<Program>,
+    /// Engine clock time the task is next due; `None` until the first scan
+    next_release: Option<Duration>,
+    consecutive_overruns: u32,
+    stats: TaskStats,
+}
+
+impl Task {
+    fn is_due(&self, now: Duration) -> bool {
+        self.next_release.is_none_or(|release| release <= now)
+    }
+}
+
+/// Retentive signals and blocks and the file they are saved to
+struct Retain {
+    store: RetainStore,
+    interval: Duration,
+    /// Engine clock time of the last save
+    last_save: Duration,
+    signals: Vec<(String, SignalId)>,
+    /// Indices into `ScanEngine::blocks`
+    blocks: Vec<usize>,
+}
+
+/// Blocks and tasks built from a configuration, on a bus that already holds
+/// its signals
+struct Logic {
+    blocks: Vec<Box<dyn blocks::BlockTrait>>,
+    tasks: Vec<Task>,
+    locations: Vec<BlockLocation>,
+    status: Vec<BlockStatus>,
+    eno: Vec<Option<SignalId>>,
+    safe_states: Vec<(SignalId, SignalValue)>,
+    retained_signals: Vec<(String, SignalId)>,
+    /// Indices into `blocks`
+    retained_blocks: Vec<usize>,
+}
+
+impl Logic {
+    fn build(config: &PlcConfig, signal_bus: &SignalBus) -> Result<Self> {
+        let signals = config.elementary_signals()?;
+        let mut safe_values = HashMap::new();
+        for signal_config in &signals {
+            if let Some(value) = signal_config.to_safe_value()? {
+                safe_values.insert(signal_config.name.as_str(), (signal_bus.register(&signal_config.name), value));
+            }
+        }
+        
+        // Create blocks task by task, each program in data-flow order
+        let mut blocks = Vec::new();
+        let mut locations = Vec::new();
+        let mut status = Vec::new();
+        let mut eno = Vec::new();
+        let mut retained_blocks = Vec::new();
+        let mut tasks = Vec::new();
+        for plan in plan_tasks(config)? {
+            let mut programs = Vec::new();
+            
+            for program in &plan.programs {
+                let program_start = blocks.len();
+                let mut safe_outputs = Vec::new();
+                
+                for block_config in program.blocks.iter().map(|&index| &config.blocks[index]) {
+                    let block = blocks::create_block(block_config, signal_bus)?;
+                    info!("Created block '{}' of type '{}'", 
+                        block_config.name, block_config.block_type);
+                    if block_config.retain {
+                        retained_blocks.push(blocks.len());
+                    }
+                    blocks.push(block);
+                    locations.push(BlockLocation {
+                        task: plan.name.clone(),
+                        program: program.name.clone(),
+                        block: block_config.name.clone(),
+                    });
+                    status.push(BlockStatus {
+                        name: block_config.name.clone(),
+                        block_type: block_config.block_type.clone(),
+                        task: plan.name.clone(),
+                        program: program.name.clone(),
+                        failed: false,
+                        skipped: false,
+                        error_count: 0,
+                        last_error: None,
+                    });
+                    eno.push(block_config.outputs.get(ENO_PORT).map(|signal| signal_bus.register(signal)));
+                    
+                    let mut outputs: Vec<&String> = block_config.outputs.values().collect();
+                    outputs.sort();
+                    safe_outputs.extend(outputs.into_iter().filter_map(|signal| safe_values.get(signal.as_str()).cloned()));
+                }
+                
+                programs.push(Program {
+                    name: program.name.clone(),
+                    blocks: program_start..blocks.len(),
+                    on_fault: program.on_fault,
+                    safe_outputs,
+                    halted: false,
+                });
+            }
+            
+            info!("Created task '{}' every {:?} at priority {} with programs {:?}",
+                plan.name, plan.interval, plan.priority,
+                programs.iter().map(|program| program.name.as_str()).collect::<Vec<_>>());
+            tasks.push(Task {
+                programs,
+                next_release: None,
+                consecutive_overruns: 0,
+                stats: TaskStats {
+                    name: plan.name,
+                    interval: plan.interval,
+                    priority: plan.priority,
+                    executions: 0,
+                    overruns: 0,
+                    last_execution: Duration::ZERO,
+                    max_execution: Duration::ZERO,
+                    total_execution: Duration::ZERO,
+                },
+            });
+        }
+        
+        let mut safe_states: Vec<_> = safe_values.into_values().collect();
+        safe_states.sort_by_key(|(signal, _)| signal.index());
+        
+        let retained_signals = signals.iter()
+            .filter(|signal| signal.retain)
+            .map(|signal| (signal.name.clone(), signal_bus.register(&signal.name)))
+            .collect();
+        
+        Ok(Self { blocks, tasks, locations, status, eno, safe_states, retained_signals, retained_blocks })
+    }
+}
+
+/// Stops a running engine from another task
+#[derive(Clone)]
+pub struct StopHandle(Arc<RwLock<bool>>);
+
+impl StopHandle {
+    pub async fn stop(&self) {
+        *self.0.write().await = false;
+    }
+}
+
+struct ChangeRequest {
+    config: PlcConfig,
+    dry_run: bool,
+    reply: oneshot::Sender<Result<ChangeReport>>,
+}
+
+/// Changes the configuration of a running engine from another task
+#[derive(Clone)]
+pub struct ReloadHandle(mpsc::UnboundedSender<ChangeRequest>);
+
+impl ReloadHandle {
+    /// Report what loading `config` would change, without changing anything
+    pub async fn dry_run(&self, config: PlcConfig) -> Result<ChangeReport> {
+        self.request(config, true).await
+    }
+    
+    /// Load `config` between two scans, see `ScanEngine::apply_change`
+    pub async fn apply(&self, config: PlcConfig) -> Result<ChangeReport> {
+        self.request(config, false).await
+    }
+    
+    // Answered at the start of the engine's next scan
+    async fn request(&self, config: PlcConfig, dry_run: bool) -> Result<ChangeReport> {
+        let (reply, response) = oneshot::channel();
+        let stopped = || PlcError::ExecutionError("scan engine has stopped".to_string());
+        self.0.send(ChangeRequest { config, dry_run, reply }).map_err(|_| stopped())?;
+        response.await.map_err(|_| stopped())?
+    }
+}
 
 pub struct ScanEngine {
     config: PlcConfig,
     signal_bus: SignalBus,
+    /// Handle onto the process image that logic and I/O drivers work on
+    image: SignalBus,
+    /// All blocks, grouped by task in priority order and by program
     blocks: Vec<Box<dyn blocks::BlockTrait>>,
+    tasks: Vec<Task>,
+    /// Task, program and name of every block, for fault records
+    locations: Vec<BlockLocation>,
+    /// Error status of every block
+    status: Vec<BlockStatus>,
+    /// Whether `status` changed since it was last published to the handle
+    status_changed: bool,
+    /// `eno` output of every block, if connected
+    eno: Vec<Option<SignalId>>,
+    /// Every signal that has a safe value
+    safe_states: Vec<(SignalId, SignalValue)>,
+    faults: DiagnosticBuffer,
+    retain: Option<Retain>,
+    heartbeat: Arc<Heartbeat>,
+    /// Set when a fault stopped the engine
+    fault_stop: bool,
+    io_drivers: Vec<Box<dyn IoDriver>>,
     running: Arc<RwLock<bool>>,
     scan_count: u64,
+    clock: Arc<dyn Clock>,
+    changes: mpsc::UnboundedSender<ChangeRequest>,
+    change_receiver: mpsc::UnboundedReceiver<ChangeRequest>,
+    /// Increases with every configuration change applied
+    generation: u64,
+    handle: EngineHandle,
 }
 
 impl ScanEngine {
     pub fn new(config: PlcConfig) -> Result<Self> {
+        Self::with_clock(config, Arc::new(RealTimeClock::new()))
+    }
+    
+    /// Create an engine driven by the given time source instead of wall-clock time
+    pub fn with_clock(config: PlcConfig, clock: Arc<dyn Clock>) -> Result<Self> {
         let signal_bus = SignalBus::new();
+        signal_bus.set_strict(config.strict_types);
         
         // Initialize signals
-        for signal_config in &config.signals {
-            let initial_value = signal_config.to_signal_value()?;
-            signal_bus.set(&signal_config.name, initial_value)?;
+        for signal_config in &config.elementary_signals()? {
+            let id = signal_bus.declare(&signal_config.name, signal_config.data_type()?);
+            signal_bus.write(id, signal_config.to_signal_value()?)?;
             debug!("Initialized signal '{}' with type '{}'", 
                 signal_config.name, signal_config.signal_type);
         }
         
-        // Create blocks
-        let mut blocks = Vec::new();
-        for block_config in &config.blocks {
-            let block = blocks::create_block(block_config)?;
-            info!("Created block '{}' of type '{}'", 
-                block_config.name, block_config.block_type);
-            blocks.push(block);
+        // I/O drivers register their signals before blocks resolve theirs
+        let scan_time = Duration::from_millis(config.scan_time_ms);
+        let mut io_drivers: Vec<Box<dyn IoDriver>> = Vec::new();
+        for device in &config.modbus_devices {
+            io_drivers.push(Box::new(ModbusClient::new(device, &signal_bus, scan_time)?));
+            info!("Created Modbus device '{}'", device.name);
         }
         
-        Ok(Self {
+        let Logic { mut blocks, tasks, locations, status, eno, safe_states, retained_signals, retained_blocks } =
+            Logic::build(&config, &signal_bus)?;
+        
+        let retain = match &config.retain {
+            Some(retain_config) => {
+                let retain = Retain {
+                    store: RetainStore::new(&retain_config.path),
+                    interval: Duration::from_millis(retain_config.interval_ms),
+                    last_save: clock.now(),
+                    signals: retained_signals,
+                    blocks: retained_blocks,
+                };
+                Self::restore_retained(&retain, &signal_bus, &mut blocks, clock.as_ref());
+                Some(retain)
+            }
+            None => None,
+        };
+        
+        let image = signal_bus.process_image();
+        let (changes, change_receiver) = mpsc::unbounded_channel();
+        let faults = DiagnosticBuffer::new(DIAGNOSTIC_BUFFER_SIZE);
+        
+        let mut engine = Self {
             config,
             signal_bus,
+            image,
             blocks,
+            tasks,
+            locations,
+            status,
+            status_changed: true,
+            eno,
+            safe_states,
+            faults: faults.clone(),
+            retain,
+            heartbeat: Arc::new(Heartbeat::new()),
+            fault_stop: false,
+            io_drivers,
             running: Arc::new(RwLock::new(false)),
             scan_count: 0,
-        })
+            clock,
+            changes,
+            change_receiver,
+            generation: 0,
+            handle: EngineHandle::new(faults),
+        };
+        engine.publish_status();
+        Ok(engine)
+    }
+    
+    /// Load the retain file over the initial values. A missing file means a
+    /// first start; an unreadable one is reported and the engine starts cold.
+    fn restore_retained(retain: &Retain, signal_bus: &SignalBus, blocks: &mut [Box<dyn blocks::BlockTrait>], clock: &dyn Clock) {
+        let data = match retain.store.load() {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                info!("No retain file at {}, starting from initial values", retain.store.path().display());
+                return;
+            }
+            Err(e) => {
+                error!("Retain file not restored, starting from initial values: {}", e);
+                return;
+            }
+        };
+        
+        for (name, signal) in &retain.signals {
+            let Some(value) = data.signals.get(name) else { continue };
+            // A signal whose type changed since the save starts from its initial value
+            let restored = signal_bus.declared_type(*signal)
+                .and_then(|declared| value.coerce(declared).ok());
+            match restored {
+                Some(value) => { signal_bus.write(*signal, value).ok(); }
+                None => warn!("Retained value {:?} of signal '{}' does not match its type", value, name),
+            }
+        }
+        
+        for &index in &retain.blocks {
+            let block = &mut blocks[index];
+            let Some(state) = data.blocks.get(block.name()) else { continue };
+            if let Err(e) = block.restore_state(state.clone(), clock) {
+                warn!("Retained state not restored: {}", e);
+            }
+        }
+        
+        info!("Restored {} signal(s) and {} block state(s) from {}",
+            data.signals.len(), data.blocks.len(), retain.store.path().display());
+    }
+    
+    /// Save retentive signals and block states now. The engine also does this
+    /// every `retain.interval_ms` and when it stops. Signals that have no
+    /// value yet are left out of the file.
+    pub fn save_retained(&mut self) -> Result<()> {
+        let Some(retain) = &mut self.retain else {
+            return Ok(());
+        };
+        
+        let mut data = Snapshot::default();
+        for (name, signal) in &retain.signals {
+            match self.image.read(*signal) {
+                Ok(value) => {
+                    data.signals.insert(name.clone(), value);
+                }
+                Err(e) => debug!("Retained signal '{}' not saved: {}", name, e),
+            }
+        }
+        for &index in &retain.blocks {
+            let block = &self.blocks[index];
+            if let Some(state) = block.save_state() {
+                data.blocks.insert(block.name().to_string(), state);
+            }
+        }
+        
+        retain.last_save = self.clock.now();
+        retain.store.save(&data)
+    }
+    
+    /// Every signal value and the state of every block that has one, as of
+    /// the end of the last scan
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            signals: self.image.iter().into_iter().collect(),
+            blocks: self.blocks.iter()
+                .filter_map(|block| block.save_state().map(|state| (block.name().to_string(), state)))
+                .collect(),
+        }
+    }
+    
+    /// Put back a snapshot, typically taken from an engine running the same
+    /// configuration. Signals and blocks the snapshot leaves out keep their
+    /// current values and states.
+    ///
+    /// Nothing is changed if the snapshot names a signal or block this engine
+    /// does not have, or holds a value of the wrong type. A block rejecting
+    /// its state fails the restore after the signals and the blocks before it
+    /// have been restored.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<()> {
+        let mut signals = Vec::new();
+        for (name, value) in &snapshot.signals {
+            let id = self.image.id(name).ok_or_else(|| PlcError::SignalNotFound(name.clone()))?;
+            // Values come back as bool, int, float or string, whatever the
+            // signal was declared as
+            let declared = self.image.declared_type(id)
+                .or_else(|| self.image.read(id).ok().map(|current| current.signal_type()));
+            let value = match declared {
+                Some(declared) => value.coerce(declared)?,
+                None => value.clone(),
+            };
+            signals.push((id, value));
+        }
+        
+        let mut states = Vec::new();
+        for (name, state) in &snapshot.blocks {
+            let index = self.blocks.iter().position(|block| block.name() == name)
+                .ok_or_else(|| PlcError::StateError(format!("block '{}' does not exist", name)))?;
+            states.push((index, state));
+        }
+        
+        for (id, value) in signals {
+            self.image.write(id, value)?;
+        }
+        self.image.publish();
+        
+        for (index, state) in states {
+            self.blocks[index].restore_state(state.clone(), self.clock.as_ref())?;
+        }
+        
+        info!("Restored {} signal(s) and {} block state(s) from a snapshot",
+            snapshot.signals.len(), snapshot.blocks.len());
+        Ok(())
+    }
+    
+    /// Validate `config` and report what `apply_change` would do with it
+    pub fn plan_change(&self, config: &PlcConfig) -> Result<ChangeReport> {
+        config.validate()?;
+        let report = ChangeReport::diff(&self.config, config);
+        if !report.restart_required.is_empty() {
+            return Err(PlcError::ConfigError(format!(
+                "changing {} requires a restart", report.restart_required.join(", "))));
+        }
+        Ok(report)
+    }
+    
+    /// Replace the running configuration without stopping outputs.
+    ///
+    /// A configuration that does not validate is rejected and nothing
+    /// changes. Otherwise blocks keep their state if their name and type are
+    /// unchanged, and signals keep their values unless they are new or their
+    /// type changed. Signals of removed blocks hold their last values. Task
+    /// statistics and release times carry over for tasks whose name and
+    /// interval are unchanged.
+    pub fn apply_change(&mut self, config: PlcConfig) -> Result<ChangeReport> {
+        let report = self.plan_change(&config)?;
+        
+        // Build everything before touching the running logic, so a failure
+        // leaves it as it was
+        let Logic { mut blocks, mut tasks, locations, mut status, eno, safe_states, retained_signals, retained_blocks } =
+            Logic::build(&config, &self.signal_bus)?;
+        
+        self.image.set_strict(config.strict_types);
+        for signal_config in &config.elementary_signals()? {
+            let id = self.image.declare(&signal_config.name, signal_config.data_type()?);
+            if self.image.read(id).is_err() || report.retyped_signals.contains(&signal_config.name) {
+                self.image.write(id, signal_config.to_signal_value()?)?;
+            }
+        }
+        
+        let old_blocks: HashMap<&str, usize> = self.blocks.iter().enumerate()
+            .map(|(index, block)| (block.name(), index))
+            .collect();
+        for (index, block) in blocks.iter_mut().enumerate() {
+            let Some(&old) = old_blocks.get(block.name()) else { continue };
+            if self.blocks[old].block_type() != block.block_type() {
+                continue;
+            }
+            if let Some(state) = self.blocks[old].save_state() {
+                if let Err(e) = block.restore_state(state, self.clock.as_ref()) {
+                    warn!("State not carried over: {}", e);
+                }
+            }
+            status[index].error_count = self.status[old].error_count;
+            status[index].last_error = self.status[old].last_error.clone();
+        }
+        
+        for task in &mut tasks {
+            let old = self.tasks.iter()
+                .find(|old| old.stats.name == task.stats.name && old.stats.interval == task.stats.interval);
+            if let Some(old) = old {
+                task.next_release = old.next_release;
+                task.stats = TaskStats { priority: task.stats.priority, ..old.stats.clone() };
+            }
+        }
+        
+        if let Some(retain) = &mut self.retain {
+            retain.signals = retained_signals;
+            retain.blocks = retained_blocks;
+        }
+        
+        self.blocks = blocks;
+        self.tasks = tasks;
+        self.locations = locations;
+        self.status = status;
+        self.eno = eno;
+        self.safe_states = safe_states;
+        self.config = config;
+        self.generation += 1;
+        self.status_changed = true;
+        self.image.publish();
+        
+        info!("Configuration changed: {}", report);
+        Ok(report)
+    }
+    
+    /// Handle that changes the configuration of the running engine from
+    /// another task
+    pub fn reload_handle(&self) -> ReloadHandle {
+        ReloadHandle(self.changes.clone())
+    }
+    
+    /// Answer configuration change requests at a scan boundary
+    fn handle_change_requests(&mut self) {
+        while let Ok(request) = self.change_receiver.try_recv() {
+            let result = if request.dry_run {
+                self.plan_change(&request.config)
+            } else {
+                self.apply_change(request.config)
+            };
+            if let Err(e) = &result {
+                warn!("Configuration change rejected: {}", e);
+            }
+            request.reply.send(result).ok();
+        }
     }
     
     pub fn from_file(config_path: &str) -> Result<Self> {
@@ -49,57 +545,464 @@ impl ScanEngine {
         Self::new(config)
     }
     
+    /// The bus as seen from outside the scan: values published by the last
+    /// scan, with writes taking effect at the start of the next one
     pub fn signal_bus(&self) -> &SignalBus {
         &self.signal_bus
     }
     
-    // Add method to execute blocks manually (for testing)
+    pub fn config(&self) -> &PlcConfig {
+        &self.config
+    }
+    
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+    
+    /// Block names in the order they execute each scan
+    pub fn execution_order(&self) -> Vec<&str> {
+        self.blocks.iter().map(|block| block.name()).collect()
+    }
+    
+    /// Statistics of every task, in priority order
+    pub fn task_stats(&self) -> Vec<TaskStats> {
+        self.tasks.iter().map(|task| task.stats.clone()).collect()
+    }
+    
+    /// Programs of every task, in execution order
+    pub fn programs(&self) -> Vec<(&str, &str)> {
+        self.tasks.iter()
+            .flat_map(|task| task.programs.iter().map(|program| (task.stats.name.as_str(), program.name.as_str())))
+            .collect()
+    }
+    
+    /// Faults recorded so far. Clone it to read faults while the engine runs.
+    pub fn faults(&self) -> &DiagnosticBuffer {
+        &self.faults
+    }
+    
+    /// Error status of every block, in execution order
+    pub fn block_status(&self) -> &[BlockStatus] {
+        &self.status
+    }
+    
+    /// Whether a fault has stopped the engine
+    pub fn is_faulted(&self) -> bool {
+        self.fault_stop
+    }
+    
+    /// Acknowledge all faults: skipped blocks and programs held in their safe
+    /// state run again, and an engine stopped by a fault can be run again.
+    /// The diagnostic buffer keeps its history.
+    pub fn clear_faults(&mut self) {
+        for status in &mut self.status {
+            status.failed = false;
+            status.skipped = false;
+        }
+        self.status_changed = true;
+        for task in &mut self.tasks {
+            task.consecutive_overruns = 0;
+            task.programs.iter_mut().for_each(|program| program.halted = false);
+        }
+        self.fault_stop = false;
+        self.heartbeat.reset();
+        info!("Faults cleared");
+    }
+    
+    /// One scan of every task, due or not, without exchanging I/O with the
+    /// drivers (for testing)
     pub fn execute_blocks(&mut self) -> Result<()> {
-        for block in &mut self.blocks {
-            block.execute(&self.signal_bus)?;
+        self.check_not_stopped()?;
+        self.image.apply_queued_writes();
+        
+        let mut result = Ok(());
+        for task in 0..self.tasks.len() {
+            if let Err(e) = self.execute_task(task) {
+                result = result.and(Err(e));
+            }
+            if self.fault_stop {
+                break;
+            }
+        }
+        
+        self.image.publish();
+        self.publish_status();
+        result
+    }
+    
+    fn check_not_stopped(&self) -> Result<()> {
+        if self.fault_stop {
+            return Err(PlcError::ExecutionError(
+                "engine stopped by a fault; clear faults to restart".to_string()));
         }
         Ok(())
     }
     
+    /// Execute every program of a task, returning the first block error
+    fn execute_task(&mut self, task: usize) -> Result<()> {
+        let mut result = Ok(());
+        for program in 0..self.tasks[task].programs.len() {
+            if let Err(e) = self.execute_program(task, program) {
+                result = result.and(Err(e));
+            }
+            if self.fault_stop {
+                break;
+            }
+        }
+        result
+    }
+    
+    /// Execute a program's blocks, reacting to failures with its fault policy
+    fn execute_program(&mut self, task: usize, program: usize) -> Result<()> {
+        let Program { blocks, on_fault, halted, .. } = &self.tasks[task].programs[program];
+        let (blocks, policy) = (blocks.clone(), *on_fault);
+        if *halted {
+            for index in blocks {
+                self.write_eno(index, false);
+            }
+            self.drive_safe_outputs(task, program);
+            return Ok(());
+        }
+        
+        let mut result = Ok(());
+        for index in blocks {
+            if self.status[index].skipped {
+                self.write_eno(index, false);
+                continue;
+            }
+            
+            self.heartbeat.block_started(index);
+            self.image.begin_block();
+            let outcome = self.blocks[index].execute(&self.image, self.clock.as_ref());
+            self.image.end_block();
+            let error = match outcome {
+                Ok(()) => {
+                    if self.status[index].failed {
+                        self.status[index].failed = false;
+                        self.status_changed = true;
+                    }
+                    self.write_eno(index, true);
+                    continue;
+                }
+                Err(e) => e,
+            };
+            self.write_eno(index, false);
+            
+            // A block failing every scan is recorded once until its error changes
+            let message = error.to_string();
+            let status = &mut self.status[index];
+            let repeated = status.failed && status.last_error.as_ref() == Some(&message);
+            status.failed = true;
+            status.error_count += 1;
+            status.last_error = Some(message.clone());
+            self.status_changed = true;
+            if !repeated {
+                let location = &self.locations[index];
+                self.faults.push(FaultRecord::new(FaultKind::BlockError, &location.task, message, policy)
+                    .at(&location.program, &location.block));
+            }
+            
+            match policy {
+                FaultPolicy::Continue => {}
+                FaultPolicy::SkipBlock => self.status[index].skipped = true,
+                FaultPolicy::SafeState => {
+                    self.tasks[task].programs[program].halted = true;
+                    self.drive_safe_outputs(task, program);
+                    return Err(error);
+                }
+                FaultPolicy::StopEngine => {
+                    self.stop_on_fault();
+                    return Err(error);
+                }
+            }
+            result = result.and(Err(error));
+        }
+        result
+    }
+    
+    fn write_eno(&self, block: usize, ok: bool) {
+        if let Some(signal) = self.eno[block] {
+            self.image.write(signal, SignalValue::Bool(ok)).ok();
+        }
+    }
+    
+    fn drive_safe_outputs(&self, task: usize, program: usize) {
+        for (signal, value) in &self.tasks[task].programs[program].safe_outputs {
+            self.image.write(*signal, value.clone()).ok();
+        }
+    }
+    
+    /// Drive every safe state and stop scanning until faults are cleared
+    fn stop_on_fault(&mut self) {
+        for (signal, value) in &self.safe_states {
+            self.image.write(*signal, value.clone()).ok();
+        }
+        if !self.fault_stop {
+            error!("Scan engine stopped by a fault; outputs are in their safe states");
+        }
+        self.fault_stop = true;
+    }
+    
+    /// Run every task due at `now` in priority order
+    fn execute_due_tasks(&mut self, now: Duration) -> Result<()> {
+        let watchdog = self.config.watchdog.clone().unwrap_or_default();
+        let mut result = Ok(());
+        
+        for index in 0..self.tasks.len() {
+            if !self.tasks[index].is_due(now) {
+                continue;
+            }
+            
+            let release = self.tasks[index].next_release.unwrap_or(now);
+            let start = Instant::now();
+            self.heartbeat.task_started();
+            if let Err(e) = self.execute_task(index) {
+                result = result.and(Err(e));
+            }
+            self.heartbeat.task_finished();
+            let elapsed = start.elapsed();
+            let finish = self.clock.now();
+            
+            let task = &mut self.tasks[index];
+            let stats = &mut task.stats;
+            stats.executions += 1;
+            stats.last_execution = elapsed;
+            stats.max_execution = stats.max_execution.max(elapsed);
+            stats.total_execution += elapsed;
+            
+            // Skip releases that have already passed rather than running the
+            // task back to back to catch up
+            let mut next = release + stats.interval;
+            if next <= finish {
+                stats.overruns += 1;
+                task.consecutive_overruns += 1;
+                warn!("Task '{}' overrun: finished {:?} after its release, interval {:?}",
+                    stats.name, finish - release, stats.interval);
+                let missed = (finish - next).as_nanos() / stats.interval.as_nanos() + 1;
+                next += stats.interval * missed as u32;
+            } else {
+                task.consecutive_overruns = 0;
+            }
+            task.next_release = Some(next);
+            
+            // The watchdog thread has already recorded a hung block
+            let tripped = self.heartbeat.tripped();
+            let timed_out = watchdog.timeout_ms.is_some_and(|timeout| elapsed > Duration::from_millis(timeout));
+            let fault = if tripped {
+                None
+            } else if timed_out {
+                Some((FaultKind::WatchdogTimeout, format!("execution took {:?}", elapsed)))
+            } else if watchdog.max_overruns.is_some_and(|max| task.consecutive_overruns >= max) {
+                Some((FaultKind::RepeatedOverruns, format!("{} overruns in a row", task.consecutive_overruns)))
+            } else {
+                None
+            };
+            
+            let stop = tripped || fault.is_some();
+            if let Some((kind, message)) = fault {
+                self.faults.push(FaultRecord::new(kind, &task.stats.name, message, FaultPolicy::StopEngine));
+            }
+            if stop {
+                self.stop_on_fault();
+            }
+            if self.fault_stop {
+                break;
+            }
+        }
+        result
+    }
+    
+    /// Move the release of every task due at `now` to its next interval
+    /// without running it, so tasks resume on schedule after a pause
+    fn skip_due_tasks(&mut self, now: Duration) {
+        for task in &mut self.tasks {
+            if task.is_due(now) {
+                task.next_release = Some(now + task.stats.interval);
+            }
+        }
+    }
+    
+    fn publish_status(&mut self) {
+        let changed = std::mem::take(&mut self.status_changed);
+        let running = self.is_running();
+        self.handle.update(|status| {
+            status.running = running;
+            status.faulted = self.fault_stop;
+            status.scan_count = self.scan_count;
+            status.tasks.clear();
+            status.tasks.extend(self.tasks.iter().map(|task| task.stats.clone()));
+            if changed {
+                status.blocks.clone_from(&self.status);
+            }
+        });
+    }
+    
+    /// Handle that reads the engine status and pauses the engine from
+    /// another task
+    pub fn handle(&self) -> EngineHandle {
+        self.handle.clone()
+    }
+    
+    /// Engine clock time at which the next task is due
+    fn next_release(&self) -> Duration {
+        let now = self.clock.now();
+        self.tasks.iter()
+            .map(|task| task.next_release.unwrap_or(now))
+            .min()
+            .unwrap_or(now)
+    }
+    
+    /// Add a driver whose I/O is exchanged around every scan
+    pub fn add_io_driver(&mut self, driver: Box<dyn IoDriver>) {
+        self.io_drivers.push(driver);
+    }
+    
+    /// Start background communication of every I/O driver. `run` does this
+    /// itself; call it before driving scans by hand.
+    pub fn start_io(&mut self) {
+        for driver in &mut self.io_drivers {
+            driver.start();
+        }
+    }
+    
+    /// One complete scan.
+    ///
+    /// Input phase: writes queued on the bus since the last scan and the
+    /// latest device inputs are copied into the process image. Logic phase:
+    /// the tasks due on the engine clock execute against the image only.
+    /// Output phase: the image is published to the bus in one step and
+    /// handed to the I/O drivers.
+    pub fn scan(&mut self) -> Result<()> {
+        self.handle_change_requests();
+        if self.handle.take_clear_faults() {
+            self.clear_faults();
+        }
+        self.check_not_stopped()?;
+        self.image.apply_queued_writes();
+        // A driver that cannot latch flags its points and the logic runs anyway
+        for driver in &mut self.io_drivers {
+            if let Err(e) = driver.latch_inputs(&self.image) {
+                warn!("Latching inputs of '{}' failed: {}", driver.name(), e);
+            }
+        }
+        
+        let now = self.clock.now();
+        let result = if self.handle.is_paused() {
+            self.skip_due_tasks(now);
+            Ok(())
+        } else {
+            self.execute_due_tasks(now)
+        };
+        self.scan_count += 1;
+        
+        self.image.set_scan_number(self.scan_count);
+        self.image.publish();
+        for driver in &mut self.io_drivers {
+            driver.write_outputs(&self.image)?;
+        }
+        
+        if self.retain.as_ref().is_some_and(|retain| now >= retain.last_save + retain.interval) {
+            if let Err(e) = self.save_retained() {
+                error!("Saving retained values failed: {}", e);
+            }
+        }
+        
+        self.publish_status();
+        result
+    }
+    
+    /// Run scans for `duration` of simulated time, advancing the engine's
+    /// clock to the next task release after each scan. The engine must have
+    /// been created with a `SimulatedClock`.
+    pub fn run_simulated(&mut self, duration: Duration) -> Result<()> {
+        let clock = self.clock.as_simulated()
+            .ok_or_else(|| PlcError::ExecutionError("run_simulated needs an engine created with a SimulatedClock".to_string()))?
+            .clone();
+        let end = clock.now() + duration;
+        
+        while clock.now() < end {
+            self.scan()?;
+            clock.set(self.next_release().max(clock.now()));
+        }
+        
+        Ok(())
+    }
+    
     pub async fn run(&mut self) -> Result<()> {
-        info!("Starting scan engine with {}ms scan time", self.config.scan_time_ms);
+        for task in &self.tasks {
+            info!("Starting task '{}' every {:?}", task.stats.name, task.stats.interval);
+        }
         
         {
             let mut running = self.running.write().await;
             *running = true;
         }
         
-        let mut scan_interval = interval(Duration::from_millis(self.config.scan_time_ms));
+        self.start_io();
+        
+        // Stops the watchdog thread when the engine stops
+        let mut watchdog = self.spawn_watchdog();
+        let mut generation = self.generation;
         
-        while *self.running.read().await {
-            scan_interval.tick().await;
+        while *self.running.read().await && !self.fault_stop {
+            let delay = self.next_release().saturating_sub(self.clock.now());
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
             
             let scan_start = std::time::Instant::now();
             
-            // Execute all blocks in order
-            self.execute_blocks().ok();
+            // Block errors are already in the diagnostic buffer
+            if let Err(e) = self.scan() {
+                debug!("Scan {} failed: {}", self.scan_count, e);
+            }
             
-            self.scan_count += 1;
-            let scan_duration = scan_start.elapsed();
+            debug!("Scan {} completed in {:?}", self.scan_count, scan_start.elapsed());
             
-            if scan_duration.as_millis() > self.config.scan_time_ms as u128 {
-                error!("Scan overrun: {}ms > {}ms", 
-                    scan_duration.as_millis(), self.config.scan_time_ms);
-            } else {
-                debug!("Scan {} completed in {:?}", self.scan_count, scan_duration);
+            // The watchdog works on a copy of the block layout
+            if generation != self.generation {
+                watchdog = self.spawn_watchdog();
+                generation = self.generation;
             }
         }
+        drop(watchdog);
+        
+        *self.running.write().await = false;
+        self.publish_status();
+        
+        if let Err(e) = self.save_retained() {
+            error!("Saving retained values failed: {}", e);
+        }
         
         info!("Scan engine stopped after {} scans", self.scan_count);
         Ok(())
     }
     
+    fn spawn_watchdog(&self) -> Option<WatchdogGuard> {
+        self.config.watchdog.as_ref()
+            .and_then(|watchdog| watchdog.timeout_ms)
+            .map(|timeout| Watchdog {
+                heartbeat: self.heartbeat.clone(),
+                timeout: Duration::from_millis(timeout),
+                locations: self.locations.clone(),
+                safe_states: self.safe_states.clone(),
+                image: self.image.clone(),
+                faults: self.faults.clone(),
+            }.spawn())
+    }
+    
     pub async fn stop(&self) {
         info!("Stopping scan engine...");
         let mut running = self.running.write().await;
         *running = false;
     }
     
+    /// Handle that stops `run` from another task, once the scan in progress
+    /// has finished
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle(self.running.clone())
+    }
+    
     pub fn is_running(&self) -> bool {
         // Try to read without blocking
         self.running.try_read().map(|r| *r).unwrap_or(false)
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use crate::signal::SignalValue;
+use std::collections::BTreeMap;
+
+/// Signal values and block states of an engine, by name, for warm restarts,
+/// debugging and keeping a standby engine in step
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(default)]
+    pub signals: BTreeMap<String, SignalValue>,
+    /// States as produced by `Block::save_state`
+    #[serde(default)]
+    pub blocks: BTreeMap<String, serde_json::Value>,
+}
@@ -0,0 +1,85 @@
+use crate::engine::faults::{BlockStatus, DiagnosticBuffer};
+use crate::engine::tasks::TaskStats;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// State of an engine as of its last scan
+#[derive(Debug, Clone, Default)]
+pub struct EngineStatus {
+    /// Whether `run` is executing
+    pub running: bool,
+    pub paused: bool,
+    /// Whether a fault has stopped the engine
+    pub faulted: bool,
+    pub scan_count: u64,
+    pub tasks: Vec<TaskStats>,
+    /// Every block, in execution order
+    pub blocks: Vec<BlockStatus>,
+}
+
+struct Shared {
+    status: RwLock<EngineStatus>,
+    paused: AtomicBool,
+    faults: DiagnosticBuffer,
+    /// Faults to be acknowledged at the start of the next scan
+    clear_faults: AtomicBool,
+}
+
+/// Watches and pauses an engine from other tasks, such as the REST API
+#[derive(Clone)]
+pub struct EngineHandle {
+    shared: Arc<Shared>,
+}
+
+impl EngineHandle {
+    pub(crate) fn new(faults: DiagnosticBuffer) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                status: RwLock::new(EngineStatus::default()),
+                paused: AtomicBool::new(false),
+                faults,
+                clear_faults: AtomicBool::new(false),
+            }),
+        }
+    }
+    
+    pub fn status(&self) -> EngineStatus {
+        let mut status = self.shared.status.read().unwrap_or_else(|e| e.into_inner()).clone();
+        status.paused = self.is_paused();
+        status
+    }
+    
+    pub(crate) fn update(&self, update: impl FnOnce(&mut EngineStatus)) {
+        update(&mut self.shared.status.write().unwrap_or_else(|e| e.into_inner()));
+    }
+    
+    /// Stop executing logic from the next scan on. Outputs hold their
+    /// values, while external writes and I/O are still exchanged.
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::SeqCst);
+    }
+    
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::SeqCst);
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::SeqCst)
+    }
+    
+    /// The engine's diagnostic buffer
+    pub fn faults(&self) -> &DiagnosticBuffer {
+        &self.shared.faults
+    }
+    
+    /// Acknowledge all faults at the start of the next scan, as
+    /// `ScanEngine::clear_faults` does. An engine already stopped by a fault
+    /// has left its scan loop and is not restarted by this.
+    pub fn clear_faults(&self) {
+        self.shared.clear_faults.store(true, Ordering::SeqCst);
+    }
+    
+    pub(crate) fn take_clear_faults(&self) -> bool {
+        self.shared.clear_faults.swap(false, Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use crate::{Result, PlcError, signal::SignalType};
+use super::config::SignalConfig;
+
+/// Most elements one array or struct signal may expand to
+pub const MAX_ELEMENTS: usize = 65_536;
+
+/// Indices of an array signal: `array: 5` for indices 0 to 4, or
+/// `array: [1, 5]` for 1 to 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArrayBounds {
+    Length(u32),
+    Range(i64, i64),
+}
+
+impl ArrayBounds {
+    pub fn indices(&self) -> std::ops::RangeInclusive<i64> {
+        match *self {
+            ArrayBounds::Length(length) => 0..=length as i64 - 1,
+            ArrayBounds::Range(first, last) => first..=last,
+        }
+    }
+    
+    pub fn len(&self) -> usize {
+        let indices = self.indices();
+        (indices.end() - indices.start() + 1).max(0) as usize
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// User-defined struct type, declared under `types` and used as the type of
+/// signals and of other struct fields
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StructType {
+    pub name: String,
+    pub fields: Vec<FieldConfig>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FieldConfig {
+    pub name: String,
+    /// Elementary type or the name of another struct type
+    #[serde(rename = "type")]
+    pub field_type: String,
+    /// Value the field starts from, unless the signal gives one
+    #[serde(default)]
+    pub initial: serde_yaml::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array: Option<ArrayBounds>,
+}
+
+/// Split a signal into its elementary elements, named like `pumps[2].run`.
+///
+/// An array takes one initial value for all its elements or a list with one
+/// per element, and a struct a mapping from field names to values, falling
+/// back to each field's own `initial`. `safe_value` follows the same rules
+/// and `retain` applies to every element. Elements come out in declaration
+/// order, so the bus keeps those of one signal next to each other.
+pub fn expand(signal: &SignalConfig, types: &[StructType]) -> Result<Vec<SignalConfig>> {
+    let mut expansion = Expansion { signal, types, nesting: Vec::new(), elements: Vec::new() };
+    expansion.visit(
+        signal.name.clone(),
+        &signal.signal_type,
+        signal.array,
+        &signal.initial,
+        signal.safe_value.as_ref(),
+    )?;
+    Ok(expansion.elements)
+}
+
+struct Expansion<'a> {
+    signal: &'a SignalConfig,
+    types: &'a [StructType],
+    /// Struct types being expanded, to catch types that contain themselves
+    nesting: Vec<&'a str>,
+    elements: Vec<SignalConfig>,
+}
+
+impl<'a> Expansion<'a> {
+    fn visit(
+        &mut self,
+        name: String,
+        type_name: &'a str,
+        array: Option<ArrayBounds>,
+        initial: &serde_yaml::Value,
+        safe_value: Option<&serde_yaml::Value>,
+    ) -> Result<()> {
+        if let Some(bounds) = array {
+            if bounds.is_empty() || bounds.len() > MAX_ELEMENTS {
+                return Err(PlcError::ConfigError(format!(
+                    "array '{}' must have between 1 and {} elements", name, MAX_ELEMENTS)));
+            }
+            for (position, index) in bounds.indices().enumerate() {
+                let element = format!("{}[{}]", name, index);
+                let initial = element_value(initial, position, bounds.len(), &name, "initial")?;
+                let safe_value = safe_value
+                    .map(|value| element_value(value, position, bounds.len(), &name, "safe_value"))
+                    .transpose()?;
+                self.visit(element, type_name, None, initial, safe_value)?;
+            }
+            return Ok(());
+        }
+        
+        let Some(struct_type) = self.types.iter().find(|t| t.name == type_name) else {
+            if self.elements.len() >= MAX_ELEMENTS {
+                return Err(PlcError::ConfigError(format!(
+                    "signal '{}' has more than {} elements", self.signal.name, MAX_ELEMENTS)));
+            }
+            self.elements.push(SignalConfig {
+                name,
+                signal_type: type_name.to_string(),
+                initial: initial.clone(),
+                safe_value: safe_value.cloned(),
+                retain: self.signal.retain,
+                array: None,
+            });
+            return Ok(());
+        };
+        
+        if self.nesting.contains(&type_name) {
+            return Err(PlcError::ConfigError(format!("type '{}' contains itself", type_name)));
+        }
+        check_fields(initial, struct_type, &name, "initial")?;
+        if let Some(value) = safe_value {
+            check_fields(value, struct_type, &name, "safe_value")?;
+        }
+        
+        self.nesting.push(type_name);
+        for field in &struct_type.fields {
+            let initial = initial.get(&field.name).unwrap_or(&field.initial);
+            let safe_value = safe_value.and_then(|value| value.get(&field.name));
+            self.visit(format!("{}.{}", name, field.name), &field.field_type, field.array, initial, safe_value)?;
+        }
+        self.nesting.pop();
+        Ok(())
+    }
+}
+
+/// Value for one element of an array: an entry of a list, or the value
+/// shared by all elements
+fn element_value<'v>(
+    value: &'v serde_yaml::Value,
+    position: usize,
+    length: usize,
+    name: &str,
+    key: &str,
+) -> Result<&'v serde_yaml::Value> {
+    match value {
+        serde_yaml::Value::Sequence(values) if values.len() != length => Err(PlcError::ConfigError(format!(
+            "array '{}' has {} elements but {} has {}", name, length, key, values.len()))),
+        serde_yaml::Value::Sequence(values) => Ok(&values[position]),
+        value => Ok(value),
+    }
+}
+
+/// Check that a struct value is a mapping of the type's fields
+fn check_fields(value: &serde_yaml::Value, struct_type: &StructType, name: &str, key: &str) -> Result<()> {
+    match value {
+        serde_yaml::Value::Null => Ok(()),
+        serde_yaml::Value::Mapping(fields) => {
+            for field in fields.keys() {
+                let field = field.as_str().map(str::to_string).unwrap_or_else(|| format!("{:?}", field));
+                if !struct_type.fields.iter().any(|f| f.name == field) {
+                    return Err(PlcError::ConfigError(format!(
+                        "{} of '{}': {} has no field '{}'", key, name, struct_type.name, field)));
+                }
+            }
+            Ok(())
+        }
+        _ => Err(PlcError::ConfigError(format!(
+            "{} of '{}' must map fields of {} to values", key, name, struct_type.name))),
+    }
+}
+
+/// Problems with the struct type declarations themselves
+pub(crate) fn check_types(types: &[StructType]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (index, struct_type) in types.iter().enumerate() {
+        if types[..index].iter().any(|other| other.name == struct_type.name) {
+            problems.push(format!("type '{}' is declared more than once", struct_type.name));
+        }
+        if SignalType::parse(&struct_type.name).is_some() {
+            problems.push(format!("type '{}' has the name of an elementary type", struct_type.name));
+        }
+        if struct_type.fields.is_empty() {
+            problems.push(format!("type '{}' has no fields", struct_type.name));
+        }
+        for (index, field) in struct_type.fields.iter().enumerate() {
+            if struct_type.fields[..index].iter().any(|other| other.name == field.name) {
+                problems.push(format!("type '{}': field '{}' is declared more than once", struct_type.name, field.name));
+            }
+            if SignalType::parse(&field.field_type).is_none() && !types.iter().any(|t| t.name == field.field_type) {
+                problems.push(format!("type '{}': field '{}' has unknown type '{}'",
+                    struct_type.name, field.name, field.field_type));
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,155 @@
+use crate::{Result, PlcError, blocks::BlockConfig};
+use crate::engine::config::PlcConfig;
+use crate::engine::faults::FaultPolicy;
+use crate::engine::ordering::execution_order;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Name of the task and program that run every block not assigned to a program
+pub const MAIN_TASK: &str = "main";
+
+/// A program with its blocks in execution order, as indices into `PlcConfig::blocks`
+#[derive(Debug, Clone)]
+pub struct ProgramPlan {
+    pub name: String,
+    pub blocks: Vec<usize>,
+    pub on_fault: FaultPolicy,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskPlan {
+    pub name: String,
+    pub interval: Duration,
+    pub priority: u32,
+    pub programs: Vec<ProgramPlan>,
+}
+
+/// Resolve the tasks and programs of `config`, sorted by priority.
+///
+/// Blocks not listed in any program form the `main` program of the `main`
+/// task, which runs every `scan_time_ms` after all other tasks due at the
+/// same time. A configuration without tasks therefore runs exactly as before
+/// tasks existed. Each program is ordered by data flow on its own, so signals
+/// passed between programs or tasks are read as last written.
+pub fn plan_tasks(config: &PlcConfig) -> Result<Vec<TaskPlan>> {
+    let error = |message: String| Err(PlcError::ConfigError(message));
+    
+    let mut block_index: HashMap<&str, usize> = HashMap::new();
+    for (index, block) in config.blocks.iter().enumerate() {
+        block_index.entry(&block.name).or_insert(index);
+    }
+    
+    let mut owners: Vec<Option<&str>> = vec![None; config.blocks.len()];
+    let mut programs: HashMap<&str, ProgramPlan> = HashMap::new();
+    
+    for program in &config.programs {
+        if program.name == MAIN_TASK {
+            return error(format!("program name '{}' is reserved for blocks not assigned to a program", MAIN_TASK));
+        }
+        if programs.contains_key(program.name.as_str()) {
+            return error(format!("program '{}' is defined more than once", program.name));
+        }
+        
+        let mut blocks = Vec::new();
+        for name in &program.blocks {
+            let Some(&index) = block_index.get(name.as_str()) else {
+                return error(format!("program '{}': block '{}' does not exist", program.name, name));
+            };
+            if let Some(owner) = owners[index] {
+                return error(format!("block '{}' is in both program '{}' and '{}'", name, owner, program.name));
+            }
+            owners[index] = Some(&program.name);
+            blocks.push(index);
+        }
+        
+        programs.insert(&program.name, ProgramPlan {
+            name: program.name.clone(),
+            blocks: ordered(&config.blocks, blocks)?,
+            on_fault: program.on_fault,
+        });
+    }
+    
+    let mut tasks = Vec::new();
+    for task in &config.tasks {
+        if task.name == MAIN_TASK {
+            return error(format!("task name '{}' is reserved for blocks not assigned to a program", MAIN_TASK));
+        }
+        if tasks.iter().any(|other: &TaskPlan| other.name == task.name) {
+            return error(format!("task '{}' is defined more than once", task.name));
+        }
+        if task.interval_ms == 0 {
+            return error(format!("task '{}': interval_ms must be greater than zero", task.name));
+        }
+        
+        let mut plans = Vec::new();
+        for name in &task.programs {
+            let Some(program) = programs.remove(name.as_str()) else {
+                return if config.programs.iter().any(|program| &program.name == name) {
+                    error(format!("program '{}' is assigned to more than one task", name))
+                } else {
+                    error(format!("task '{}': program '{}' does not exist", task.name, name))
+                };
+            };
+            plans.push(program);
+        }
+        
+        tasks.push(TaskPlan {
+            name: task.name.clone(),
+            interval: Duration::from_millis(task.interval_ms),
+            priority: task.priority,
+            programs: plans,
+        });
+    }
+    
+    if let Some(program) = config.programs.iter().find(|program| programs.contains_key(program.name.as_str())) {
+        return error(format!("program '{}' is not assigned to a task", program.name));
+    }
+    
+    let unassigned: Vec<usize> = (0..config.blocks.len()).filter(|&index| owners[index].is_none()).collect();
+    if !unassigned.is_empty() || tasks.is_empty() {
+        tasks.push(TaskPlan {
+            name: MAIN_TASK.to_string(),
+            interval: Duration::from_millis(config.scan_time_ms.max(1)),
+            priority: u32::MAX,
+            programs: vec![ProgramPlan {
+                name: MAIN_TASK.to_string(),
+                blocks: ordered(&config.blocks, unassigned)?,
+                on_fault: config.on_fault,
+            }],
+        });
+    }
+    
+    // Stable, so tasks of equal priority keep their configured order
+    tasks.sort_by_key(|task| task.priority);
+    Ok(tasks)
+}
+
+/// Sort a subset of `blocks` into execution order
+fn ordered(blocks: &[BlockConfig], indices: Vec<usize>) -> Result<Vec<usize>> {
+    let subset: Vec<BlockConfig> = indices.iter().map(|&index| blocks[index].clone()).collect();
+    Ok(execution_order(&subset)?.into_iter().map(|position| indices[position]).collect())
+}
+
+/// Execution statistics of one task
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskStats {
+    pub name: String,
+    pub interval: Duration,
+    pub priority: u32,
+    pub executions: u64,
+    /// Executions that finished at or after the task's next release, which
+    /// also skips the releases that were missed
+    pub overruns: u64,
+    pub last_execution: Duration,
+    pub max_execution: Duration,
+    pub total_execution: Duration,
+}
+
+impl TaskStats {
+    pub fn average_execution(&self) -> Duration {
+        match self.executions {
+            0 => Duration::ZERO,
+            executions => self.total_execution / executions.min(u32::MAX as u64) as u32,
+        }
+    }
+}
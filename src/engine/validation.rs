@@ -0,0 +1,470 @@
+use crate::{Result, PlcError, blocks, signal::{SignalBus, SignalType, SignalValue, FORCES_ACTIVE}};
+use crate::modbus::{comm_fault_signal, ModbusClient, ModbusServer};
+use crate::blocks::ports::{input_port_type, is_generic_port, output_port_type, PortType, ENO_PORT};
+use crate::engine::config::PlcConfig;
+use crate::engine::structured::check_types;
+use crate::engine::{faults::DiagnosticBuffer, status::EngineHandle};
+use crate::engine::tasks::plan_tasks;
+use crate::api::ApiServer;
+use std::collections::HashMap;
+use std::fmt;
+
+/// One problem found while validating a configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub line: Option<usize>,
+    pub block: Option<String>,
+    pub port: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        if let Some(block) = &self.block {
+            write!(f, "block '{}': ", block)?;
+        }
+        if let Some(port) = &self.port {
+            write!(f, "port '{}': ", port)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// YAML line numbers of the signals, blocks and block ports of a configuration.
+///
+/// serde_yaml does not keep positions, so `PlcConfig::from_yaml` recovers them
+/// with a line scan of the block-style layout used by the example configs.
+/// Items it cannot place are reported without a line number.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    signals: Vec<usize>,
+    blocks: Vec<usize>,
+    ports: HashMap<(usize, String, String), usize>,
+}
+
+impl SourceMap {
+    pub fn from_yaml(yaml_str: &str) -> Self {
+        let mut map = SourceMap::default();
+        let mut section = "";
+        let mut item_indent = 0;
+        let mut port_section: Option<(String, usize)> = None;
+        
+        for (index, raw) in yaml_str.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw.split(" #").next().unwrap_or("").trim_end();
+            let content = line.trim_start();
+            
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            
+            let indent = line.len() - content.len();
+            
+            if indent == 0 {
+                section = match content.split(':').next() {
+                    Some("signals") => "signals",
+                    Some("blocks") => "blocks",
+                    _ => "",
+                };
+                port_section = None;
+                continue;
+            }
+            
+            if let Some(item) = content.strip_prefix("- ") {
+                if item.trim_start().starts_with("name:") {
+                    item_indent = indent + 2;
+                    port_section = None;
+                    match section {
+                        "signals" => map.signals.push(line_number),
+                        "blocks" => map.blocks.push(line_number),
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+            
+            if section != "blocks" || map.blocks.is_empty() {
+                continue;
+            }
+            
+            let Some((key, _)) = content.split_once(':') else {
+                continue;
+            };
+            
+            if indent <= item_indent {
+                port_section = matches!(key, "inputs" | "outputs")
+                    .then(|| (key.to_string(), indent));
+            } else if let Some((kind, section_indent)) = &port_section {
+                if indent > *section_indent {
+                    let block = map.blocks.len() - 1;
+                    map.ports.insert((block, kind.clone(), key.trim().to_string()), line_number);
+                }
+            }
+        }
+        
+        map
+    }
+    
+    fn signal(&self, index: usize) -> Option<usize> {
+        self.signals.get(index).copied()
+    }
+    
+    fn block(&self, index: usize) -> Option<usize> {
+        self.blocks.get(index).copied()
+    }
+    
+    fn port(&self, block: usize, kind: &str, port: &str) -> Option<usize> {
+        self.ports.get(&(block, kind.to_string(), port.to_string()))
+            .copied()
+            .or_else(|| self.block(block))
+    }
+}
+
+/// Why a CONST block's `value` cannot be written to a signal of type
+/// `signal_type`, which it is converted to like a typed literal
+fn const_value_problem(block: &blocks::BlockConfig, signal_type: &str) -> Option<String> {
+    let value = block.params.get("value")?;
+    let signal_type = SignalType::parse(signal_type)?;
+    SignalValue::from_yaml(value, signal_type).err().map(|e| e.to_string())
+}
+
+impl PlcConfig {
+    /// Check the whole configuration and report every problem found, rather
+    /// than stopping at the first one like `ScanEngine::new` does
+    pub fn validate(&self) -> Result<()> {
+        let diagnostics = self.diagnostics();
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(PlcError::ValidationError(diagnostics))
+        }
+    }
+    
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let map = &self.source_map;
+        
+        if self.scan_time_ms == 0 {
+            diagnostics.push(Diagnostic {
+                line: None,
+                block: None,
+                port: None,
+                message: "scan_time_ms must be greater than zero".to_string(),
+            });
+        }
+        
+        if let Some(watchdog) = &self.watchdog {
+            if watchdog.timeout_ms == Some(0) || watchdog.max_overruns == Some(0) {
+                diagnostics.push(Diagnostic {
+                    line: None,
+                    block: None,
+                    port: None,
+                    message: "watchdog timeout_ms and max_overruns must be greater than zero".to_string(),
+                });
+            }
+        }
+        
+        let retained = self.signals.iter().filter(|signal| signal.retain).map(|signal| format!("signal '{}'", signal.name))
+            .chain(self.blocks.iter().filter(|block| block.retain).map(|block| format!("block '{}'", block.name)));
+        match &self.retain {
+            None => {
+                for item in retained {
+                    diagnostics.push(Diagnostic {
+                        line: None,
+                        block: None,
+                        port: None,
+                        message: format!("{} is retentive but no retain file is configured", item),
+                    });
+                }
+            }
+            Some(retain) if retain.interval_ms == 0 => diagnostics.push(Diagnostic {
+                line: None,
+                block: None,
+                port: None,
+                message: "retain interval_ms must be greater than zero".to_string(),
+            }),
+            Some(_) => {}
+        }
+        
+        for problem in check_types(&self.types) {
+            diagnostics.push(Diagnostic {
+                line: None,
+                block: None,
+                port: None,
+                message: problem,
+            });
+        }
+        
+        // Arrays and structs are checked element by element
+        let mut elements = Vec::new();
+        for (index, signal) in self.signals.iter().enumerate() {
+            match signal.elements(&self.types) {
+                Ok(signals) => elements.extend(signals.into_iter().map(|element| (index, element))),
+                Err(e) => diagnostics.push(Diagnostic {
+                    line: map.signal(index),
+                    block: None,
+                    port: None,
+                    message: format!("signal '{}': {}", signal.name, e),
+                }),
+            }
+        }
+        
+        // Signals: unique names and known types
+        let mut signal_types: HashMap<&str, &str> = HashMap::new();
+        for &(index, ref signal) in &elements {
+            if signal_types.insert(&signal.name, &signal.signal_type).is_some() {
+                diagnostics.push(Diagnostic {
+                    line: map.signal(index),
+                    block: None,
+                    port: None,
+                    message: format!("signal '{}' is declared more than once", signal.name),
+                });
+            }
+            
+            if let Err(e) = signal.to_signal_value() {
+                diagnostics.push(Diagnostic {
+                    line: map.signal(index),
+                    block: None,
+                    port: None,
+                    message: format!("signal '{}': {}", signal.name, e),
+                });
+            }
+            
+            if signal.data_type().is_ok() {
+                if let Err(e) = signal.to_safe_value() {
+                    diagnostics.push(Diagnostic {
+                        line: map.signal(index),
+                        block: None,
+                        port: None,
+                        message: format!("signal '{}': safe_value: {}", signal.name, e),
+                    });
+                }
+            }
+        }
+        
+        // Modbus devices add a bool fault flag for each signal they exchange
+        let fault_signals: Vec<String> = self.modbus_devices.iter()
+            .flat_map(|device| &device.poll_groups)
+            .flat_map(|group| &group.signals)
+            .map(|mapping| comm_fault_signal(&mapping.signal))
+            .collect();
+        for signal in &fault_signals {
+            signal_types.entry(signal).or_insert("bool");
+        }
+        signal_types.entry(FORCES_ACTIVE).or_insert("bool");
+        
+        // Handles from building the blocks are thrown away with this bus
+        let scratch_bus = SignalBus::new();
+        let mut block_names: HashMap<&str, usize> = HashMap::new();
+        // Block index and output port of every writer of each signal
+        let mut writers: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+        
+        for (index, block) in self.blocks.iter().enumerate() {
+            let block_diagnostic = |port: Option<(&str, &str)>, message: String| Diagnostic {
+                line: match port {
+                    Some((kind, port)) => map.port(index, kind, port),
+                    None => map.block(index),
+                },
+                block: Some(block.name.clone()),
+                port: port.map(|(_, port)| port.to_string()),
+                message,
+            };
+            
+            if block_names.insert(&block.name, index).is_some() {
+                diagnostics.push(block_diagnostic(None, "block name is used more than once".to_string()));
+            }
+            
+            // Required ports, parameters and known block type
+            match blocks::create_block(block, &scratch_bus) {
+                Err(e) => {
+                    diagnostics.push(block_diagnostic(None, e.to_string()));
+                    continue;
+                }
+                Ok(created) if block.retain && created.save_state().is_none() => diagnostics.push(block_diagnostic(
+                    None, format!("{} has no internal state to retain", block.block_type))),
+                Ok(_) => {}
+            }
+            
+            let accepts = |expected: PortType, port: &str, actual: &str| match self.strict_types {
+                true => expected.accepts_strict(actual, is_generic_port(&block.block_type, port)),
+                false => expected.accepts(actual),
+            };
+            // Signals of the generic ports, which strict_types holds to one type
+            let mut generic: Vec<(&str, &str, &String)> = Vec::new();
+            
+            let mut ports: Vec<(&String, &String)> = block.inputs.iter().collect();
+            ports.sort();
+            for (port, signal) in ports {
+                let location = Some(("inputs", port.as_str()));
+                if is_generic_port(&block.block_type, port) {
+                    generic.push(("inputs", port, signal));
+                }
+                match input_port_type(&block.block_type, port) {
+                    None => diagnostics.push(block_diagnostic(
+                        location, format!("{} has no input port '{}'", block.block_type, port))),
+                    Some(expected) => match signal_types.get(signal.as_str()) {
+                        None => diagnostics.push(block_diagnostic(
+                            location, format!("signal '{}' is not declared", signal))),
+                        Some(actual) if !accepts(expected, port, actual) => diagnostics.push(block_diagnostic(
+                            location, format!("expected {} signal, '{}' is {}", expected.name(), signal, actual))),
+                        Some(_) => {}
+                    },
+                }
+            }
+            
+            let mut ports: Vec<(&String, &String)> = block.outputs.iter().collect();
+            ports.sort();
+            for (port, signal) in ports {
+                let location = Some(("outputs", port.as_str()));
+                writers.entry(signal.as_str()).or_default().push((index, port));
+                if is_generic_port(&block.block_type, port) {
+                    generic.push(("outputs", port, signal));
+                }
+                match output_port_type(&block.block_type, port) {
+                    None => diagnostics.push(block_diagnostic(
+                        location, format!("{} has no output port '{}'", block.block_type, port))),
+                    Some(produced) => match signal_types.get(signal.as_str()) {
+                        None => diagnostics.push(block_diagnostic(
+                            location, format!("signal '{}' is not declared", signal))),
+                        Some(actual) if !accepts(produced, port, actual) => diagnostics.push(block_diagnostic(
+                            location, format!("produces {} but '{}' is {}", produced.name(), signal, actual))),
+                        Some(actual) if block.block_type == "CONST" && port != ENO_PORT => {
+                            if let Some(problem) = const_value_problem(block, actual) {
+                                diagnostics.push(block_diagnostic(
+                                    location, format!("value cannot be written to '{}': {}", signal, problem)));
+                            }
+                        }
+                        Some(_) => {}
+                    },
+                }
+            }
+            
+            if self.strict_types {
+                let typed: Vec<_> = generic.iter()
+                    .filter_map(|&(kind, port, signal)| {
+                        let actual = SignalType::parse(signal_types.get(signal.as_str())?)?;
+                        Some((kind, port, signal, actual))
+                    })
+                    .collect();
+                if let Some(&(_, _, first, first_type)) = typed.first() {
+                    for &(kind, port, signal, actual) in &typed[1..] {
+                        if actual != first_type {
+                            diagnostics.push(block_diagnostic(Some((kind, port)), format!(
+                                "'{}' is {} but '{}' is {}; convert one of them explicitly, e.g. with {}_TO_{}",
+                                signal, actual, first, first_type,
+                                actual.name().to_uppercase(), first_type.name().to_uppercase())));
+                        }
+                    }
+                }
+            }
+        }
+        
+        // Each signal may only be driven by one output port
+        for (index, block) in self.blocks.iter().enumerate() {
+            let mut ports: Vec<(&String, &String)> = block.outputs.iter().collect();
+            ports.sort();
+            for (port, signal) in ports {
+                if signal == FORCES_ACTIVE {
+                    diagnostics.push(Diagnostic {
+                        line: map.port(index, "outputs", port),
+                        block: Some(block.name.clone()),
+                        port: Some(port.clone()),
+                        message: format!("signal '{}' is maintained by the force table and cannot be written", signal),
+                    });
+                    continue;
+                }
+                let Some(ports) = writers.get(signal.as_str()) else { continue };
+                let (first_block, first_port) = ports[0];
+                if ports[1..].contains(&(index, port.as_str())) {
+                    diagnostics.push(Diagnostic {
+                        line: map.port(index, "outputs", port),
+                        block: Some(block.name.clone()),
+                        port: Some(port.clone()),
+                        message: format!("signal '{}' is already written by block '{}' port '{}'",
+                            signal, self.blocks[first_block].name, first_port),
+                    });
+                }
+            }
+        }
+        
+        // Modbus mappings must name declared numeric or bool signals, without overlaps
+        if let Some(server) = &self.modbus_server {
+            for (table, address, signal) in server.mappings() {
+                let message = match signal_types.get(signal) {
+                    None => format!("signal '{}' is not declared", signal),
+                    Some(actual) if SignalType::parse(actual) == Some(SignalType::String) => format!("string signal '{}' cannot be mapped", signal),
+                    Some(_) => continue,
+                };
+                diagnostics.push(Diagnostic {
+                    line: None,
+                    block: None,
+                    port: None,
+                    message: format!("modbus_server: {} {}: {}", table, address, message),
+                });
+            }
+            
+            if let Err(e) = ModbusServer::new(server, scratch_bus.clone()) {
+                diagnostics.push(Diagnostic {
+                    line: None,
+                    block: None,
+                    port: None,
+                    message: e.to_string(),
+                });
+            }
+        }
+        
+        for device in &self.modbus_devices {
+            for group in &device.poll_groups {
+                for mapping in &group.signals {
+                    let message = match signal_types.get(mapping.signal.as_str()) {
+                        None => format!("signal '{}' is not declared", mapping.signal),
+                        Some(actual) if SignalType::parse(actual) == Some(SignalType::String) => format!("string signal '{}' cannot be mapped", mapping.signal),
+                        Some(_) => continue,
+                    };
+                    diagnostics.push(Diagnostic {
+                        line: None,
+                        block: None,
+                        port: None,
+                        message: format!("modbus device '{}': {} {}: {}",
+                            device.name, group.table.name(), mapping.address, message),
+                    });
+                }
+            }
+            
+            let scan_time = std::time::Duration::from_millis(self.scan_time_ms);
+            if let Err(e) = ModbusClient::new(device, &scratch_bus, scan_time) {
+                diagnostics.push(Diagnostic {
+                    line: None,
+                    block: None,
+                    port: None,
+                    message: e.to_string(),
+                });
+            }
+        }
+        
+        if let Some(api) = &self.api {
+            if let Err(e) = ApiServer::new(api, scratch_bus.clone(), EngineHandle::new(DiagnosticBuffer::new(1))) {
+                diagnostics.push(Diagnostic {
+                    line: None,
+                    block: None,
+                    port: None,
+                    message: e.to_string(),
+                });
+            }
+        }
+        
+        if let Err(e) = plan_tasks(self) {
+            diagnostics.push(Diagnostic {
+                line: None,
+                block: None,
+                port: None,
+                message: e.to_string(),
+            });
+        }
+        
+        diagnostics
+    }
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use crate::engine::Diagnostic;
 
 #[derive(Error, Debug)]
 pub enum PlcError {
@@ -8,12 +9,25 @@ pub enum PlcError {
     #[error("Invalid signal type: expected {expected}, got {actual}")]
     TypeMismatch { expected: String, actual: String },
     
+    #[error("Conversion error: {0}")]
+    ConversionError(String),
+    
     #[error("Configuration error: {0}")]
     ConfigError(String),
     
     #[error("Block execution error: {0}")]
     ExecutionError(String),
     
+    #[error("Invalid block state: {0}")]
+    StateError(String),
+    
+    #[error("Algebraic loop between blocks: {}", .0.join(" -> "))]
+    AlgebraicLoop(Vec<String>),
+    
+    #[error("Configuration invalid: {} problem(s) found: {}", .0.len(),
+        .0.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("; "))]
+    ValidationError(Vec<Diagnostic>),
+    
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     
@@ -6,15 +6,16 @@ async fn main() -> soft_plc::Result<()> {
     let config_path = "config/pump_alternation.yaml";
     let mut engine = ScanEngine::from_file(config_path)?;
     
+    // Monitor in main task
+    let bus = engine.signal_bus().clone();
+    
     // Spawn engine task
-    let engine_handle = tokio::spawn(async move {
+    let _engine_handle = tokio::spawn(async move {
         if let Err(e) = engine.run().await {
             eprintln!("Engine error: {}", e);
         }
     });
     
-    // Monitor in main task
-    let bus = engine.signal_bus().clone();
     let mut monitor_interval = tokio::time::interval(Duration::from_secs(1));
     
     println!("=== Pump Alternation Monitor ===");
@@ -41,7 +42,7 @@ async fn main() -> soft_plc::Result<()> {
         
         println!("\nPump States:");
         for i in 1..=5 {
-            if let Ok(state) = bus.get(&format!("pump{}_run", i)) {
+            if let Ok(state) = bus.get(&format!("pumps[{}].run", i)) {
                 let status = match state {
                     SignalValue::Bool(true) => "RUNNING",
                     _ => "OFF"
@@ -53,7 +54,4 @@ async fn main() -> soft_plc::Result<()> {
         // Simple command handling (would need proper async stdin in production)
         // This is just for demonstration
     }
-    
-    engine_handle.abort();
-    Ok(())
 }
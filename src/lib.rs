@@ -2,6 +2,8 @@ pub mod signal;
 pub mod blocks;
 pub mod engine;
 pub mod error;
+pub mod modbus;
+pub mod api;
 
 #[cfg(feature = "editor")]
 pub mod editor;
@@ -1,6 +1,5 @@
-use soft_plc::{Result, engine::ScanEngine};
+use soft_plc::{Result, PlcError, engine::{PlcConfig, ScanEngine}, modbus::ModbusServer, api::ApiServer};
 use tracing::{info, error};
-use tracing_subscriber;
 use tokio::signal;
 use std::time::Duration;
 
@@ -11,6 +10,15 @@ async fn main() -> Result<()> {
         .with_max_level(tracing::Level::INFO)
         .init();
     
+    // `soft-plc validate <file>` checks a configuration without running it
+    if std::env::args().nth(1).as_deref() == Some("validate") {
+        let Some(path) = std::env::args().nth(2) else {
+            eprintln!("Usage: soft-plc validate <config.yaml>");
+            std::process::exit(2);
+        };
+        validate(&path);
+    }
+    
     info!("Soft-PLC starting...");
     
     // Get config file from command line or use default
@@ -21,10 +29,63 @@ async fn main() -> Result<()> {
     info!("Loading configuration from: {}", config_path);
     
     // Create and start scan engine
-    let mut engine = ScanEngine::from_file(&config_path)?;
+    let config = PlcConfig::from_file(&config_path)?;
+    let modbus_config = config.modbus_server.clone();
+    let api_config = config.api.clone();
+    let mut engine = ScanEngine::new(config)?;
     
     // Clone signal bus for monitoring
     let signal_bus = engine.signal_bus().clone();
+    let engine_stop = engine.stop_handle();
+    
+    // Reload the configuration file on SIGHUP, without stopping outputs
+    #[cfg(unix)]
+    let reload_handle = {
+        let reload = engine.reload_handle();
+        let config_path = config_path.clone();
+        tokio::spawn(async move {
+            let Ok(mut hangup) = signal::unix::signal(signal::unix::SignalKind::hangup()) else {
+                return;
+            };
+            while hangup.recv().await.is_some() {
+                info!("Reloading configuration from: {}", config_path);
+                let result = match PlcConfig::from_file(&config_path) {
+                    Ok(config) => reload.apply(config).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    error!("Configuration not reloaded: {}", e);
+                }
+            }
+        })
+    };
+    
+    // Spawn Modbus TCP server if configured
+    let modbus_handle = match modbus_config {
+        Some(modbus_config) => {
+            let server = ModbusServer::new(&modbus_config, signal_bus.clone())?;
+            Some(tokio::spawn(async move {
+                if let Err(e) = server.run().await {
+                    error!("Modbus server error: {}", e);
+                }
+            }))
+        }
+        None => None,
+    };
+    
+    // Spawn HTTP API if configured
+    let api_handle = match api_config {
+        Some(api_config) => {
+            let server = ApiServer::new(&api_config, signal_bus.clone(), engine.handle())?
+                .with_reload(engine.reload_handle());
+            Some(tokio::spawn(async move {
+                if let Err(e) = server.run().await {
+                    error!("HTTP API error: {}", e);
+                }
+            }))
+        }
+        None => None,
+    };
     
     // Spawn monitoring task
     let monitor_handle = tokio::spawn(async move {
@@ -57,10 +118,43 @@ async fn main() -> Result<()> {
     
     info!("Shutdown signal received");
     
-    // Stop tasks
+    // Stop tasks, letting the engine finish its scan and save retained values
     monitor_handle.abort();
-    engine_handle.abort();
+    #[cfg(unix)]
+    reload_handle.abort();
+    engine_stop.stop().await;
+    if tokio::time::timeout(Duration::from_secs(5), engine_handle).await.is_err() {
+        error!("Scan engine did not stop in time");
+    }
+    if let Some(handle) = modbus_handle {
+        handle.abort();
+    }
+    if let Some(handle) = api_handle {
+        handle.abort();
+    }
     
     info!("Soft-PLC stopped");
     Ok(())
 }
+
+fn validate(path: &str) -> ! {
+    let result = PlcConfig::from_file(path).and_then(|config| config.validate());
+    
+    match result {
+        Ok(()) => {
+            println!("{}: OK", path);
+            std::process::exit(0);
+        }
+        Err(PlcError::ValidationError(diagnostics)) => {
+            for diagnostic in &diagnostics {
+                eprintln!("{}: {}", path, diagnostic);
+            }
+            eprintln!("{}: {} problem(s) found", path, diagnostics.len());
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }
+    }
+}
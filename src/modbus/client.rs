@@ -0,0 +1,443 @@
+use crate::{Result, PlcError, signal::{Quality, SignalBus, SignalId, SignalValue}};
+use crate::engine::IoDriver;
+use super::config::{comm_fault_signal, Direction, ModbusDeviceConfig, Parity, PollGroupConfig, RtuConfig, Table};
+use super::protocol::{self, Header, Request, Response};
+use super::types::{from_number, RegisterType, WordOrder};
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, timeout, MissedTickBehavior};
+use tokio_serial::{SerialPortBuilderExt, SerialStream};
+use tracing::{debug, info, warn};
+
+/// A signal within a poll group
+struct Point {
+    signal: SignalId,
+    fault: SignalId,
+    /// Offset from the group's first address
+    offset: u16,
+    data_type: RegisterType,
+    word_order: WordOrder,
+    /// Value the signal was declared with. Values read from the device keep its type.
+    template: Option<SignalValue>,
+}
+
+impl Point {
+    fn words(&self, table: Table) -> u16 {
+        if table.is_bits() { 1 } else { self.data_type.words() }
+    }
+}
+
+/// A contiguous range of one table, transferred with a single request
+struct PollGroup {
+    table: Table,
+    direction: Direction,
+    address: u16,
+    count: u16,
+    points: Vec<Point>,
+}
+
+impl PollGroup {
+    fn build(device: &str, config: &PollGroupConfig, bus: &SignalBus) -> Result<Self> {
+        let error = |message: String| PlcError::ConfigError(format!(
+            "modbus device '{}': {} group: {}", device, config.table.name(), message
+        ));
+        
+        if config.signals.is_empty() {
+            return Err(error("no signals".to_string()));
+        }
+        
+        if config.direction == Direction::Write && matches!(config.table, Table::DiscreteInputs | Table::InputRegisters) {
+            return Err(error("is read-only and cannot be written".to_string()));
+        }
+        
+        let words = |data_type: RegisterType| if config.table.is_bits() { 1 } else { data_type.words() as u32 };
+        let address = config.signals.iter().map(|s| s.address).min().unwrap_or_default();
+        let end = config.signals.iter().map(|s| s.address as u32 + words(s.data_type)).max().unwrap_or_default();
+        
+        let max = match (config.table.is_bits(), config.direction) {
+            (true, Direction::Read) => protocol::MAX_READ_BITS,
+            (false, Direction::Read) => protocol::MAX_READ_REGISTERS,
+            (true, Direction::Write) => protocol::MAX_WRITE_BITS,
+            (false, Direction::Write) => protocol::MAX_WRITE_REGISTERS,
+        };
+        if end > 0x1_0000 || end - address as u32 > max as u32 {
+            return Err(error(format!("addresses {}..{} do not fit in one request of at most {}", address, end, max)));
+        }
+        let count = (end - address as u32) as u16;
+        
+        // Writes send the whole range, so it must not have gaps
+        let mut used = vec![false; count as usize];
+        for signal in &config.signals {
+            let start = (signal.address - address) as usize;
+            for slot in &mut used[start..start + words(signal.data_type) as usize] {
+                if *slot {
+                    return Err(error(format!("address overlap at signal '{}'", signal.signal)));
+                }
+                *slot = true;
+            }
+        }
+        if config.direction == Direction::Write {
+            if let Some(gap) = used.iter().position(|used| !used) {
+                return Err(error(format!("address {} is not mapped; write groups must be contiguous", address as usize + gap)));
+            }
+        }
+        
+        let points = config.signals.iter()
+            .map(|mapping| {
+                let signal = bus.register(&mapping.signal);
+                let fault = bus.register(&comm_fault_signal(&mapping.signal));
+                Point {
+                    signal,
+                    fault,
+                    offset: mapping.address - address,
+                    data_type: mapping.data_type,
+                    word_order: mapping.word_order,
+                    template: bus.read(signal).ok(),
+                }
+            })
+            .collect();
+        
+        Ok(Self {
+            table: config.table,
+            direction: config.direction,
+            address,
+            count,
+            points,
+        })
+    }
+    
+    fn read_request(&self) -> Request {
+        let (address, count) = (self.address, self.count);
+        match self.table {
+            Table::Coils => Request::ReadCoils { address, count },
+            Table::DiscreteInputs => Request::ReadDiscreteInputs { address, count },
+            Table::HoldingRegisters => Request::ReadHoldingRegisters { address, count },
+            Table::InputRegisters => Request::ReadInputRegisters { address, count },
+        }
+    }
+    
+    /// Request writing `values` (one per point), or `None` until the scan
+    /// has produced a value for every point
+    fn write_request(&self, values: &[Option<SignalValue>]) -> std::result::Result<Option<Request>, String> {
+        if values.iter().any(Option::is_none) {
+            return Ok(None);
+        }
+        
+        let address = self.address;
+        if self.table.is_bits() {
+            let mut bits = vec![false; self.count as usize];
+            for (point, value) in self.points.iter().zip(values.iter().flatten()) {
+                bits[point.offset as usize] = value.as_bool()
+                    .ok_or_else(|| format!("cannot write {} value as a coil", value.type_name()))?;
+            }
+            Ok(Some(Request::WriteMultipleCoils { address, values: bits }))
+        } else {
+            let mut registers = vec![0u16; self.count as usize];
+            for (point, value) in self.points.iter().zip(values.iter().flatten()) {
+                let words = point.data_type.encode(value, point.word_order).map_err(|e| e.to_string())?;
+                let start = point.offset as usize;
+                registers[start..start + words.len()].copy_from_slice(&words);
+            }
+            Ok(Some(Request::WriteMultipleRegisters { address, values: registers }))
+        }
+    }
+    
+    /// Values for each point from a read response
+    fn decode(&self, response: Response) -> Vec<SignalValue> {
+        self.points.iter()
+            .map(|point| {
+                let start = point.offset as usize;
+                match &response {
+                    Response::Bits(bits) => match point.template {
+                        Some(SignalValue::Bool(_)) | Some(SignalValue::String(_)) | None => SignalValue::Bool(bits[start]),
+                        Some(ref template) => from_number(bits[start] as i32 as f64, Some(template)),
+                    },
+                    Response::Registers(registers) => point.data_type.decode(
+                        &registers[start..start + point.words(self.table) as usize],
+                        point.word_order,
+                        point.template.as_ref(),
+                    ),
+                    _ => unreachable!("read requests get bit or register responses"),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Data shared between the scan and the poll task
+struct Image {
+    /// Per group and point: the last value read, or the next value to write
+    values: Vec<Vec<Option<SignalValue>>>,
+    /// Per group: whether the last exchange failed. Set until the first success.
+    faults: Vec<bool>,
+}
+
+enum Transport {
+    Tcp(String),
+    Rtu(RtuConfig),
+}
+
+enum Link {
+    Tcp { stream: TcpStream, transaction_id: u16 },
+    Rtu(SerialStream),
+}
+
+impl Link {
+    async fn open(transport: &Transport) -> io::Result<Self> {
+        match transport {
+            Transport::Tcp(address) => Ok(Link::Tcp {
+                stream: TcpStream::connect(address).await?,
+                transaction_id: 0,
+            }),
+            Transport::Rtu(rtu) => {
+                let parity = match rtu.parity {
+                    Parity::None => tokio_serial::Parity::None,
+                    Parity::Even => tokio_serial::Parity::Even,
+                    Parity::Odd => tokio_serial::Parity::Odd,
+                };
+                let stop_bits = match rtu.stop_bits {
+                    2 => tokio_serial::StopBits::Two,
+                    _ => tokio_serial::StopBits::One,
+                };
+                let port = tokio_serial::new(&rtu.port, rtu.baud_rate)
+                    .data_bits(tokio_serial::DataBits::Eight)
+                    .parity(parity)
+                    .stop_bits(stop_bits)
+                    .open_native_async()?;
+                Ok(Link::Rtu(port))
+            }
+        }
+    }
+    
+    /// Send a request PDU and return the response PDU
+    async fn transact(&mut self, unit_id: u8, pdu: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Link::Tcp { stream, transaction_id } => {
+                *transaction_id = transaction_id.wrapping_add(1);
+                let header = Header { transaction_id: *transaction_id, unit_id };
+                protocol::write_frame(stream, header, pdu).await?;
+                
+                match protocol::read_frame(stream).await? {
+                    Some((reply, pdu)) if reply == header => Ok(pdu),
+                    Some(_) => Err(io::Error::new(io::ErrorKind::InvalidData, "response does not match the request")),
+                    None => Err(io::ErrorKind::UnexpectedEof.into()),
+                }
+            }
+            Link::Rtu(port) => {
+                port.write_all(&protocol::rtu_frame(unit_id, pdu)).await?;
+                protocol::read_rtu_response(port, unit_id).await
+            }
+        }
+    }
+}
+
+/// Static description of a device, shared with its poll task
+struct Device {
+    name: String,
+    transport: Transport,
+    unit_id: u8,
+    timeout: Duration,
+    retries: u32,
+    poll_period: Duration,
+    groups: Vec<PollGroup>,
+}
+
+impl Device {
+    /// Send a request, reconnecting and retrying on timeouts and I/O errors.
+    /// Exception responses are not retried.
+    async fn exchange(&self, link: &mut Option<Link>, request: &Request) -> std::result::Result<Response, String> {
+        let pdu = request.encode();
+        let mut last_error = String::new();
+        
+        for attempt in 1..=self.retries + 1 {
+            let result = timeout(self.timeout, async {
+                if link.is_none() {
+                    *link = Some(Link::open(&self.transport).await?);
+                }
+                match link.as_mut() {
+                    Some(link) => link.transact(self.unit_id, &pdu).await,
+                    None => Err(io::ErrorKind::NotConnected.into()),
+                }
+            })
+            .await
+            .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::TimedOut, "no response")));
+            
+            match result {
+                Ok(response) => return Response::parse(request, &response),
+                Err(e) => {
+                    debug!("Modbus device '{}': attempt {} failed: {}", self.name, attempt, e);
+                    // A late response would be mistaken for the next one
+                    *link = None;
+                    last_error = e.to_string();
+                }
+            }
+        }
+        
+        Err(format!("{} (after {} attempts)", last_error, self.retries + 1))
+    }
+    
+    async fn poll(self: Arc<Self>, image: Arc<Mutex<Image>>) {
+        let mut link = None;
+        let mut ticker = interval(self.poll_period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        
+        loop {
+            ticker.tick().await;
+            
+            for (index, group) in self.groups.iter().enumerate() {
+                let request = match group.direction {
+                    Direction::Read => Ok(Some(group.read_request())),
+                    Direction::Write => group.write_request(&lock(&image).values[index]),
+                };
+                
+                let result = match request {
+                    Ok(Some(request)) => self.exchange(&mut link, &request).await,
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                };
+                
+                let mut image = lock(&image);
+                let was_faulted = image.faults[index];
+                match result {
+                    Ok(response) => {
+                        if group.direction == Direction::Read {
+                            image.values[index] = group.decode(response).into_iter().map(Some).collect();
+                        }
+                        if was_faulted {
+                            info!("Modbus device '{}': {} at {} communicating", self.name, group.table.name(), group.address);
+                        }
+                        image.faults[index] = false;
+                    }
+                    Err(e) => {
+                        if !was_faulted {
+                            warn!("Modbus device '{}': {} at {} failed: {}", self.name, group.table.name(), group.address, e);
+                        }
+                        image.faults[index] = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The image only holds plain data, so a panic elsewhere cannot leave it inconsistent
+fn lock(image: &Mutex<Image>) -> MutexGuard<'_, Image> {
+    image.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Modbus TCP/RTU client (master) polling one device.
+///
+/// A background task exchanges each poll group with the device every poll
+/// period. `latch_inputs` copies the latest values read onto the bus and
+/// `write_outputs` hands the current values of write groups to the task.
+/// Every mapped signal has a `<signal>_comm_fault` flag that is set while its
+/// group cannot be exchanged, including before the first successful poll;
+/// signals read from the device keep their last good value meanwhile, with
+/// `comm_fault` quality.
+pub struct ModbusClient {
+    device: Arc<Device>,
+    image: Arc<Mutex<Image>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl ModbusClient {
+    pub fn new(config: &ModbusDeviceConfig, bus: &SignalBus, scan_time: Duration) -> Result<Self> {
+        let transport = match (&config.tcp, &config.rtu) {
+            (Some(address), None) => Transport::Tcp(address.clone()),
+            (None, Some(rtu)) => Transport::Rtu(rtu.clone()),
+            _ => return Err(PlcError::ConfigError(format!(
+                "modbus device '{}': exactly one of 'tcp' or 'rtu' is required", config.name
+            ))),
+        };
+        
+        let groups = config.poll_groups.iter()
+            .map(|group| PollGroup::build(&config.name, group, bus))
+            .collect::<Result<Vec<_>>>()?;
+        
+        for point in groups.iter().flat_map(|group| &group.points) {
+            bus.write(point.fault, SignalValue::Bool(true))?;
+        }
+        
+        let image = Image {
+            values: groups.iter().map(|group| vec![None; group.points.len()]).collect(),
+            faults: vec![true; groups.len()],
+        };
+        
+        let device = Device {
+            name: config.name.clone(),
+            transport,
+            unit_id: config.unit_id,
+            timeout: Duration::from_millis(config.timeout_ms),
+            retries: config.retries,
+            poll_period: config.poll_ms.map(Duration::from_millis).unwrap_or(scan_time).max(Duration::from_millis(1)),
+            groups,
+        };
+        
+        Ok(Self {
+            device: Arc::new(device),
+            image: Arc::new(Mutex::new(image)),
+            task: None,
+        })
+    }
+}
+
+impl IoDriver for ModbusClient {
+    fn name(&self) -> &str {
+        &self.device.name
+    }
+    
+    fn start(&mut self) {
+        if self.task.is_none() {
+            self.task = Some(tokio::spawn(self.device.clone().poll(self.image.clone())));
+        }
+    }
+    
+    fn latch_inputs(&mut self, bus: &SignalBus) -> Result<()> {
+        let image = lock(&self.image);
+        for (index, group) in self.device.groups.iter().enumerate() {
+            let quality = if image.faults[index] { Quality::CommFault } else { Quality::Good };
+            for (point, value) in group.points.iter().zip(&image.values[index]) {
+                let mut fault = image.faults[index];
+                match (group.direction, value) {
+                    (Direction::Read, Some(value)) => {
+                        // A value the signal cannot take faults this point only
+                        if let Err(e) = bus.write_with_quality(point.signal, value.clone(), quality) {
+                            debug!("Device '{}': latching {} failed: {}", self.device.name, bus.name(point.signal), e);
+                            bus.write_quality(point.signal, Quality::CommFault).ok();
+                            fault = true;
+                        }
+                    }
+                    // Nothing read yet: mark whatever initial value the signal has
+                    (Direction::Read, None) => {
+                        bus.write_quality(point.signal, quality).ok();
+                    }
+                    (Direction::Write, _) => {}
+                }
+                bus.write(point.fault, SignalValue::Bool(fault))?;
+            }
+        }
+        Ok(())
+    }
+    
+    fn write_outputs(&mut self, bus: &SignalBus) -> Result<()> {
+        let mut image = lock(&self.image);
+        for (index, group) in self.device.groups.iter().enumerate() {
+            if group.direction == Direction::Write {
+                image.values[index] = group.points.iter().map(|point| bus.read(point.signal).ok()).collect();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ModbusClient {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
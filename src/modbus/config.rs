@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use super::types::{RegisterType, WordOrder};
+
+/// `modbus_server` section of a PLC configuration
+///
+/// ```yaml
+/// modbus_server:
+///   bind: "0.0.0.0:502"
+///   coils:
+///     - { address: 0, signal: "motor_run" }
+///   holding_registers:
+///     - { address: 0, signal: "speed_setpoint", type: "float32", word_order: "little" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusServerConfig {
+    #[serde(default = "default_bind")]
+    pub bind: String,
+    /// Only answer requests for this unit id. Any unit id is accepted when unset.
+    #[serde(default)]
+    pub unit_id: Option<u8>,
+    /// Read/write bits
+    #[serde(default)]
+    pub coils: Vec<BitMapping>,
+    /// Read-only bits
+    #[serde(default)]
+    pub discrete_inputs: Vec<BitMapping>,
+    /// Read/write registers
+    #[serde(default)]
+    pub holding_registers: Vec<RegisterMapping>,
+    /// Read-only registers
+    #[serde(default)]
+    pub input_registers: Vec<RegisterMapping>,
+}
+
+fn default_bind() -> String {
+    "0.0.0.0:502".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitMapping {
+    pub address: u16,
+    pub signal: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterMapping {
+    /// First register; 32-bit types also occupy `address + 1`
+    pub address: u16,
+    pub signal: String,
+    #[serde(rename = "type", default)]
+    pub data_type: RegisterType,
+    #[serde(default)]
+    pub word_order: WordOrder,
+}
+
+impl ModbusServerConfig {
+    /// Every mapping as (table, address, signal), for diagnostics
+    pub fn mappings(&self) -> Vec<(&'static str, u16, &str)> {
+        let mut mappings = Vec::new();
+        mappings.extend(self.coils.iter().map(|m| ("coil", m.address, m.signal.as_str())));
+        mappings.extend(self.discrete_inputs.iter().map(|m| ("discrete input", m.address, m.signal.as_str())));
+        mappings.extend(self.holding_registers.iter().map(|m| ("holding register", m.address, m.signal.as_str())));
+        mappings.extend(self.input_registers.iter().map(|m| ("input register", m.address, m.signal.as_str())));
+        mappings
+    }
+}
+
+/// One entry of the `modbus_devices` section: a remote slave the PLC polls
+///
+/// ```yaml
+/// modbus_devices:
+///   - name: "flow_meter"
+///     tcp: "192.168.1.20:502"
+///     timeout_ms: 200
+///     retries: 2
+///     poll_groups:
+///       - table: "input_registers"
+///         signals:
+///           - { address: 0, signal: "flow", type: "float32" }
+///       - table: "coils"
+///         direction: "write"
+///         signals:
+///           - { address: 0, signal: "pump_cmd" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModbusDeviceConfig {
+    pub name: String,
+    /// `host:port` of a Modbus TCP device
+    #[serde(default)]
+    pub tcp: Option<String>,
+    /// Serial line of a Modbus RTU device
+    #[serde(default)]
+    pub rtu: Option<RtuConfig>,
+    #[serde(default = "default_unit_id")]
+    pub unit_id: u8,
+    /// How long to wait for each response
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Extra attempts after a request times out or fails
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Poll period; defaults to the scan time
+    #[serde(default)]
+    pub poll_ms: Option<u64>,
+    #[serde(default)]
+    pub poll_groups: Vec<PollGroupConfig>,
+}
+
+fn default_unit_id() -> u8 {
+    1
+}
+
+fn default_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RtuConfig {
+    pub port: String,
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+    #[serde(default)]
+    pub parity: Parity,
+    #[serde(default = "default_stop_bits")]
+    pub stop_bits: u8,
+}
+
+fn default_baud_rate() -> u32 {
+    9600
+}
+
+fn default_stop_bits() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Parity {
+    #[default]
+    None,
+    Even,
+    Odd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Table {
+    Coils,
+    DiscreteInputs,
+    HoldingRegisters,
+    InputRegisters,
+}
+
+impl Table {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Table::Coils => "coils",
+            Table::DiscreteInputs => "discrete_inputs",
+            Table::HoldingRegisters => "holding_registers",
+            Table::InputRegisters => "input_registers",
+        }
+    }
+    
+    pub fn is_bits(&self) -> bool {
+        matches!(self, Table::Coils | Table::DiscreteInputs)
+    }
+}
+
+/// Whether a poll group is latched into the PLC before logic runs, or
+/// written to the device after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Read,
+    Write,
+}
+
+/// A contiguous range of one table, transferred with a single request.
+/// `type` and `word_order` of the signals only apply to register tables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollGroupConfig {
+    pub table: Table,
+    #[serde(default)]
+    pub direction: Direction,
+    pub signals: Vec<RegisterMapping>,
+}
+
+/// Name of the flag raised while `signal` cannot be exchanged with its device
+pub fn comm_fault_signal(signal: &str) -> String {
+    format!("{}_comm_fault", signal)
+}
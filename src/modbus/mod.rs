@@ -0,0 +1,11 @@
+mod client;
+mod config;
+mod protocol;
+mod server;
+mod types;
+
+pub use client::ModbusClient;
+pub use config::{ModbusServerConfig, BitMapping, RegisterMapping};
+pub use config::{ModbusDeviceConfig, PollGroupConfig, RtuConfig, Parity, Table, Direction, comm_fault_signal};
+pub use server::ModbusServer;
+pub use types::{RegisterType, WordOrder};
@@ -0,0 +1,374 @@
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+pub const READ_COILS: u8 = 0x01;
+pub const READ_DISCRETE_INPUTS: u8 = 0x02;
+pub const READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const READ_INPUT_REGISTERS: u8 = 0x04;
+pub const WRITE_SINGLE_COIL: u8 = 0x05;
+pub const WRITE_SINGLE_REGISTER: u8 = 0x06;
+pub const WRITE_MULTIPLE_COILS: u8 = 0x0F;
+pub const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/// Largest quantities a single request may carry (Modbus application protocol v1.1b3)
+pub const MAX_READ_BITS: u16 = 2000;
+pub const MAX_READ_REGISTERS: u16 = 125;
+pub const MAX_WRITE_BITS: u16 = 1968;
+pub const MAX_WRITE_REGISTERS: u16 = 123;
+
+/// Exception codes returned in place of a normal response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exception {
+    IllegalFunction = 0x01,
+    IllegalDataAddress = 0x02,
+    IllegalDataValue = 0x03,
+    ServerDeviceFailure = 0x04,
+}
+
+/// A decoded request PDU
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    ReadCoils { address: u16, count: u16 },
+    ReadDiscreteInputs { address: u16, count: u16 },
+    ReadHoldingRegisters { address: u16, count: u16 },
+    ReadInputRegisters { address: u16, count: u16 },
+    WriteSingleCoil { address: u16, value: bool },
+    WriteSingleRegister { address: u16, value: u16 },
+    WriteMultipleCoils { address: u16, values: Vec<bool> },
+    WriteMultipleRegisters { address: u16, values: Vec<u16> },
+}
+
+/// Response payload for a request that succeeded
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    Bits(Vec<bool>),
+    Registers(Vec<u16>),
+    /// Single writes echo the request
+    WriteSingle { address: u16, value: u16 },
+    /// Multiple writes report the starting address and quantity written
+    WriteMultiple { address: u16, count: u16 },
+}
+
+/// Pack bits LSB first, as coil and discrete input data is sent
+fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+    for (i, _) in bits.iter().enumerate().filter(|(_, &bit)| bit) {
+        bytes[i / 8] |= 1 << (i % 8);
+    }
+    bytes
+}
+
+fn word(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*bytes.get(offset)?, *bytes.get(offset + 1)?]))
+}
+
+/// Reject empty reads and reads that run past the end of the address space
+fn checked_range(address: u16, count: u16, max: u16) -> Result<(), Exception> {
+    if count == 0 || count > max {
+        return Err(Exception::IllegalDataValue);
+    }
+    if address as u32 + count as u32 > 0x1_0000 {
+        return Err(Exception::IllegalDataAddress);
+    }
+    Ok(())
+}
+
+impl Request {
+    pub fn parse(pdu: &[u8]) -> Result<Self, Exception> {
+        let function = *pdu.first().ok_or(Exception::IllegalFunction)?;
+        if !matches!(function, READ_COILS..=WRITE_SINGLE_REGISTER | WRITE_MULTIPLE_COILS | WRITE_MULTIPLE_REGISTERS) {
+            return Err(Exception::IllegalFunction);
+        }
+        
+        let address = word(pdu, 1).ok_or(Exception::IllegalDataValue)?;
+        let field = word(pdu, 3).ok_or(Exception::IllegalDataValue)?;
+        
+        match function {
+            READ_COILS | READ_DISCRETE_INPUTS => {
+                checked_range(address, field, MAX_READ_BITS)?;
+                Ok(if function == READ_COILS {
+                    Request::ReadCoils { address, count: field }
+                } else {
+                    Request::ReadDiscreteInputs { address, count: field }
+                })
+            }
+            READ_HOLDING_REGISTERS | READ_INPUT_REGISTERS => {
+                checked_range(address, field, MAX_READ_REGISTERS)?;
+                Ok(if function == READ_HOLDING_REGISTERS {
+                    Request::ReadHoldingRegisters { address, count: field }
+                } else {
+                    Request::ReadInputRegisters { address, count: field }
+                })
+            }
+            WRITE_SINGLE_COIL => match field {
+                0xFF00 => Ok(Request::WriteSingleCoil { address, value: true }),
+                0x0000 => Ok(Request::WriteSingleCoil { address, value: false }),
+                _ => Err(Exception::IllegalDataValue),
+            },
+            WRITE_SINGLE_REGISTER => Ok(Request::WriteSingleRegister { address, value: field }),
+            WRITE_MULTIPLE_COILS => {
+                checked_range(address, field, MAX_WRITE_BITS)?;
+                let data = pdu.get(6..).ok_or(Exception::IllegalDataValue)?;
+                let byte_count = *pdu.get(5).ok_or(Exception::IllegalDataValue)? as usize;
+                if byte_count != (field as usize).div_ceil(8) || data.len() != byte_count {
+                    return Err(Exception::IllegalDataValue);
+                }
+                let values = (0..field as usize)
+                    .map(|i| data[i / 8] & (1 << (i % 8)) != 0)
+                    .collect();
+                Ok(Request::WriteMultipleCoils { address, values })
+            }
+            WRITE_MULTIPLE_REGISTERS => {
+                checked_range(address, field, MAX_WRITE_REGISTERS)?;
+                let data = pdu.get(6..).ok_or(Exception::IllegalDataValue)?;
+                let byte_count = *pdu.get(5).ok_or(Exception::IllegalDataValue)? as usize;
+                if byte_count != field as usize * 2 || data.len() != byte_count {
+                    return Err(Exception::IllegalDataValue);
+                }
+                let values = data.chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                Ok(Request::WriteMultipleRegisters { address, values })
+            }
+            _ => unreachable!("function code checked above"),
+        }
+    }
+    
+    pub fn function_code(&self) -> u8 {
+        match self {
+            Request::ReadCoils { .. } => READ_COILS,
+            Request::ReadDiscreteInputs { .. } => READ_DISCRETE_INPUTS,
+            Request::ReadHoldingRegisters { .. } => READ_HOLDING_REGISTERS,
+            Request::ReadInputRegisters { .. } => READ_INPUT_REGISTERS,
+            Request::WriteSingleCoil { .. } => WRITE_SINGLE_COIL,
+            Request::WriteSingleRegister { .. } => WRITE_SINGLE_REGISTER,
+            Request::WriteMultipleCoils { .. } => WRITE_MULTIPLE_COILS,
+            Request::WriteMultipleRegisters { .. } => WRITE_MULTIPLE_REGISTERS,
+        }
+    }
+    
+    pub fn encode(&self) -> Vec<u8> {
+        let mut pdu = vec![self.function_code()];
+        match self {
+            Request::ReadCoils { address, count }
+            | Request::ReadDiscreteInputs { address, count }
+            | Request::ReadHoldingRegisters { address, count }
+            | Request::ReadInputRegisters { address, count } => {
+                pdu.extend(address.to_be_bytes());
+                pdu.extend(count.to_be_bytes());
+            }
+            Request::WriteSingleCoil { address, value } => {
+                pdu.extend(address.to_be_bytes());
+                pdu.extend(if *value { [0xFF, 0x00] } else { [0x00, 0x00] });
+            }
+            Request::WriteSingleRegister { address, value } => {
+                pdu.extend(address.to_be_bytes());
+                pdu.extend(value.to_be_bytes());
+            }
+            Request::WriteMultipleCoils { address, values } => {
+                pdu.extend(address.to_be_bytes());
+                pdu.extend((values.len() as u16).to_be_bytes());
+                let bytes = pack_bits(values);
+                pdu.push(bytes.len() as u8);
+                pdu.extend(bytes);
+            }
+            Request::WriteMultipleRegisters { address, values } => {
+                pdu.extend(address.to_be_bytes());
+                pdu.extend((values.len() as u16).to_be_bytes());
+                pdu.push((values.len() * 2) as u8);
+                pdu.extend(values.iter().flat_map(|v| v.to_be_bytes()));
+            }
+        }
+        pdu
+    }
+}
+
+impl Response {
+    pub fn encode(&self, function: u8) -> Vec<u8> {
+        let mut pdu = vec![function];
+        match self {
+            Response::Bits(bits) => {
+                let bytes = pack_bits(bits);
+                pdu.push(bytes.len() as u8);
+                pdu.extend(bytes);
+            }
+            Response::Registers(registers) => {
+                pdu.push((registers.len() * 2) as u8);
+                pdu.extend(registers.iter().flat_map(|r| r.to_be_bytes()));
+            }
+            Response::WriteSingle { address, value } => {
+                pdu.extend(address.to_be_bytes());
+                pdu.extend(value.to_be_bytes());
+            }
+            Response::WriteMultiple { address, count } => {
+                pdu.extend(address.to_be_bytes());
+                pdu.extend(count.to_be_bytes());
+            }
+        }
+        pdu
+    }
+    
+    /// Decode the response a server sent for `request`. Exception responses
+    /// and responses that do not match the request are errors.
+    pub fn parse(request: &Request, pdu: &[u8]) -> std::result::Result<Self, String> {
+        let function = request.function_code();
+        match pdu.first() {
+            Some(&code) if code == function | 0x80 => {
+                return Err(match pdu.get(1) {
+                    Some(exception) => format!("exception {:#04x} for function {:#04x}", exception, function),
+                    None => "truncated exception response".to_string(),
+                });
+            }
+            Some(&code) if code == function => {}
+            Some(&code) => return Err(format!("function {:#04x} in response to {:#04x}", code, function)),
+            None => return Err("empty response".to_string()),
+        }
+        
+        let data = pdu.get(2..).unwrap_or_default();
+        let byte_count = pdu.get(1).copied().unwrap_or_default() as usize;
+        let echoes = |address: u16, field: u16| match (word(pdu, 1), word(pdu, 3)) {
+            (Some(a), Some(f)) if a == address && f == field => Ok(()),
+            _ => Err("write response does not match the request".to_string()),
+        };
+        
+        match request {
+            Request::ReadCoils { count, .. } | Request::ReadDiscreteInputs { count, .. } => {
+                if byte_count != (*count as usize).div_ceil(8) || data.len() != byte_count {
+                    return Err("bit count does not match request".to_string());
+                }
+                Ok(Response::Bits((0..*count as usize).map(|i| data[i / 8] & (1 << (i % 8)) != 0).collect()))
+            }
+            Request::ReadHoldingRegisters { count, .. } | Request::ReadInputRegisters { count, .. } => {
+                if byte_count != *count as usize * 2 || data.len() != byte_count {
+                    return Err("register count does not match request".to_string());
+                }
+                Ok(Response::Registers(data.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect()))
+            }
+            // Single writes echo the request, multiple writes its address and quantity
+            Request::WriteSingleCoil { address, value } => {
+                let value = if *value { 0xFF00 } else { 0 };
+                echoes(*address, value).map(|_| Response::WriteSingle { address: *address, value })
+            }
+            Request::WriteSingleRegister { address, value } => {
+                echoes(*address, *value).map(|_| Response::WriteSingle { address: *address, value: *value })
+            }
+            Request::WriteMultipleCoils { address, values } => {
+                let count = values.len() as u16;
+                echoes(*address, count).map(|_| Response::WriteMultiple { address: *address, count })
+            }
+            Request::WriteMultipleRegisters { address, values } => {
+                let count = values.len() as u16;
+                echoes(*address, count).map(|_| Response::WriteMultiple { address: *address, count })
+            }
+        }
+    }
+}
+
+impl Exception {
+    pub fn encode(&self, function: u8) -> Vec<u8> {
+        vec![function | 0x80, *self as u8]
+    }
+}
+
+/// MBAP header that prefixes every Modbus TCP frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub transaction_id: u16,
+    pub unit_id: u8,
+}
+
+/// Largest PDU that fits in a Modbus TCP frame
+pub const MAX_PDU_LEN: usize = 253;
+
+/// Read one frame. Returns `None` when the peer closed the connection
+/// between frames, and an error for a malformed header.
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Option<(Header, Vec<u8>)>> {
+    let mut mbap = [0u8; 7];
+    match reader.read_exact(&mut mbap).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    
+    let transaction_id = u16::from_be_bytes([mbap[0], mbap[1]]);
+    let protocol_id = u16::from_be_bytes([mbap[2], mbap[3]]);
+    let length = u16::from_be_bytes([mbap[4], mbap[5]]) as usize;
+    
+    // Length counts the unit id byte as well as the PDU
+    if protocol_id != 0 || length < 2 || length - 1 > MAX_PDU_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid MBAP header (protocol {}, length {})", protocol_id, length),
+        ));
+    }
+    
+    let mut pdu = vec![0u8; length - 1];
+    reader.read_exact(&mut pdu).await?;
+    
+    Ok(Some((Header { transaction_id, unit_id: mbap[6] }, pdu)))
+}
+
+pub async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, header: Header, pdu: &[u8]) -> std::io::Result<()> {
+    let mut frame = Vec::with_capacity(7 + pdu.len());
+    frame.extend(header.transaction_id.to_be_bytes());
+    frame.extend(0u16.to_be_bytes());
+    frame.extend((pdu.len() as u16 + 1).to_be_bytes());
+    frame.push(header.unit_id);
+    frame.extend(pdu);
+    writer.write_all(&frame).await
+}
+
+/// CRC-16/MODBUS of an RTU frame
+pub fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for &byte in bytes {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xA001 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+/// Build an RTU frame: unit id, PDU, CRC low byte first
+pub fn rtu_frame(unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(pdu.len() + 3);
+    frame.push(unit_id);
+    frame.extend(pdu);
+    frame.extend(crc16(&frame).to_le_bytes());
+    frame
+}
+
+/// Read an RTU response frame and return its PDU. RTU has no length field,
+/// so the length is worked out from the function code as the frame arrives.
+pub async fn read_rtu_response<R: AsyncRead + Unpin>(reader: &mut R, unit_id: u8) -> std::io::Result<Vec<u8>> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+    
+    let mut frame = vec![0u8; 2];
+    reader.read_exact(&mut frame).await?;
+    
+    let remaining = match frame[1] {
+        code if code & 0x80 != 0 => 1,
+        READ_COILS..=READ_INPUT_REGISTERS => {
+            let mut byte_count = [0u8; 1];
+            reader.read_exact(&mut byte_count).await?;
+            frame.push(byte_count[0]);
+            byte_count[0] as usize
+        }
+        WRITE_SINGLE_COIL | WRITE_SINGLE_REGISTER | WRITE_MULTIPLE_COILS | WRITE_MULTIPLE_REGISTERS => 4,
+        code => return Err(invalid(format!("unexpected function {:#04x} in RTU response", code))),
+    };
+    
+    let start = frame.len();
+    frame.resize(start + remaining + 2, 0);
+    reader.read_exact(&mut frame[start..]).await?;
+    
+    let (body, crc) = frame.split_at(frame.len() - 2);
+    if crc16(body).to_le_bytes() != crc {
+        return Err(invalid("RTU response CRC mismatch".to_string()));
+    }
+    if body[0] != unit_id {
+        return Err(invalid(format!("RTU response from unit {} instead of {}", body[0], unit_id)));
+    }
+    
+    Ok(body[1..].to_vec())
+}
@@ -0,0 +1,262 @@
+use crate::{Result, PlcError, signal::{SignalBus, SignalId, SignalValue}};
+use super::config::{BitMapping, ModbusServerConfig, RegisterMapping};
+use super::protocol::{self, Exception, Request, Response};
+use super::types::{from_number, RegisterType, WordOrder};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn, debug};
+
+/// A signal mapped onto one or two consecutive registers
+struct Register {
+    signal: SignalId,
+    data_type: RegisterType,
+    word_order: WordOrder,
+    address: u16,
+}
+
+#[derive(Default)]
+struct RegisterTable {
+    registers: Vec<Register>,
+    /// Every occupied address, pointing into `registers`
+    addresses: HashMap<u16, usize>,
+}
+
+impl RegisterTable {
+    fn build(table: &str, mappings: &[RegisterMapping], bus: &SignalBus) -> Result<Self> {
+        let mut registers = RegisterTable::default();
+        
+        for mapping in mappings {
+            let last = mapping.address as u32 + mapping.data_type.words() as u32 - 1;
+            if last > u16::MAX as u32 {
+                return Err(PlcError::ConfigError(format!(
+                    "modbus_server: {} {} ({}) runs past the last address",
+                    table, mapping.address, mapping.data_type.name()
+                )));
+            }
+            
+            for address in mapping.address..=last as u16 {
+                if let Some(&other) = registers.addresses.get(&address) {
+                    return Err(PlcError::ConfigError(format!(
+                        "modbus_server: {} {} is used by both '{}' and '{}'",
+                        table, address, mappings[other].signal, mapping.signal
+                    )));
+                }
+                registers.addresses.insert(address, registers.registers.len());
+            }
+            
+            registers.registers.push(Register {
+                signal: bus.register(&mapping.signal),
+                data_type: mapping.data_type,
+                word_order: mapping.word_order,
+                address: mapping.address,
+            });
+        }
+        
+        Ok(registers)
+    }
+}
+
+fn bit_table(table: &str, mappings: &[BitMapping], bus: &SignalBus) -> Result<HashMap<u16, SignalId>> {
+    let mut bits = HashMap::new();
+    for mapping in mappings {
+        if bits.insert(mapping.address, bus.register(&mapping.signal)).is_some() {
+            return Err(PlcError::ConfigError(format!(
+                "modbus_server: {} {} is mapped more than once", table, mapping.address
+            )));
+        }
+    }
+    Ok(bits)
+}
+
+/// `count` consecutive addresses from `start`. The request parser has already
+/// checked that they fit in the address space.
+fn addresses(start: u16, count: usize) -> impl Iterator<Item = u16> {
+    (start as u32..start as u32 + count as u32).map(|address| address as u16)
+}
+
+struct Tables {
+    coils: HashMap<u16, SignalId>,
+    discrete_inputs: HashMap<u16, SignalId>,
+    holding_registers: RegisterTable,
+    input_registers: RegisterTable,
+}
+
+/// Modbus TCP server (slave) exposing signals as coils and registers.
+///
+/// Requests are served straight from the `SignalBus`, so clients see the
+/// values published by the last scan and their writes are applied at the
+/// start of the next one. Accesses to
+/// unmapped addresses are answered with an illegal data address exception.
+#[derive(Clone)]
+pub struct ModbusServer {
+    bind: String,
+    unit_id: Option<u8>,
+    bus: SignalBus,
+    tables: Arc<Tables>,
+}
+
+impl ModbusServer {
+    pub fn new(config: &ModbusServerConfig, bus: SignalBus) -> Result<Self> {
+        let tables = Tables {
+            coils: bit_table("coil", &config.coils, &bus)?,
+            discrete_inputs: bit_table("discrete input", &config.discrete_inputs, &bus)?,
+            holding_registers: RegisterTable::build("holding register", &config.holding_registers, &bus)?,
+            input_registers: RegisterTable::build("input register", &config.input_registers, &bus)?,
+        };
+        
+        Ok(Self {
+            bind: config.bind.clone(),
+            unit_id: config.unit_id,
+            bus,
+            tables: Arc::new(tables),
+        })
+    }
+    
+    /// Listen on the configured address until an accept fails
+    pub async fn run(self) -> Result<()> {
+        let listener = TcpListener::bind(&self.bind).await?;
+        self.serve(listener).await
+    }
+    
+    /// Serve clients connecting to an already bound listener
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        info!("Modbus TCP server listening on {}", listener.local_addr()?);
+        
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            debug!("Modbus client connected from {}", peer);
+            
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.connection(stream).await {
+                    warn!("Modbus connection from {} closed: {}", peer, e);
+                }
+            });
+        }
+    }
+    
+    async fn connection(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        while let Some((header, pdu)) = protocol::read_frame(&mut stream).await? {
+            if self.unit_id.is_some_and(|unit| unit != header.unit_id) {
+                debug!("Ignoring Modbus request for unit {}", header.unit_id);
+                continue;
+            }
+            
+            let function = pdu[0];
+            let reply = match Request::parse(&pdu).and_then(|request| self.handle(request)) {
+                Ok(response) => response.encode(function),
+                Err(exception) => exception.encode(function),
+            };
+            
+            protocol::write_frame(&mut stream, header, &reply).await?;
+        }
+        Ok(())
+    }
+    
+    fn handle(&self, request: Request) -> std::result::Result<Response, Exception> {
+        let tables = &self.tables;
+        match request {
+            Request::ReadCoils { address, count } => self.read_bits(&tables.coils, address, count),
+            Request::ReadDiscreteInputs { address, count } => self.read_bits(&tables.discrete_inputs, address, count),
+            Request::ReadHoldingRegisters { address, count } => self.read_registers(&tables.holding_registers, address, count),
+            Request::ReadInputRegisters { address, count } => self.read_registers(&tables.input_registers, address, count),
+            Request::WriteSingleCoil { address, value } => {
+                self.write_bits(address, &[value])?;
+                Ok(Response::WriteSingle { address, value: if value { 0xFF00 } else { 0 } })
+            }
+            Request::WriteSingleRegister { address, value } => {
+                self.write_registers(address, &[value])?;
+                Ok(Response::WriteSingle { address, value })
+            }
+            Request::WriteMultipleCoils { address, values } => {
+                self.write_bits(address, &values)?;
+                Ok(Response::WriteMultiple { address, count: values.len() as u16 })
+            }
+            Request::WriteMultipleRegisters { address, values } => {
+                self.write_registers(address, &values)?;
+                Ok(Response::WriteMultiple { address, count: values.len() as u16 })
+            }
+        }
+    }
+    
+    fn read_bits(&self, table: &HashMap<u16, SignalId>, address: u16, count: u16) -> std::result::Result<Response, Exception> {
+        let bits = addresses(address, count as usize)
+            .map(|address| {
+                let signal = table.get(&address).ok_or(Exception::IllegalDataAddress)?;
+                self.bus.read(*signal).ok()
+                    .and_then(|value| value.as_bool())
+                    .ok_or(Exception::ServerDeviceFailure)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(Response::Bits(bits))
+    }
+    
+    fn read_registers(&self, table: &RegisterTable, address: u16, count: u16) -> std::result::Result<Response, Exception> {
+        let mut encoded: HashMap<usize, Vec<u16>> = HashMap::new();
+        let mut words = Vec::with_capacity(count as usize);
+        
+        for address in addresses(address, count as usize) {
+            let &index = table.addresses.get(&address).ok_or(Exception::IllegalDataAddress)?;
+            let register = &table.registers[index];
+            
+            let value = match encoded.entry(index) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let value = self.bus.read(register.signal).map_err(|_| Exception::ServerDeviceFailure)?;
+                    entry.insert(register.data_type.encode(&value, register.word_order)
+                        .map_err(|_| Exception::ServerDeviceFailure)?)
+                }
+            };
+            
+            words.push(value[(address - register.address) as usize]);
+        }
+        
+        Ok(Response::Registers(words))
+    }
+    
+    /// Write coils, keeping each signal's type. Nothing is written unless
+    /// every address is mapped.
+    fn write_bits(&self, address: u16, values: &[bool]) -> std::result::Result<(), Exception> {
+        let signals = addresses(address, values.len())
+            .map(|address| self.tables.coils.get(&address).copied().ok_or(Exception::IllegalDataAddress))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        
+        for (signal, &value) in signals.into_iter().zip(values) {
+            let value = match self.bus.read(signal) {
+                Ok(SignalValue::Bool(_)) | Ok(SignalValue::String(_)) | Err(_) => SignalValue::Bool(value),
+                Ok(current) => from_number(value as i32 as f64, Some(&current)),
+            };
+            self.bus.write(signal, value).map_err(|_| Exception::ServerDeviceFailure)?;
+        }
+        Ok(())
+    }
+    
+    /// Write holding registers. A write covering only one word of a 32-bit
+    /// value is merged with the other word's current contents, including
+    /// writes still queued for the next scan.
+    fn write_registers(&self, address: u16, values: &[u16]) -> std::result::Result<(), Exception> {
+        let table = &self.tables.holding_registers;
+        let mut pending: BTreeMap<usize, Vec<u16>> = BTreeMap::new();
+        
+        for (address, &value) in addresses(address, values.len()).zip(values) {
+            let &index = table.addresses.get(&address).ok_or(Exception::IllegalDataAddress)?;
+            let register = &table.registers[index];
+            
+            let words = pending.entry(index).or_insert_with(|| {
+                self.bus.read_pending(register.signal).ok()
+                    .and_then(|current| register.data_type.encode(&current, register.word_order).ok())
+                    .unwrap_or_else(|| vec![0; register.data_type.words() as usize])
+            });
+            words[(address - register.address) as usize] = value;
+        }
+        
+        for (index, words) in pending {
+            let register = &table.registers[index];
+            let current = self.bus.read(register.signal).ok();
+            let value = register.data_type.decode(&words, register.word_order, current.as_ref());
+            self.bus.write(register.signal, value).map_err(|_| Exception::ServerDeviceFailure)?;
+        }
+        Ok(())
+    }
+}
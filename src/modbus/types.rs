@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use crate::{Result, PlcError, signal::{SignalType, SignalValue}};
+
+/// How a signal is laid out in 16-bit registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegisterType {
+    #[default]
+    Int16,
+    Uint16,
+    Int32,
+    Float32,
+}
+
+/// Order of the two registers of a 32-bit value. Bytes within a register are
+/// always big-endian, as Modbus requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WordOrder {
+    /// High word at the lower address (ABCD)
+    #[default]
+    Big,
+    /// Low word at the lower address (CDAB)
+    Little,
+}
+
+impl RegisterType {
+    /// Number of registers a value occupies
+    pub fn words(&self) -> u16 {
+        match self {
+            RegisterType::Int16 | RegisterType::Uint16 => 1,
+            RegisterType::Int32 | RegisterType::Float32 => 2,
+        }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            RegisterType::Int16 => "int16",
+            RegisterType::Uint16 => "uint16",
+            RegisterType::Int32 => "int32",
+            RegisterType::Float32 => "float32",
+        }
+    }
+    
+    /// Encode a signal value into registers. Integers saturate at the limits
+    /// of the register type and floats are rounded when stored as integers.
+    pub fn encode(&self, value: &SignalValue, order: WordOrder) -> Result<Vec<u16>> {
+        let number = match value {
+            SignalValue::Bool(b) => *b as i32 as f64,
+            SignalValue::String(_) => return Err(PlcError::TypeMismatch {
+                expected: "numeric".to_string(),
+                actual: value.type_name().to_string(),
+            }),
+            _ => value.as_float().or_else(|| value.as_millis().map(|ms| ms as f64)).unwrap_or_default(),
+        };
+        
+        let (high, low) = match self {
+            RegisterType::Int16 => return Ok(vec![number.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16 as u16]),
+            RegisterType::Uint16 => return Ok(vec![number.round().clamp(0.0, u16::MAX as f64) as u16]),
+            RegisterType::Int32 => {
+                let bits = match value.as_i128() {
+                    Some(i) => i.clamp(i32::MIN as i128, i32::MAX as i128) as i32,
+                    None => number.round().clamp(i32::MIN as f64, i32::MAX as f64) as i32,
+                } as u32;
+                ((bits >> 16) as u16, bits as u16)
+            }
+            RegisterType::Float32 => {
+                let bits = (number as f32).to_bits();
+                ((bits >> 16) as u16, bits as u16)
+            }
+        };
+        
+        Ok(match order {
+            WordOrder::Big => vec![high, low],
+            WordOrder::Little => vec![low, high],
+        })
+    }
+    
+    /// Decode registers into a value of the same type as `current`, so a
+    /// write from a client never changes a signal's type
+    pub fn decode(&self, words: &[u16], order: WordOrder, current: Option<&SignalValue>) -> SignalValue {
+        let number = match self {
+            RegisterType::Int16 => words[0] as i16 as f64,
+            RegisterType::Uint16 => words[0] as f64,
+            RegisterType::Int32 | RegisterType::Float32 => {
+                let (high, low) = match order {
+                    WordOrder::Big => (words[0], words[1]),
+                    WordOrder::Little => (words[1], words[0]),
+                };
+                let bits = (high as u32) << 16 | low as u32;
+                if *self == RegisterType::Int32 {
+                    bits as i32 as f64
+                } else {
+                    f32::from_bits(bits) as f64
+                }
+            }
+        };
+        
+        match current {
+            None | Some(SignalValue::String(_)) if *self == RegisterType::Float32 => SignalValue::Float(number),
+            current => from_number(number, current),
+        }
+    }
+}
+
+/// Value of the same type as `current` for a number read from a register or
+/// coil. Integers are rounded and saturate at the limits of their type.
+pub(crate) fn from_number(number: f64, current: Option<&SignalValue>) -> SignalValue {
+    let Some(signal_type) = current.map(SignalValue::signal_type) else {
+        return SignalValue::Int(number as i32);
+    };
+    match signal_type {
+        SignalType::Bool => SignalValue::Bool(number != 0.0),
+        SignalType::Float => SignalValue::Float(number),
+        SignalType::Real => SignalValue::Real(number as f32),
+        signal_type => match signal_type.integer_range() {
+            Some((min, max)) => {
+                let clamped = (number.round() as i128).clamp(min, max);
+                SignalValue::from_integer(clamped, signal_type).unwrap_or(SignalValue::Int(number as i32))
+            }
+            None => SignalValue::Int(number as i32),
+        },
+    }
+}
@@ -1,30 +1,548 @@
-use dashmap::DashMap;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::SystemTime;
+use tokio::sync::{broadcast, watch};
+use tracing::warn;
 use crate::{PlcError, Result};
-use super::SignalValue;
+use super::{ChangeSet, Force, Quality, Sample, SignalChange, SignalType, SignalValue, Subscription, FORCES_ACTIVE};
 
+/// Change sets a receiver of `SignalBus::changes` can fall behind by before
+/// it misses some
+pub const CHANGE_BUFFER: usize = 256;
+
+/// Dense handle to a signal slot on a `SignalBus`.
+///
+/// Blocks resolve their signal names to handles once when they are created,
+/// so a scan reads and writes by index instead of hashing names. A handle is
+/// only meaningful on the bus that issued it (and its clones).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignalId(u32);
+
+impl SignalId {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Slot of `FORCES_ACTIVE`, which every bus starts with
+const FORCES_ACTIVE_ID: SignalId = SignalId(0);
+
+/// Never reached by a rank, so reads outside blocks leave it as it is
+const NOT_TRACKING: u8 = u8::MAX;
+
+/// A write from outside the scan. A missing value keeps the value the
+/// signal has when the write is applied and only changes its quality.
+type QueuedWrite = (SignalId, Option<SignalValue>, Quality);
+
+struct Slots {
+    index: HashMap<String, SignalId>,
+    names: Vec<String>,
+    /// Declared type of each signal, which written values are converted to
+    types: Vec<Option<SignalType>>,
+    /// Published values, and the only values until a process image exists
+    values: Vec<Option<Sample>>,
+    /// Working copy the scan runs against, see `SignalBus::process_image`
+    image: Option<Vec<Option<Sample>>>,
+    /// Writes from outside the scan, applied at the next input phase
+    queued: Vec<QueuedWrite>,
+    /// Worst quality read through the image since `begin_block`, as a
+    /// `Quality::rank`, or `NOT_TRACKING` outside blocks
+    input_quality: AtomicU8,
+    /// Stamped on the change sets sent from now on
+    scan: u64,
+    /// Whether writes must already have the declared type, see `set_strict`
+    strict: bool,
+    changes: broadcast::Sender<Arc<ChangeSet>>,
+    /// Latest published sample of each signal someone watches
+    watchers: HashMap<SignalId, watch::Sender<Option<Sample>>>,
+    forces: HashMap<SignalId, Force>,
+}
+
+impl Default for Slots {
+    fn default() -> Self {
+        let mut slots = Self {
+            index: HashMap::new(),
+            names: Vec::new(),
+            types: Vec::new(),
+            values: Vec::new(),
+            image: None,
+            queued: Vec::new(),
+            input_quality: AtomicU8::new(NOT_TRACKING),
+            scan: 0,
+            strict: false,
+            changes: broadcast::channel(CHANGE_BUFFER).0,
+            watchers: HashMap::new(),
+            forces: HashMap::new(),
+        };
+        slots.register(FORCES_ACTIVE);
+        slots.types[FORCES_ACTIVE_ID.index()] = Some(SignalType::Bool);
+        slots.put(FORCES_ACTIVE_ID, Some(SignalValue::Bool(false)), Quality::Good);
+        slots
+    }
+}
+
+impl Slots {
+    fn register(&mut self, name: &str) -> SignalId {
+        if let Some(&id) = self.index.get(name) {
+            return id;
+        }
+        
+        let id = SignalId(self.names.len() as u32);
+        self.index.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        self.types.push(None);
+        self.values.push(None);
+        if let Some(image) = &mut self.image {
+            image.push(None);
+        }
+        id
+    }
+    
+    /// Values seen through a handle onto the image or onto the published values
+    fn view(&self, image: bool) -> &[Option<Sample>] {
+        match &self.image {
+            Some(values) if image => values,
+            _ => &self.values,
+        }
+    }
+    
+    fn sample(&self, id: SignalId, image: bool) -> Result<&Sample> {
+        let sample = self.view(image).get(id.index())
+            .and_then(Option::as_ref)
+            .ok_or_else(|| PlcError::SignalNotFound(self.name(id)))?;
+        if image {
+            self.input_quality.fetch_max(sample.quality.rank(), Ordering::Relaxed);
+        }
+        Ok(sample)
+    }
+    
+    fn value(&self, id: SignalId, image: bool) -> Result<&SignalValue> {
+        self.sample(id, image).map(|sample| &sample.value)
+    }
+    
+    /// Quality a plain write through a handle gets
+    fn plain_write_quality(&self, image: bool) -> Quality {
+        match self.input_quality.load(Ordering::Relaxed) {
+            rank if image && rank != NOT_TRACKING => Quality::from_rank(rank),
+            _ => Quality::Good,
+        }
+    }
+    
+    fn store(&mut self, id: SignalId, value: Option<SignalValue>, quality: Quality, image: bool) -> Result<()> {
+        if id.index() >= self.names.len() {
+            return Err(PlcError::SignalNotFound(self.name(id)));
+        }
+        if self.read_only(id) {
+            return Ok(());
+        }
+        let value = match (value, self.types[id.index()]) {
+            (Some(value), Some(declared)) if value.signal_type() != declared && self.strict => {
+                return Err(PlcError::TypeMismatch {
+                    expected: declared.name().to_string(),
+                    actual: value.type_name().to_string(),
+                });
+            }
+            (Some(value), Some(declared)) if value.signal_type() != declared => Some(value.coerce(declared)?),
+            (value, _) => value,
+        };
+        
+        match &mut self.image {
+            Some(values) if image => update(&mut values[id.index()], value, quality)
+                .then_some(())
+                .ok_or_else(|| PlcError::SignalNotFound(self.names[id.index()].clone())),
+            Some(_) => {
+                self.queued.push((id, value, quality));
+                Ok(())
+            }
+            None => {
+                // Without a process image every write is published at once
+                let previous = self.values[id.index()].clone();
+                if !update(&mut self.values[id.index()], value, quality) {
+                    return Err(PlcError::SignalNotFound(self.names[id.index()].clone()));
+                }
+                if self.observed() {
+                    let changes = self.change(id, previous.as_ref()).into_iter().collect();
+                    self.notify(changes);
+                }
+                Ok(())
+            }
+        }
+    }
+    
+    /// Whether writes to the signal are ignored
+    fn read_only(&self, id: SignalId) -> bool {
+        id == FORCES_ACTIVE_ID || self.forces.contains_key(&id)
+    }
+    
+    /// Write into the image, if any, and straight into the published values,
+    /// for changes that take effect at once
+    fn put(&mut self, id: SignalId, value: Option<SignalValue>, quality: Quality) {
+        if let Some(image) = &mut self.image {
+            update(&mut image[id.index()], value.clone(), quality);
+        }
+        let previous = self.values[id.index()].clone();
+        update(&mut self.values[id.index()], value, quality);
+        if self.observed() {
+            let changes = self.change(id, previous.as_ref()).into_iter().collect();
+            self.notify(changes);
+        }
+    }
+    
+    fn update_forces_active(&mut self) {
+        let active = !self.forces.is_empty();
+        self.put(FORCES_ACTIVE_ID, Some(SignalValue::Bool(active)), Quality::Good);
+    }
+    
+    /// The change of a published signal from `previous`, if its value or
+    /// quality differs
+    fn change(&self, id: SignalId, previous: Option<&Sample>) -> Option<SignalChange> {
+        let sample = self.values[id.index()].as_ref()?;
+        if previous.is_some_and(|previous| previous.value == sample.value && previous.quality == sample.quality) {
+            return None;
+        }
+        Some(SignalChange {
+            id,
+            name: self.names[id.index()].clone(),
+            previous: previous.map(|previous| previous.value.clone()),
+            value: sample.value.clone(),
+            quality: sample.quality,
+            timestamp: sample.timestamp,
+        })
+    }
+    
+    /// Whether publishing has to work out what changed
+    fn observed(&self) -> bool {
+        self.changes.receiver_count() > 0 || !self.watchers.is_empty()
+    }
+    
+    fn notify(&self, changes: Vec<SignalChange>) {
+        for change in &changes {
+            if let Some(watcher) = self.watchers.get(&change.id) {
+                watcher.send_replace(self.values[change.id.index()].clone());
+            }
+        }
+        if !changes.is_empty() && self.changes.receiver_count() > 0 {
+            // Nobody listening is not an error
+            self.changes.send(Arc::new(ChangeSet {
+                scan: self.scan,
+                timestamp: SystemTime::now(),
+                changes,
+            })).ok();
+        }
+    }
+    
+    fn name(&self, id: SignalId) -> String {
+        self.names.get(id.index())
+            .cloned()
+            .unwrap_or_else(|| format!("#{}", id.0))
+    }
+}
+
+/// Write into a slot. Only the quality can change on a slot without a
+/// value, which fails.
+fn update(slot: &mut Option<Sample>, value: Option<SignalValue>, quality: Quality) -> bool {
+    let value = match (value, slot.as_ref()) {
+        (Some(value), _) => value,
+        (None, Some(sample)) => sample.value.clone(),
+        (None, None) => return false,
+    };
+    *slot = Some(Sample::update(slot.as_ref(), value, quality));
+    true
+}
+
+/// Shared process data. Clones refer to the same signals.
+///
+/// Signals are stored in slots that are never removed, so a `SignalId` stays
+/// valid for the life of the bus. The name-based methods are for external
+/// access; blocks use the `SignalId` ones during a scan.
+///
+/// Once a scan engine takes a process image with `process_image`, handles
+/// other than the image see the values published at the end of the last
+/// scan, and their writes are queued until the engine's next input phase.
+///
+/// Every value carries a `Quality` and the time it last changed. Plain
+/// writes are good, except while the engine executes a block (between
+/// `begin_block` and `end_block`): plain writes through the image then get
+/// the worst quality the block read, so a block fed a bad input produces
+/// bad outputs without knowing about quality.
+///
+/// Signals in the force table keep their forced value whatever is written
+/// to them. Every bus has a `FORCES_ACTIVE` signal that tells whether the
+/// table has entries.
 #[derive(Clone)]
 pub struct SignalBus {
-    signals: Arc<DashMap<String, SignalValue>>,
+    slots: Arc<RwLock<Slots>>,
+    /// Whether this handle reads and writes the process image
+    image: bool,
 }
 
 impl SignalBus {
     pub fn new() -> Self {
         Self {
-            signals: Arc::new(DashMap::new()),
+            slots: Arc::new(RwLock::new(Slots::default())),
+            image: false,
+        }
+    }
+    
+    /// Start scanning this bus through a process image, returning the handle
+    /// the scan reads and writes. The image starts as a copy of the current
+    /// values, which stay published until the first `publish`.
+    pub fn process_image(&self) -> SignalBus {
+        let mut slots = self.write_slots();
+        if slots.image.is_none() {
+            slots.image = Some(slots.values.clone());
+        }
+        
+        SignalBus {
+            slots: self.slots.clone(),
+            image: true,
+        }
+    }
+    
+    /// Input phase: apply the writes queued since the last scan to the
+    /// process image, in the order they were made
+    pub fn apply_queued_writes(&self) {
+        let mut slots = self.write_slots();
+        let slots = &mut *slots;
+        let (image, queued) = (&mut slots.image, &mut slots.queued);
+        if let Some(image) = image {
+            for (id, value, quality) in queued.drain(..) {
+                // Forced after the write was made
+                if id != FORCES_ACTIVE_ID && !slots.forces.contains_key(&id) {
+                    update(&mut image[id.index()], value, quality);
+                }
+            }
+        }
+    }
+    
+    /// Output phase: make the process image visible to every other handle
+    /// in one step, and send the values that changed to `changes` receivers
+    pub fn publish(&self) {
+        let mut slots = self.write_slots();
+        // Comparing every slot is only worth it when someone listens
+        let observed = slots.observed();
+        let Slots { values, image, .. } = &mut *slots;
+        let Some(image) = image else { return };
+        if !observed {
+            values.clone_from(image);
+            return;
+        }
+        
+        let previous = std::mem::replace(values, image.clone());
+        let changes = previous.iter().enumerate()
+            .filter_map(|(index, previous)| slots.change(SignalId(index as u32), previous.as_ref()))
+            .collect();
+        slots.notify(changes);
+    }
+    
+    /// Scan number stamped on the change sets sent from now on
+    pub fn set_scan_number(&self, scan: u64) {
+        self.write_slots().scan = scan;
+    }
+    
+    pub fn scan_number(&self) -> u64 {
+        self.read_slots().scan
+    }
+    
+    /// Start passing the worst quality read through the image on to the
+    /// plain writes through it, until `end_block`
+    pub fn begin_block(&self) {
+        self.read_slots().input_quality.store(0, Ordering::Relaxed);
+    }
+    
+    pub fn end_block(&self) {
+        self.read_slots().input_quality.store(NOT_TRACKING, Ordering::Relaxed);
+    }
+    
+    /// Receive the changes of every later publish, or of every later write
+    /// while there is no process image. Only values or qualities that differ
+    /// from the published ones are reported. A receiver more than
+    /// `CHANGE_BUFFER` change sets behind misses the oldest ones and is told so.
+    pub fn changes(&self) -> broadcast::Receiver<Arc<ChangeSet>> {
+        self.read_slots().changes.subscribe()
+    }
+    
+    /// Receive the later changes of the signals matching any of `patterns`,
+    /// with their old and new values. See `matches_pattern` for the syntax;
+    /// a plain name subscribes to that signal only.
+    pub fn subscribe<I, S>(&self, patterns: I) -> Subscription
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Subscription::new(patterns.into_iter().map(Into::into).collect(), self.changes())
+    }
+    
+    /// Follow the published sample of one signal. The receiver starts with
+    /// the current sample, which is `None` until the signal is set, and is
+    /// marked changed whenever a different value or quality is published.
+    pub fn watch(&self, name: &str) -> watch::Receiver<Option<Sample>> {
+        let mut slots = self.write_slots();
+        let id = slots.register(name);
+        slots.watchers.retain(|_, watcher| !watcher.is_closed());
+        
+        let current = slots.values[id.index()].clone();
+        slots.watchers.entry(id)
+            .or_insert_with(|| watch::channel(current).0)
+            .subscribe()
+    }
+    
+    // Nothing panics while holding the lock, but don't lose the bus if something did
+    fn read_slots(&self) -> RwLockReadGuard<'_, Slots> {
+        self.slots.read().unwrap_or_else(|e| e.into_inner())
+    }
+    
+    fn write_slots(&self) -> RwLockWriteGuard<'_, Slots> {
+        self.slots.write().unwrap_or_else(|e| e.into_inner())
+    }
+    
+    /// Get the handle for `name`, allocating an empty slot if the signal
+    /// does not exist yet
+    pub fn register(&self, name: &str) -> SignalId {
+        if let Some(&id) = self.read_slots().index.get(name) {
+            return id;
+        }
+        self.write_slots().register(name)
+    }
+    
+    /// Register a signal of a declared type. Values written to it from now on
+    /// are converted to that type, and writes that do not convert fail.
+    pub fn declare(&self, name: &str, signal_type: SignalType) -> SignalId {
+        let mut slots = self.write_slots();
+        let id = slots.register(name);
+        slots.types[id.index()] = Some(signal_type);
+        id
+    }
+    
+    /// Type the signal was declared with, if any
+    pub fn declared_type(&self, id: SignalId) -> Option<SignalType> {
+        self.read_slots().types.get(id.index()).copied().flatten()
+    }
+    
+    /// Turn strict type checking on or off. When strict, writes to a declared
+    /// signal must have exactly its type instead of being converted, and
+    /// `read_bool`, `read_int` and `read_float` only read bool, int and float
+    /// signals. Forces still convert to the declared type.
+    pub fn set_strict(&self, strict: bool) {
+        self.write_slots().strict = strict;
+    }
+    
+    pub fn is_strict(&self) -> bool {
+        self.read_slots().strict
+    }
+    
+    /// Handle for an existing slot, without allocating one
+    pub fn id(&self, name: &str) -> Option<SignalId> {
+        self.read_slots().index.get(name).copied()
+    }
+    
+    /// Name the handle was registered under
+    pub fn name(&self, id: SignalId) -> String {
+        self.read_slots().name(id)
+    }
+    
+    pub fn read(&self, id: SignalId) -> Result<SignalValue> {
+        self.read_slots().value(id, self.image).cloned()
+    }
+    
+    /// Value the signal will have once the writes queued so far are applied:
+    /// the last of them that carries a value, or else the value `read` returns
+    pub fn read_pending(&self, id: SignalId) -> Result<SignalValue> {
+        let slots = self.read_slots();
+        if !self.image {
+            let queued = slots.queued.iter().rev().find(|(queued, value, _)| *queued == id && value.is_some());
+            if let Some((_, Some(value), _)) = queued {
+                return Ok(value.clone());
+            }
+        }
+        slots.value(id, self.image).cloned()
+    }
+    
+    pub fn read_sample(&self, id: SignalId) -> Result<Sample> {
+        self.read_slots().sample(id, self.image).cloned()
+    }
+    
+    pub fn read_bool(&self, id: SignalId) -> Result<bool> {
+        let slots = self.read_slots();
+        let value = slots.value(id, self.image)?;
+        if slots.strict {
+            return value.strict_bool();
         }
+        value.as_bool()
+            .ok_or_else(|| PlcError::TypeMismatch {
+                expected: "bool".to_string(),
+                actual: value.type_name().to_string(),
+            })
+    }
+    
+    pub fn read_int(&self, id: SignalId) -> Result<i32> {
+        let slots = self.read_slots();
+        let value = slots.value(id, self.image)?;
+        if slots.strict {
+            return value.strict_int();
+        }
+        value.as_int()
+            .ok_or_else(|| PlcError::TypeMismatch {
+                expected: "int".to_string(),
+                actual: value.type_name().to_string(),
+            })
+    }
+    
+    pub fn read_float(&self, id: SignalId) -> Result<f64> {
+        let slots = self.read_slots();
+        let value = slots.value(id, self.image)?;
+        if slots.strict {
+            return value.strict_float();
+        }
+        value.as_float()
+            .ok_or_else(|| PlcError::TypeMismatch {
+                expected: "float".to_string(),
+                actual: value.type_name().to_string(),
+            })
+    }
+    
+    pub fn write(&self, id: SignalId, value: SignalValue) -> Result<()> {
+        let mut slots = self.write_slots();
+        let quality = slots.plain_write_quality(self.image);
+        slots.store(id, Some(value), quality, self.image)
+    }
+    
+    pub fn write_with_quality(&self, id: SignalId, value: SignalValue, quality: Quality) -> Result<()> {
+        self.write_slots().store(id, Some(value), quality, self.image)
+    }
+    
+    /// Change the quality of a signal that has a value, keeping the value
+    pub fn write_quality(&self, id: SignalId, quality: Quality) -> Result<()> {
+        self.write_slots().store(id, None, quality, self.image)
     }
     
     pub fn set(&self, name: &str, value: SignalValue) -> Result<()> {
-        self.signals.insert(name.to_string(), value);
-        Ok(())
+        let mut slots = self.write_slots();
+        let id = slots.register(name);
+        let quality = slots.plain_write_quality(self.image);
+        slots.store(id, Some(value), quality, self.image)
+    }
+    
+    pub fn set_with_quality(&self, name: &str, value: SignalValue, quality: Quality) -> Result<()> {
+        let mut slots = self.write_slots();
+        let id = slots.register(name);
+        slots.store(id, Some(value), quality, self.image)
     }
     
     pub fn get(&self, name: &str) -> Result<SignalValue> {
-        self.signals
-            .get(name)
-            .map(|entry| entry.value().clone())
-            .ok_or_else(|| PlcError::SignalNotFound(name.to_string()))
+        self.get_sample(name).map(|sample| sample.value)
+    }
+    
+    pub fn get_sample(&self, name: &str) -> Result<Sample> {
+        let slots = self.read_slots();
+        let id = slots.index.get(name)
+            .ok_or_else(|| PlcError::SignalNotFound(name.to_string()))?;
+        slots.sample(*id, self.image).cloned()
+    }
+    
+    pub fn quality(&self, name: &str) -> Result<Quality> {
+        self.get_sample(name).map(|sample| sample.quality)
     }
     
     pub fn get_bool(&self, name: &str) -> Result<bool> {
@@ -55,15 +573,131 @@ impl SignalBus {
     }
     
     pub fn exists(&self, name: &str) -> bool {
-        self.signals.contains_key(name)
+        let slots = self.read_slots();
+        slots.index.get(name)
+            .is_some_and(|id| slots.view(self.image)[id.index()].is_some())
     }
     
+    /// Remove all values, including the process image, queued writes and forces.
+    /// Registered handles stay valid and read as missing until the signal is
+    /// set again.
     pub fn clear(&self) {
-        self.signals.clear();
+        let mut slots = self.write_slots();
+        slots.values.iter_mut().for_each(|value| *value = None);
+        if let Some(image) = &mut slots.image {
+            image.iter_mut().for_each(|value| *value = None);
+        }
+        slots.queued.clear();
+        slots.forces.clear();
+        for watcher in slots.watchers.values() {
+            watcher.send_replace(None);
+        }
+        slots.update_forces_active();
+    }
+    
+    /// Hold a signal at `value`, ignoring every write to it from blocks,
+    /// drivers and other handles until the force is removed. The value takes
+    /// effect at once with `forced` quality. `by` is recorded with the force.
+    pub fn force(&self, name: &str, value: SignalValue, by: &str) -> Result<()> {
+        let mut slots = self.write_slots();
+        let id = *slots.index.get(name)
+            .ok_or_else(|| PlcError::SignalNotFound(name.to_string()))?;
+        if id == FORCES_ACTIVE_ID {
+            return Err(PlcError::ExecutionError(format!("'{}' cannot be forced", FORCES_ACTIVE)));
+        }
+        let declared = slots.types[id.index()]
+            .or_else(|| slots.values[id.index()].as_ref().map(|current| current.value.signal_type()));
+        let value = match declared {
+            Some(declared) => value.coerce(declared)?,
+            None => value,
+        };
+        
+        warn!("Signal '{}' forced to {:?} by {}", name, value, by);
+        slots.put(id, Some(value.clone()), Quality::Forced);
+        slots.forces.insert(id, Force {
+            signal: name.to_string(),
+            value,
+            by: by.to_string(),
+            since: SystemTime::now(),
+        });
+        slots.update_forces_active();
+        Ok(())
+    }
+    
+    /// Remove the force on a signal, returning whether there was one. The
+    /// signal keeps the forced value, now good, until it is next written.
+    pub fn unforce(&self, name: &str, by: &str) -> bool {
+        let mut slots = self.write_slots();
+        let Some(id) = slots.index.get(name).copied() else { return false };
+        if slots.forces.remove(&id).is_none() {
+            return false;
+        }
+        
+        warn!("Force on signal '{}' removed by {}", name, by);
+        slots.put(id, None, Quality::Good);
+        slots.update_forces_active();
+        true
+    }
+    
+    /// Remove every force, returning how many there were
+    pub fn clear_forces(&self, by: &str) -> usize {
+        let mut slots = self.write_slots();
+        let forced: Vec<SignalId> = slots.forces.keys().copied().collect();
+        if !forced.is_empty() {
+            warn!("{} forces removed by {}", forced.len(), by);
+        }
+        
+        slots.forces.clear();
+        for &id in &forced {
+            slots.put(id, None, Quality::Good);
+        }
+        slots.update_forces_active();
+        forced.len()
+    }
+    
+    /// The force table, by signal name
+    pub fn forces(&self) -> Vec<Force> {
+        let mut forces: Vec<Force> = self.read_slots().forces.values().cloned().collect();
+        forces.sort_by(|a, b| a.signal.cmp(&b.signal));
+        forces
+    }
+    
+    pub fn is_forced(&self, name: &str) -> bool {
+        let slots = self.read_slots();
+        slots.index.get(name).is_some_and(|id| slots.forces.contains_key(id))
     }
     
     // Return a Vec instead of an iterator to avoid lifetime issues
     pub fn iter(&self) -> Vec<(String, SignalValue)> {
-        self.signals.iter().map(|entry| (entry.key().clone(), entry.value().clone())).collect()
+        self.samples().into_iter()
+            .map(|(name, sample)| (name, sample.value))
+            .collect()
+    }
+    
+    /// Samples of the elements of an array or struct signal, such as
+    /// `pumps[1].run` and `pumps[2].run` for `pumps`, in declaration order
+    pub fn element_samples(&self, name: &str) -> Vec<(String, Sample)> {
+        let slots = self.read_slots();
+        slots.names.iter()
+            .zip(slots.view(self.image))
+            .filter(|(element, _)| element.strip_prefix(name)
+                .is_some_and(|rest| rest.starts_with('[') || rest.starts_with('.')))
+            .filter_map(|(element, sample)| sample.clone().map(|sample| (element.clone(), sample)))
+            .collect()
+    }
+    
+    /// Every signal that has a value, with its quality and timestamp
+    pub fn samples(&self) -> Vec<(String, Sample)> {
+        let slots = self.read_slots();
+        slots.names.iter()
+            .zip(slots.view(self.image))
+            .filter_map(|(name, sample)| sample.clone().map(|sample| (name.clone(), sample)))
+            .collect()
+    }
+}
+
+impl Default for SignalBus {
+    fn default() -> Self {
+        Self::new()
     }
 }
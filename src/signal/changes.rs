@@ -0,0 +1,118 @@
+use super::{Quality, SignalId, SignalValue};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::broadcast::{self, error::{RecvError, TryRecvError}};
+use tracing::warn;
+
+/// Signals whose value or quality changed in one publish of the bus
+#[derive(Debug, Clone)]
+pub struct ChangeSet {
+    /// Scan number set on the bus when the values were published
+    pub scan: u64,
+    pub timestamp: SystemTime,
+    pub changes: Vec<SignalChange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalChange {
+    pub id: SignalId,
+    pub name: String,
+    /// Value before the change, `None` if the signal had no value yet
+    pub previous: Option<SignalValue>,
+    pub value: SignalValue,
+    pub quality: Quality,
+    /// When the signal last changed, which is earlier than the change set's
+    /// timestamp when it changed during the scan
+    pub timestamp: SystemTime,
+}
+
+/// Match a signal name against a pattern where `*` stands for any run of
+/// characters and `?` for exactly one, e.g. `tank_*` or `pump?.run`
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    
+    // Backtracking over the last `*` is enough for this pattern language
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Changes to the signals matching a set of patterns, from `SignalBus::subscribe`
+pub struct Subscription {
+    patterns: Vec<String>,
+    changes: broadcast::Receiver<Arc<ChangeSet>>,
+    missed: u64,
+}
+
+impl Subscription {
+    pub(crate) fn new(patterns: Vec<String>, changes: broadcast::Receiver<Arc<ChangeSet>>) -> Self {
+        Self { patterns, changes, missed: 0 }
+    }
+    
+    /// The subscribed part of a change set, if any
+    fn filter(&self, set: &ChangeSet) -> Option<ChangeSet> {
+        let changes: Vec<SignalChange> = set.changes.iter()
+            .filter(|change| self.patterns.iter().any(|pattern| matches_pattern(pattern, &change.name)))
+            .cloned()
+            .collect();
+        (!changes.is_empty()).then_some(ChangeSet { changes, ..*set })
+    }
+    
+    fn lagged(&mut self, missed: u64) {
+        self.missed += missed;
+        warn!("Signal subscription {:?} fell behind and missed {} change sets", self.patterns, missed);
+    }
+    
+    /// Wait for the next change to a subscribed signal. Returns `None` once
+    /// the bus is gone.
+    pub async fn recv(&mut self) -> Option<ChangeSet> {
+        loop {
+            match self.changes.recv().await {
+                Ok(set) => if let Some(set) = self.filter(&set) {
+                    return Some(set);
+                },
+                Err(RecvError::Lagged(missed)) => self.lagged(missed),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+    
+    /// Next change to a subscribed signal that has already happened, without waiting
+    pub fn try_recv(&mut self) -> Option<ChangeSet> {
+        loop {
+            match self.changes.try_recv() {
+                Ok(set) => if let Some(set) = self.filter(&set) {
+                    return Some(set);
+                },
+                Err(TryRecvError::Lagged(missed)) => self.lagged(missed),
+                Err(_) => return None,
+            }
+        }
+    }
+    
+    /// Change sets dropped because this subscription was not read fast enough
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+}
@@ -0,0 +1,16 @@
+use super::SignalValue;
+use std::time::SystemTime;
+
+/// Name of the bool signal that is on while any signal is forced. It is
+/// maintained by the bus and ignores writes.
+pub const FORCES_ACTIVE: &str = "forces_active";
+
+/// Entry of the force table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Force {
+    pub signal: String,
+    pub value: SignalValue,
+    /// Who placed the force, for the commissioning log
+    pub by: String,
+    pub since: SystemTime,
+}
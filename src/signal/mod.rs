@@ -1,5 +1,13 @@
 mod value;
+mod types;
 mod bus;
+mod changes;
+mod quality;
+mod force;
 
 pub use value::SignalValue;
-pub use bus::SignalBus;
+pub use types::SignalType;
+pub use bus::{SignalBus, SignalId, CHANGE_BUFFER};
+pub use changes::{ChangeSet, SignalChange, Subscription, matches_pattern};
+pub use quality::{Quality, Sample};
+pub use force::{Force, FORCES_ACTIVE};
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use super::SignalValue;
+
+/// How far a signal's value can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quality {
+    #[default]
+    Good,
+    Uncertain,
+    Bad,
+    /// The device the value comes from cannot be reached; the value is the
+    /// last one read
+    CommFault,
+    /// Set by an operator instead of by the logic or the I/O
+    Forced,
+    /// Set by a simulation instead of by the process
+    Simulated,
+}
+
+impl Quality {
+    /// Whether the value can be used as is
+    pub fn is_good(self) -> bool {
+        matches!(self, Quality::Good | Quality::Forced | Quality::Simulated)
+    }
+    
+    /// Quality this input passes on to the outputs of a block that reads it:
+    /// good, uncertain or bad
+    pub fn propagated(self) -> Quality {
+        match self {
+            Quality::Bad | Quality::CommFault => Quality::Bad,
+            Quality::Uncertain => Quality::Uncertain,
+            Quality::Good | Quality::Forced | Quality::Simulated => Quality::Good,
+        }
+    }
+    
+    pub(crate) fn rank(self) -> u8 {
+        match self.propagated() {
+            Quality::Bad => 2,
+            Quality::Uncertain => 1,
+            _ => 0,
+        }
+    }
+    
+    pub(crate) fn from_rank(rank: u8) -> Quality {
+        match rank {
+            0 => Quality::Good,
+            1 => Quality::Uncertain,
+            _ => Quality::Bad,
+        }
+    }
+}
+
+/// A signal's value with its quality and the time either last changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub value: SignalValue,
+    pub quality: Quality,
+    pub timestamp: SystemTime,
+}
+
+impl Sample {
+    /// `value` with `quality`, keeping the timestamp of `previous` if neither changed
+    pub(crate) fn update(previous: Option<&Sample>, value: SignalValue, quality: Quality) -> Sample {
+        match previous {
+            Some(previous) if previous.value == value && previous.quality == quality => previous.clone(),
+            _ => Sample { value, quality, timestamp: SystemTime::now() },
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use super::SignalValue;
+use std::fmt;
+
+/// Declared type of a signal, named after the IEC 61131-3 elementary types.
+///
+/// `int` keeps its original meaning of a 32-bit integer (IEC `DINT`) and
+/// `float` that of a 64-bit real (IEC `LREAL`), so existing configurations
+/// load unchanged. The 16-bit IEC `INT` is spelled `int16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignalType {
+    Bool,
+    SInt,
+    Int16,
+    Int,
+    LInt,
+    USInt,
+    UInt,
+    UDInt,
+    ULInt,
+    Real,
+    Float,
+    /// Duration in milliseconds
+    Time,
+    /// Milliseconds since the Unix epoch, UTC
+    DateAndTime,
+    Word,
+    DWord,
+    String,
+}
+
+impl SignalType {
+    /// Type for a name as written in a configuration, ignoring case
+    pub fn parse(name: &str) -> Option<Self> {
+        let signal_type = match name.to_ascii_lowercase().as_str() {
+            "bool" => SignalType::Bool,
+            "sint" => SignalType::SInt,
+            "int16" => SignalType::Int16,
+            "int" | "dint" => SignalType::Int,
+            "lint" => SignalType::LInt,
+            "usint" | "byte" => SignalType::USInt,
+            "uint" => SignalType::UInt,
+            "udint" => SignalType::UDInt,
+            "ulint" => SignalType::ULInt,
+            "real" => SignalType::Real,
+            "float" | "lreal" => SignalType::Float,
+            "time" => SignalType::Time,
+            "date_and_time" | "dt" => SignalType::DateAndTime,
+            "word" => SignalType::Word,
+            "dword" => SignalType::DWord,
+            "string" => SignalType::String,
+            _ => return None,
+        };
+        Some(signal_type)
+    }
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            SignalType::Bool => "bool",
+            SignalType::SInt => "sint",
+            SignalType::Int16 => "int16",
+            SignalType::Int => "int",
+            SignalType::LInt => "lint",
+            SignalType::USInt => "usint",
+            SignalType::UInt => "uint",
+            SignalType::UDInt => "udint",
+            SignalType::ULInt => "ulint",
+            SignalType::Real => "real",
+            SignalType::Float => "float",
+            SignalType::Time => "time",
+            SignalType::DateAndTime => "date_and_time",
+            SignalType::Word => "word",
+            SignalType::DWord => "dword",
+            SignalType::String => "string",
+        }
+    }
+    
+    /// Signed and unsigned integers, and the WORD bit strings
+    pub fn is_integer(self) -> bool {
+        self.integer_range().is_some() && !self.is_time()
+    }
+    
+    pub fn is_float(self) -> bool {
+        matches!(self, SignalType::Real | SignalType::Float)
+    }
+    
+    pub fn is_numeric(self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+    
+    /// TIME and DATE_AND_TIME, both held as whole milliseconds
+    pub fn is_time(self) -> bool {
+        matches!(self, SignalType::Time | SignalType::DateAndTime)
+    }
+    
+    /// Smallest and largest value of an integer, bit string or time type
+    pub(crate) fn integer_range(self) -> Option<(i128, i128)> {
+        let range = match self {
+            SignalType::SInt => (i8::MIN as i128, i8::MAX as i128),
+            SignalType::Int16 => (i16::MIN as i128, i16::MAX as i128),
+            SignalType::Int => (i32::MIN as i128, i32::MAX as i128),
+            SignalType::LInt | SignalType::Time | SignalType::DateAndTime => (i64::MIN as i128, i64::MAX as i128),
+            SignalType::USInt => (0, u8::MAX as i128),
+            SignalType::UInt | SignalType::Word => (0, u16::MAX as i128),
+            SignalType::UDInt | SignalType::DWord => (0, u32::MAX as i128),
+            SignalType::ULInt => (0, u64::MAX as i128),
+            SignalType::Bool | SignalType::Real | SignalType::Float | SignalType::String => return None,
+        };
+        Some(range)
+    }
+    
+    /// Number of bits of an integer or bit string type
+    pub fn bit_width(self) -> Option<u32> {
+        let width = match self {
+            SignalType::SInt | SignalType::USInt => 8,
+            SignalType::Int16 | SignalType::UInt | SignalType::Word => 16,
+            SignalType::Int | SignalType::UDInt | SignalType::DWord => 32,
+            SignalType::LInt | SignalType::ULInt => 64,
+            _ => return None,
+        };
+        Some(width)
+    }
+    
+    /// Value a signal of this type starts from when no initial value is given
+    pub fn default_value(self) -> SignalValue {
+        match self {
+            SignalType::Bool => SignalValue::Bool(false),
+            SignalType::Real => SignalValue::Real(0.0),
+            SignalType::Float => SignalValue::Float(0.0),
+            SignalType::String => SignalValue::String(String::new()),
+            _ => SignalValue::from_integer(0, self).unwrap_or(SignalValue::Int(0)),
+        }
+    }
+}
+
+impl fmt::Display for SignalType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Milliseconds of a TIME literal such as `T#1h30m`, `250ms` or `1.5s`.
+///
+/// A bare number is taken as milliseconds. Units are `d`, `h`, `m`, `s` and
+/// `ms`; the last one may have a fraction, and the whole may be negative.
+pub(crate) fn parse_time(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let text = ["T#", "t#", "TIME#", "time#"].iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .unwrap_or(text)
+        .replace('_', "");
+    let (negative, mut rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    if rest.is_empty() {
+        return None;
+    }
+    if let Ok(ms) = rest.parse::<i64>() {
+        return Some(if negative { -ms } else { ms });
+    }
+    
+    let mut total = 0.0;
+    let mut last_unit = f64::INFINITY;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let number: f64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let letters = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let unit = match rest[..letters].to_ascii_lowercase().as_str() {
+            "d" => 86_400_000.0,
+            "h" => 3_600_000.0,
+            "m" => 60_000.0,
+            "s" => 1_000.0,
+            "ms" => 1.0,
+            _ => return None,
+        };
+        // Units go from largest to smallest, and only the last has a fraction
+        if unit >= last_unit || (number.fract() != 0.0 && letters < rest.len()) {
+            return None;
+        }
+        last_unit = unit;
+        total += number * unit;
+        rest = &rest[letters..];
+    }
+    
+    let ms = if negative { -total } else { total }.round();
+    (ms.abs() < i64::MAX as f64).then_some(ms as i64)
+}
+
+/// Milliseconds since the Unix epoch of a DATE_AND_TIME literal, written
+/// `YYYY-MM-DDTHH:MM:SS[.mmm][Z]`, optionally behind `DT#`. A space may
+/// stand for the `T`, and IEC's `DT#YYYY-MM-DD-HH:MM:SS` form is accepted.
+/// Times are UTC.
+pub(crate) fn parse_date_and_time(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let text = ["DT#", "dt#", "DATE_AND_TIME#", "date_and_time#"].iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .unwrap_or(text);
+    let text = text.strip_suffix('Z').unwrap_or(text);
+    if text.len() < 19 || !text.is_char_boundary(10) {
+        return None;
+    }
+    let (date, time) = (&text[..10], &text[11..]);
+    if !matches!(&text[10..11], "T" | " " | "-") {
+        return None;
+    }
+    
+    let mut date_parts = date.split('-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+    
+    let (clock, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut clock_parts = clock.split(':');
+    let hour: i64 = clock_parts.next()?.parse().ok()?;
+    let minute: i64 = clock_parts.next()?.parse().ok()?;
+    let second: i64 = clock_parts.next()?.parse().ok()?;
+    if date_parts.next().is_some() || clock_parts.next().is_some() {
+        return None;
+    }
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let millis = match fraction {
+        "" => 0,
+        digits if digits.len() <= 3 && digits.bytes().all(|b| b.is_ascii_digit()) => {
+            digits.parse::<i64>().ok()? * 10_i64.pow(3 - digits.len() as u32)
+        }
+        _ => return None,
+    };
+    
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(seconds * 1_000 + millis)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days from 1970-01-01 to a date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
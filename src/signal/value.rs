@@ -1,19 +1,47 @@
 use serde::{Deserialize, Serialize};
+use crate::{PlcError, Result};
+use super::types::{parse_date_and_time, parse_time, SignalType};
 
+/// Value of a signal.
+///
+/// Values serialize as plain JSON or YAML scalars, with TIME and
+/// DATE_AND_TIME as milliseconds. Reading one back only recovers bool, int,
+/// lint, ulint, float or string; use [`SignalValue::convert`] to get the
+/// declared type again.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SignalValue {
     Bool(bool),
+    /// 32-bit integer, IEC DINT
     Int(i32),
+    LInt(i64),
+    ULInt(u64),
+    /// 64-bit real, IEC LREAL
     Float(f64),
     String(String),
+    SInt(i8),
+    /// 16-bit integer, IEC INT
+    Int16(i16),
+    USInt(u8),
+    UInt(u16),
+    UDInt(u32),
+    Real(f32),
+    /// Duration in milliseconds
+    Time(i64),
+    /// Milliseconds since the Unix epoch, UTC
+    DateAndTime(i64),
+    Word(u16),
+    DWord(u32),
 }
 
 impl SignalValue {
+    /// The bool of a BOOL, or whether an integer is non-zero. The `as_*`
+    /// accessors convert leniently; the `strict_*` ones take only their
+    /// own type.
     pub fn as_bool(&self) -> Option<bool> {
         match self {
             SignalValue::Bool(b) => Some(*b),
-            SignalValue::Int(i) => Some(*i != 0),
+            value if value.signal_type().is_integer() => value.as_i128().map(|i| i != 0),
             _ => None,
         }
     }
@@ -23,6 +51,24 @@ impl SignalValue {
             SignalValue::Int(i) => Some(*i),
             SignalValue::Bool(b) => Some(if *b { 1 } else { 0 }),
             SignalValue::Float(f) => Some(*f as i32),
+            SignalValue::Real(f) => Some(*f as i32),
+            value if value.signal_type().is_integer() => value.as_i128().and_then(|i| i32::try_from(i).ok()),
+            _ => None,
+        }
+    }
+    
+    /// Value of any integer type that fits an `i64`
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            value if value.signal_type().is_integer() => value.as_i128().and_then(|i| i64::try_from(i).ok()),
+            _ => None,
+        }
+    }
+    
+    /// Value of any integer type that fits a `u64`
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            value if value.signal_type().is_integer() => value.as_i128().and_then(|i| u64::try_from(i).ok()),
             _ => None,
         }
     }
@@ -30,17 +76,233 @@ impl SignalValue {
     pub fn as_float(&self) -> Option<f64> {
         match self {
             SignalValue::Float(f) => Some(*f),
-            SignalValue::Int(i) => Some(*i as f64),
+            SignalValue::Real(f) => Some(*f as f64),
+            value if value.signal_type().is_integer() => value.as_i128().map(|i| i as f64),
             _ => None,
         }
     }
     
-    pub fn type_name(&self) -> &'static str {
+    /// Like `as_bool`, but only for a BOOL, as strict type checking requires
+    pub fn strict_bool(&self) -> Result<bool> {
         match self {
-            SignalValue::Bool(_) => "bool",
-            SignalValue::Int(_) => "int",
-            SignalValue::Float(_) => "float",
-            SignalValue::String(_) => "string",
+            SignalValue::Bool(b) => Ok(*b),
+            other => Err(other.mismatch(SignalType::Bool)),
+        }
+    }
+    
+    /// Like `as_int`, but only for an INT
+    pub fn strict_int(&self) -> Result<i32> {
+        match self {
+            SignalValue::Int(i) => Ok(*i),
+            other => Err(other.mismatch(SignalType::Int)),
+        }
+    }
+    
+    /// Like `as_float`, but only for a FLOAT
+    pub fn strict_float(&self) -> Result<f64> {
+        match self {
+            SignalValue::Float(f) => Ok(*f),
+            other => Err(other.mismatch(SignalType::Float)),
+        }
+    }
+    
+    fn mismatch(&self, expected: SignalType) -> PlcError {
+        PlcError::TypeMismatch {
+            expected: expected.name().to_string(),
+            actual: self.type_name().to_string(),
+        }
+    }
+    
+    /// Milliseconds of a TIME or DATE_AND_TIME value
+    pub fn as_millis(&self) -> Option<i64> {
+        match self {
+            SignalValue::Time(ms) | SignalValue::DateAndTime(ms) => Some(*ms),
+            _ => None,
         }
     }
+    
+    /// Value of an integer, bit string or time type
+    pub(crate) fn as_i128(&self) -> Option<i128> {
+        let value = match self {
+            SignalValue::SInt(i) => *i as i128,
+            SignalValue::Int16(i) => *i as i128,
+            SignalValue::Int(i) => *i as i128,
+            SignalValue::LInt(i) => *i as i128,
+            SignalValue::USInt(i) => *i as i128,
+            SignalValue::UInt(i) | SignalValue::Word(i) => *i as i128,
+            SignalValue::UDInt(i) | SignalValue::DWord(i) => *i as i128,
+            SignalValue::ULInt(i) => *i as i128,
+            SignalValue::Time(ms) | SignalValue::DateAndTime(ms) => *ms as i128,
+            _ => return None,
+        };
+        Some(value)
+    }
+    
+    /// Bit pattern of an integer or bit string, two's complement for signed
+    /// types, in the low `bit_width` bits
+    pub fn to_bits(&self) -> Option<u64> {
+        let width = self.signal_type().bit_width()?;
+        let bits = self.as_i128()? as u64;
+        Some(if width == 64 { bits } else { bits & ((1 << width) - 1) })
+    }
+    
+    /// Integer or bit string of type `to` with the bit pattern in the low
+    /// bits of `bits`, ignoring the bits above its width
+    pub fn from_bits(bits: u64, to: SignalType) -> Option<SignalValue> {
+        let width = to.bit_width()?;
+        let (min, _) = to.integer_range()?;
+        let unused = 64 - width;
+        let value = if min < 0 {
+            ((bits << unused) as i64 >> unused) as i128
+        } else {
+            ((bits << unused) >> unused) as i128
+        };
+        SignalValue::from_integer(value, to).ok()
+    }
+    
+    /// Value of type `to` for an integer, failing if it is out of range
+    pub fn from_integer(value: i128, to: SignalType) -> Result<SignalValue> {
+        let out_of_range = || PlcError::ConversionError(format!("{} is out of range for {}", value, to));
+        let converted = match to {
+            SignalType::SInt => SignalValue::SInt(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::Int16 => SignalValue::Int16(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::Int => SignalValue::Int(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::LInt => SignalValue::LInt(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::USInt => SignalValue::USInt(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::UInt => SignalValue::UInt(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::UDInt => SignalValue::UDInt(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::ULInt => SignalValue::ULInt(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::Word => SignalValue::Word(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::DWord => SignalValue::DWord(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::Time => SignalValue::Time(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::DateAndTime => SignalValue::DateAndTime(value.try_into().map_err(|_| out_of_range())?),
+            SignalType::Real => SignalValue::Real(value as f32),
+            SignalType::Float => SignalValue::Float(value as f64),
+            SignalType::Bool | SignalType::String => return Err(PlcError::TypeMismatch {
+                expected: to.name().to_string(),
+                actual: "integer".to_string(),
+            }),
+        };
+        Ok(converted)
+    }
+    
+    /// Convert to type `to` without losing information.
+    ///
+    /// Integers, bit strings and times convert among each other when the
+    /// value is in range of the target, and to reals. Reals convert to
+    /// integers only when they hold a whole number in range, and LREAL to
+    /// REAL only when the value fits. BOOL converts to and from integers as
+    /// 0 and 1, where any non-zero integer is TRUE. Strings do not convert.
+    pub fn convert(&self, to: SignalType) -> Result<SignalValue> {
+        let from = self.signal_type();
+        if from == to {
+            return Ok(self.clone());
+        }
+        let incompatible = || PlcError::TypeMismatch {
+            expected: to.name().to_string(),
+            actual: from.name().to_string(),
+        };
+        
+        match self {
+            SignalValue::String(_) => Err(incompatible()),
+            SignalValue::Bool(b) if to.is_integer() => SignalValue::from_integer(*b as i128, to),
+            SignalValue::Bool(_) => Err(incompatible()),
+            _ if to == SignalType::Bool => self.as_bool().map(SignalValue::Bool).ok_or_else(incompatible),
+            _ if to == SignalType::String => Err(incompatible()),
+            SignalValue::Float(f) if to == SignalType::Real => {
+                if f.is_finite() && f.abs() > f32::MAX as f64 {
+                    return Err(PlcError::ConversionError(format!("{} is out of range for real", f)));
+                }
+                Ok(SignalValue::Real(*f as f32))
+            }
+            SignalValue::Real(f) if to == SignalType::Float => Ok(SignalValue::Float(*f as f64)),
+            SignalValue::Float(_) | SignalValue::Real(_) => {
+                let f = self.as_float().unwrap_or_default();
+                if !f.is_finite() || f.fract() != 0.0 {
+                    return Err(PlcError::ConversionError(format!("{} is not a whole number", f)));
+                }
+                // Out of range of every integer type, so `from_integer` rejects it
+                let i = if f.abs() < 1e38 { f as i128 } else { i128::MAX };
+                SignalValue::from_integer(i, to)
+            }
+            _ => SignalValue::from_integer(self.as_i128().ok_or_else(incompatible)?, to),
+        }
+    }
+    
+    /// Convert for storing into a signal declared as `to`. Numbers and times
+    /// convert as with [`SignalValue::convert`], while bools and strings
+    /// only take values of their own type.
+    pub fn coerce(&self, to: SignalType) -> Result<SignalValue> {
+        let from = self.signal_type();
+        let number = |t: SignalType| t.is_numeric() || t.is_time();
+        if from != to && !(number(from) && number(to)) {
+            return Err(PlcError::TypeMismatch {
+                expected: to.name().to_string(),
+                actual: from.name().to_string(),
+            });
+        }
+        self.convert(to)
+    }
+    
+    /// Parse a configuration value as type `to`.
+    ///
+    /// Nothing (`~`) gives the type's default. Numbers follow the rules of
+    /// [`SignalValue::convert`]; TIME also takes literals like `T#1m30s` or
+    /// `250ms` and DATE_AND_TIME ones like `2024-05-01T06:00:00Z`.
+    pub fn from_yaml(value: &serde_yaml::Value, to: SignalType) -> Result<SignalValue> {
+        let invalid = || PlcError::ConfigError(format!("{:?} is not a {} value", value, to));
+        match value {
+            serde_yaml::Value::Null => Ok(to.default_value()),
+            serde_yaml::Value::Bool(b) => SignalValue::Bool(*b).coerce(to).map_err(|_| invalid()),
+            serde_yaml::Value::Number(n) => {
+                let number = if let Some(i) = n.as_i64() {
+                    SignalValue::LInt(i)
+                } else if let Some(u) = n.as_u64() {
+                    SignalValue::ULInt(u)
+                } else {
+                    SignalValue::Float(n.as_f64().ok_or_else(invalid)?)
+                };
+                // Booleans are written true or false, not as numbers
+                if to == SignalType::Bool {
+                    return Err(invalid());
+                }
+                number.convert(to).map_err(|e| match e {
+                    PlcError::TypeMismatch { .. } => invalid(),
+                    e => e,
+                })
+            }
+            serde_yaml::Value::String(s) => match to {
+                SignalType::String => Ok(SignalValue::String(s.clone())),
+                SignalType::Time => parse_time(s).map(SignalValue::Time).ok_or_else(invalid),
+                SignalType::DateAndTime => parse_date_and_time(s).map(SignalValue::DateAndTime).ok_or_else(invalid),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+    
+    pub fn signal_type(&self) -> SignalType {
+        match self {
+            SignalValue::Bool(_) => SignalType::Bool,
+            SignalValue::SInt(_) => SignalType::SInt,
+            SignalValue::Int16(_) => SignalType::Int16,
+            SignalValue::Int(_) => SignalType::Int,
+            SignalValue::LInt(_) => SignalType::LInt,
+            SignalValue::USInt(_) => SignalType::USInt,
+            SignalValue::UInt(_) => SignalType::UInt,
+            SignalValue::UDInt(_) => SignalType::UDInt,
+            SignalValue::ULInt(_) => SignalType::ULInt,
+            SignalValue::Real(_) => SignalType::Real,
+            SignalValue::Float(_) => SignalType::Float,
+            SignalValue::Time(_) => SignalType::Time,
+            SignalValue::DateAndTime(_) => SignalType::DateAndTime,
+            SignalValue::Word(_) => SignalType::Word,
+            SignalValue::DWord(_) => SignalType::DWord,
+            SignalValue::String(_) => SignalType::String,
+        }
+    }
+    
+    pub fn type_name(&self) -> &'static str {
+        self.signal_type().name()
+    }
 }
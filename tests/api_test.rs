@@ -0,0 +1,462 @@
+use soft_plc::{
+    signal::{SignalBus, SignalValue},
+    engine::{PlcConfig, ScanEngine, SimulatedClock},
+    api::{ApiConfig, ApiServer},
+    PlcError, Result,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const CONFIG: &str = r#"
+signals:
+  - name: "start"
+    type: "bool"
+  - name: "running"
+    type: "bool"
+  - name: "setpoint"
+    type: "float"
+    initial: 50.0
+
+blocks:
+  - name: "copy"
+    type: "OR"
+    inputs:
+      in1: "start"
+      in2: "start"
+    outputs:
+      out: "running"
+
+scan_time_ms: 100
+"#;
+
+/// Minimal HTTP/1.1 client: send one request and return the status code and JSON body
+async fn request(address: SocketAddr, method: &str, path: &str, body: Option<Value>) -> (u16, Value) {
+    send(address, method, path, body.map(|body| body.to_string()).unwrap_or_default()).await
+}
+
+async fn send(address: SocketAddr, method: &str, path: &str, body: String) -> (u16, Value) {
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method, path, body.len(), body);
+    stream.write_all(request.as_bytes()).await.unwrap();
+    
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap_or(Value::Null))
+}
+
+/// POST a raw body, scanning until the engine has answered it
+async fn post_between_scans(
+    engine: &mut ScanEngine,
+    address: SocketAddr,
+    path: &'static str,
+    body: String,
+) -> (u16, Value) {
+    let pending = tokio::spawn(send(address, "POST", path, body));
+    while !pending.is_finished() {
+        engine.run_simulated(Duration::from_millis(100)).unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    pending.await.unwrap()
+}
+
+async fn subscribe(address: SocketAddr, request: Value) -> Socket {
+    let (mut socket, _) = connect_async(format!("ws://{}/api/ws", address)).await.unwrap();
+    socket.send(Message::Text(request.to_string())).await.unwrap();
+    socket
+}
+
+/// Next message, as a map of signal name to update
+async fn next_updates(socket: &mut Socket) -> serde_json::Map<String, Value> {
+    let message = tokio::time::timeout(Duration::from_secs(5), socket.next()).await
+        .expect("no message within 5 s").unwrap().unwrap();
+    let body: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+    body["updates"].as_array().unwrap().iter()
+        .map(|update| (update["name"].as_str().unwrap().to_string(), update.clone()))
+        .collect()
+}
+
+/// Start the API on a loopback port in front of a simulated-clock engine
+async fn start() -> Result<(ScanEngine, SignalBus, SocketAddr)> {
+    start_with(CONFIG).await
+}
+
+async fn start_with(yaml: &str) -> Result<(ScanEngine, SignalBus, SocketAddr)> {
+    let engine = ScanEngine::with_clock(PlcConfig::from_yaml(yaml)?, Arc::new(SimulatedClock::new()))?;
+    let bus = engine.signal_bus().clone();
+    
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let config = ApiConfig { bind: address.to_string() };
+    let server = ApiServer::new(&config, bus.clone(), engine.handle())?.with_reload(engine.reload_handle());
+    tokio::spawn(server.serve(listener));
+    
+    Ok((engine, bus, address))
+}
+
+#[tokio::test]
+async fn test_read_and_write_signals() -> Result<()> {
+    let (mut engine, bus, address) = start().await?;
+    
+    let (status, body) = request(address, "GET", "/api/signals", None).await;
+    assert_eq!(status, 200);
+    let names: Vec<&str> = body.as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["forces_active", "running", "setpoint", "start"]);
+    
+    let (status, body) = request(address, "GET", "/api/signals/setpoint", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["value"], json!(50.0));
+    
+    let (status, _) = request(address, "PUT", "/api/signals/start", Some(json!({ "value": true }))).await;
+    assert_eq!(status, 200);
+    let (status, _) = request(address, "PUT", "/api/signals/setpoint", Some(json!({ "value": 75 }))).await;
+    assert_eq!(status, 200, "integers are accepted for float signals");
+    assert!(!bus.get_bool("start")?, "queued until the next scan");
+    
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("running")?);
+    assert_eq!(bus.get("setpoint")?, SignalValue::Float(75.0));
+    
+    // Quality and timestamp come with every value
+    let (_, body) = request(address, "GET", "/api/signals/running", None).await;
+    assert_eq!(body["quality"], json!("good"));
+    assert!(body["timestamp"].as_u64().unwrap() > 0);
+    
+    request(address, "PUT", "/api/signals/start", Some(json!({ "value": true, "quality": "simulated" }))).await;
+    engine.execute_blocks()?;
+    let (_, body) = request(address, "GET", "/api/signals/start", None).await;
+    assert_eq!(body["quality"], json!("simulated"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_array_and_struct_elements() -> Result<()> {
+    let yaml = r#"
+types:
+  - name: "Pump"
+    fields:
+      - name: "run"
+        type: "bool"
+      - name: "hours"
+        type: "float"
+signals:
+  - name: "pumps"
+    type: "Pump"
+    array: [1, 2]
+blocks: []
+"#;
+    let (mut engine, bus, address) = start_with(yaml).await?;
+    
+    let (status, _) = request(address, "PUT", "/api/signals/pumps%5B2%5D.run", Some(json!({ "value": true }))).await;
+    assert_eq!(status, 200);
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("pumps[2].run")?);
+    
+    let (status, body) = request(address, "GET", "/api/signals?of=pumps%5B2%5D", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["name"], json!("pumps[2].run"));
+    assert_eq!(body[0]["value"], json!(true));
+    assert_eq!(body[1]["name"], json!("pumps[2].hours"));
+    
+    let (_, body) = request(address, "GET", "/api/signals?of=pumps", None).await;
+    assert_eq!(body.as_array().unwrap().len(), 4);
+    let (status, _) = request(address, "GET", "/api/signals?of=pump", None).await;
+    assert_eq!(status, 404);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bad_requests_are_rejected() -> Result<()> {
+    let (_engine, bus, address) = start().await?;
+    
+    let (status, body) = request(address, "GET", "/api/signals/missing", None).await;
+    assert_eq!(status, 404);
+    assert!(body["error"].as_str().unwrap().contains("missing"));
+    
+    let (status, _) = request(address, "PUT", "/api/signals/missing", Some(json!({ "value": true }))).await;
+    assert_eq!(status, 404);
+    assert!(bus.get("missing").is_err(), "unknown signals are not created");
+    
+    let (status, body) = request(address, "PUT", "/api/signals/start", Some(json!({ "value": 1.5 }))).await;
+    assert_eq!(status, 400);
+    assert!(body["error"].is_string());
+    
+    let mut config = PlcConfig::from_yaml(CONFIG)?;
+    config.api = Some(ApiConfig { bind: "nowhere".to_string() });
+    match config.validate() {
+        Err(PlcError::ValidationError(diagnostics)) => {
+            assert!(diagnostics[0].to_string().contains("invalid bind address 'nowhere'"));
+        }
+        other => panic!("expected validation failure, got {:?}", other),
+    }
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_status_and_pause() -> Result<()> {
+    let (mut engine, bus, address) = start().await?;
+    engine.run_simulated(Duration::from_millis(300))?;
+    
+    let (status, body) = request(address, "GET", "/api/status", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["scan_count"], json!(3));
+    assert_eq!(body["paused"], json!(false));
+    assert_eq!(body["faulted"], json!(false));
+    assert_eq!(body["tasks"][0]["interval_ms"], json!(100));
+    assert_eq!(body["tasks"][0]["executions"], json!(3));
+    
+    let (status, body) = request(address, "POST", "/api/engine/stop", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body["paused"], json!(true));
+    
+    // Writes still reach the image while paused, but no logic runs
+    bus.set("start", SignalValue::Bool(true))?;
+    engine.run_simulated(Duration::from_millis(300))?;
+    assert!(bus.get_bool("start")?);
+    assert!(!bus.get_bool("running")?);
+    let (_, body) = request(address, "GET", "/api/status", None).await;
+    assert_eq!(body["tasks"][0]["executions"], json!(3));
+    
+    request(address, "POST", "/api/engine/start", None).await;
+    engine.run_simulated(Duration::from_millis(100))?;
+    assert!(bus.get_bool("running")?);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_force_table() -> Result<()> {
+    let (mut engine, bus, address) = start().await?;
+    
+    let (status, body) = request(address, "PUT", "/api/forces/running", Some(json!({ "value": true, "by": "alice" }))).await;
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["signal"], json!("running"));
+    assert_eq!(body[0]["by"], json!("alice"));
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("running")?);
+    assert!(bus.get_bool("forces_active")?);
+    
+    let (status, _) = request(address, "PUT", "/api/signals/running", Some(json!({ "value": false }))).await;
+    assert_eq!(status, 409);
+    let (_, body) = request(address, "GET", "/api/status", None).await;
+    assert_eq!(body["forces"], json!(1));
+    
+    let (status, body) = request(address, "DELETE", "/api/forces/running", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body, json!([]));
+    let (status, _) = request(address, "DELETE", "/api/forces/running", None).await;
+    assert_eq!(status, 404);
+    
+    request(address, "PUT", "/api/forces/start", Some(json!({ "value": true }))).await;
+    request(address, "PUT", "/api/forces/setpoint", Some(json!({ "value": 10 }))).await;
+    let (_, body) = request(address, "DELETE", "/api/forces", Some(json!({ "by": "bob" }))).await;
+    assert_eq!(body["cleared"], json!(2));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_blocks() -> Result<()> {
+    let (_engine, _bus, address) = start().await?;
+    
+    let (status, body) = request(address, "GET", "/api/blocks", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["name"], json!("copy"));
+    assert_eq!(body[0]["type"], json!("OR"));
+    assert_eq!(body[0]["failed"], json!(false));
+    assert_eq!(body[0]["error_count"], json!(0));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_faults_are_read_and_cleared() -> Result<()> {
+    let yaml = r#"
+signals:
+  - name: "a"
+    type: "int"
+    initial: 7
+  - name: "b"
+    type: "int"
+  - name: "quotient"
+    type: "int"
+
+blocks:
+  - name: "divide"
+    type: "DIV"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "quotient"
+
+programs:
+  - name: "control"
+    blocks: ["divide"]
+    on_fault: "skip_block"
+
+tasks:
+  - name: "cyclic"
+    interval_ms: 100
+    programs: ["control"]
+"#;
+    let (mut engine, bus, address) = start_with(yaml).await?;
+    assert!(engine.run_simulated(Duration::from_millis(100)).is_err());
+    
+    let (status, body) = request(address, "GET", "/api/faults", None).await;
+    assert_eq!(status, 200);
+    assert_eq!(body.as_array().unwrap().len(), 1, "{}", body);
+    assert_eq!(body[0]["sequence"], json!(1));
+    assert_eq!(body[0]["kind"], json!("block_error"));
+    assert_eq!(body[0]["block"], json!("divide"));
+    assert_eq!(body[0]["reaction"], json!("skip_block"));
+    assert!(body[0]["message"].as_str().unwrap().contains("division by zero"));
+    assert!(body[0]["time"].as_u64().unwrap() > 0);
+    
+    let (_, body) = request(address, "GET", "/api/faults?since=1", None).await;
+    assert_eq!(body, json!([]));
+    
+    // The skipped block runs again once the faults are acknowledged
+    bus.set("b", SignalValue::Int(7))?;
+    engine.run_simulated(Duration::from_millis(100))?;
+    assert_eq!(bus.get_int("quotient")?, 0);
+    let (status, _) = request(address, "POST", "/api/faults/clear", None).await;
+    assert_eq!(status, 200);
+    engine.run_simulated(Duration::from_millis(100))?;
+    assert_eq!(bus.get_int("quotient")?, 1);
+    
+    // The history stays readable
+    let (_, body) = request(address, "GET", "/api/faults", None).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fault_stop_cannot_be_cleared_remotely() -> Result<()> {
+    let yaml = r#"
+signals:
+  - name: "a"
+    type: "int"
+    initial: 7
+  - name: "b"
+    type: "int"
+  - name: "quotient"
+    type: "int"
+
+blocks:
+  - name: "divide"
+    type: "DIV"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "quotient"
+
+on_fault: "stop_engine"
+scan_time_ms: 100
+"#;
+    let (mut engine, _bus, address) = start_with(yaml).await?;
+    assert!(engine.run_simulated(Duration::from_millis(100)).is_err());
+    assert!(engine.is_faulted());
+    
+    let (status, body) = request(address, "POST", "/api/faults/clear", None).await;
+    assert_eq!(status, 409);
+    assert!(body["error"].as_str().unwrap().contains("restart"), "{}", body);
+    assert!(engine.run_simulated(Duration::from_millis(100)).is_err(), "still stopped");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reload_dry_run_and_apply() -> Result<()> {
+    let (mut engine, _bus, address) = start().await?;
+    let changed = CONFIG.replace("type: \"OR\"", "type: \"AND\"")
+        .replace("scan_time_ms: 100", "  - name: \"stop\"\n    type: \"NOT\"\n    inputs:\n      in: \"start\"\n    outputs:\n      out: \"stopped\"\n\nscan_time_ms: 100")
+        .replace("signals:\n", "signals:\n  - name: \"stopped\"\n    type: \"bool\"\n");
+    let (status, report) = post_between_scans(&mut engine, address, "/api/reload?dry_run=true", changed.clone()).await;
+    assert_eq!(status, 200, "{}", report);
+    assert_eq!(report["added_signals"], json!(["stopped"]));
+    assert_eq!(report["added_blocks"], json!(["stop"]));
+    assert_eq!(report["replaced_blocks"], json!(["copy"]));
+    
+    let (status, body) = post_between_scans(&mut engine, address, "/api/reload?dry_run=true", changed.replace("in: \"start\"", "in: \"missing\"")).await;
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("signal 'missing' is not declared"), "{}", body);
+    
+    let (status, _) = request(address, "GET", "/api/signals/stopped", None).await;
+    assert_eq!(status, 404, "a dry run changes nothing");
+    
+    let (status, report) = post_between_scans(&mut engine, address, "/api/reload", changed).await;
+    assert_eq!(status, 200, "{}", report);
+    assert_eq!(report["added_blocks"], json!(["stop"]));
+    let (_, body) = request(address, "GET", "/api/signals/stopped", None).await;
+    assert_eq!(body["value"], json!(true));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_streams_changes() -> Result<()> {
+    let (mut engine, bus, address) = start().await?;
+    let mut socket = subscribe(address, json!({ "subscribe": ["run*", "setpoint"], "deadband": 0.5 })).await;
+    
+    // Current values first
+    let updates = next_updates(&mut socket).await;
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates["setpoint"]["value"], json!(50.0));
+    assert_eq!(updates["running"]["value"], json!(false));
+    
+    // Within the deadband, then outside it
+    bus.set("setpoint", SignalValue::Float(50.2))?;
+    engine.run_simulated(Duration::from_millis(100))?;
+    bus.set("setpoint", SignalValue::Float(50.6))?;
+    bus.set("start", SignalValue::Bool(true))?;
+    engine.run_simulated(Duration::from_millis(100))?;
+    
+    let updates = next_updates(&mut socket).await;
+    assert_eq!(updates.len(), 2, "start is not subscribed");
+    assert_eq!(updates["setpoint"]["value"], json!(50.6));
+    assert_eq!(updates["running"]["value"], json!(true));
+    assert_eq!(updates["running"]["scan"], json!(2));
+    assert_eq!(updates["running"]["quality"], json!("good"));
+    assert!(updates["running"]["timestamp"].as_u64().unwrap() > 0);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_rate_limit() -> Result<()> {
+    let (mut engine, bus, address) = start().await?;
+    let mut socket = subscribe(address, json!({ "subscribe": ["setpoint"], "min_interval_ms": 300 })).await;
+    next_updates(&mut socket).await;
+    
+    // Three scans in quick succession arrive as one message with the latest value
+    for value in [1.0, 2.0, 3.0] {
+        bus.set("setpoint", SignalValue::Float(value))?;
+        engine.run_simulated(Duration::from_millis(100))?;
+    }
+    let updates = next_updates(&mut socket).await;
+    assert_eq!(updates["setpoint"]["value"], json!(3.0));
+    assert_eq!(updates["setpoint"]["scan"], json!(3));
+    
+    socket.send(Message::Text("not json".to_string())).await.unwrap();
+    let message = socket.next().await.unwrap().unwrap();
+    assert!(message.to_text().unwrap().contains("invalid subscription"));
+    
+    Ok(())
+}
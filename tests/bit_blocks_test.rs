@@ -0,0 +1,133 @@
+mod common;
+
+use common::{block, run};
+use soft_plc::{
+    signal::{SignalBus, SignalType, SignalValue},
+    blocks::create_block,
+    engine::{PlcConfig, SimulatedClock},
+    PlcError, Result,
+};
+
+#[test]
+fn test_real_to_int_rounding() -> Result<()> {
+    let convert = |rounding: &str, value: f64| {
+        let params = [("rounding", serde_yaml::Value::from(rounding))];
+        run(&block("LREAL_TO_DINT", &[("in", "x")], &[("out", "out")], &params), &[("x", SignalValue::Float(value))], SignalType::Int)
+    };
+    assert_eq!(convert("nearest", 2.5)?, SignalValue::Int(3));
+    assert_eq!(convert("nearest", -2.5)?, SignalValue::Int(-3));
+    assert_eq!(convert("even", 2.5)?, SignalValue::Int(2));
+    assert_eq!(convert("truncate", -2.7)?, SignalValue::Int(-2));
+    assert_eq!(convert("floor", -2.2)?, SignalValue::Int(-3));
+    assert_eq!(convert("ceil", 2.2)?, SignalValue::Int(3));
+    assert!(matches!(convert("ceil", 3e9), Err(PlcError::ConversionError(_))));
+    assert!(matches!(convert("up", 1.0), Err(PlcError::ConfigError(_))));
+    
+    // Only conversions from a real round
+    let params = [("rounding", serde_yaml::Value::from("floor"))];
+    let config = block("INT_TO_REAL", &[("in", "x")], &[("out", "out")], &params);
+    assert!(create_block(&config, &SignalBus::new()).is_err());
+    
+    let config = block("BOOL_TO_INT", &[("in", "x")], &[("out", "out")], &[]);
+    assert_eq!(run(&config, &[("x", SignalValue::Bool(true))], SignalType::Int)?, SignalValue::Int(1));
+    
+    Ok(())
+}
+
+#[test]
+fn test_bitwise_and_shifts() -> Result<()> {
+    let two = |block_type: &str, a: SignalValue, b: SignalValue| {
+        let out_type = a.signal_type();
+        run(&block(block_type, &[("in1", "a"), ("in2", "b")], &[("out", "out")], &[]), &[("a", a), ("b", b)], out_type)
+    };
+    assert_eq!(two("BAND", SignalValue::Word(0xFF0F), SignalValue::Word(0x0FF0))?, SignalValue::Word(0x0F00));
+    assert_eq!(two("BOR", SignalValue::Word(0xF000), SignalValue::Word(0x000F))?, SignalValue::Word(0xF00F));
+    assert_eq!(two("BXOR", SignalValue::SInt(-1), SignalValue::SInt(0x0F))?, SignalValue::SInt(-16));
+    
+    let shift = |block_type: &str, value: SignalValue, n: i32| {
+        let out_type = value.signal_type();
+        let config = block(block_type, &[("in", "x"), ("n", "n")], &[("out", "out")], &[]);
+        run(&config, &[("x", value), ("n", SignalValue::Int(n))], out_type)
+    };
+    assert_eq!(shift("SHL", SignalValue::USInt(0b1000_0001), 1)?, SignalValue::USInt(0b0000_0010));
+    assert_eq!(shift("SHR", SignalValue::SInt(-128), 7)?, SignalValue::SInt(1));
+    assert_eq!(shift("SHL", SignalValue::Word(0xFFFF), 16)?, SignalValue::Word(0));
+    assert_eq!(shift("ROL", SignalValue::USInt(0b1000_0001), 1)?, SignalValue::USInt(0b0000_0011));
+    assert_eq!(shift("ROR", SignalValue::Word(0x0001), 17)?, SignalValue::Word(0x8000));
+    assert_eq!(shift("ROL", SignalValue::ULInt(1 << 63), 1)?, SignalValue::ULInt(1));
+    assert!(shift("SHL", SignalValue::Word(1), -1).is_err());
+    
+    Ok(())
+}
+
+#[test]
+fn test_pack_unpack_and_bcd() -> Result<()> {
+    let pack = block("PACK", &[("bit0", "a"), ("bit15", "b")], &[("out", "out")], &[]);
+    let bits = [("a", SignalValue::Bool(true)), ("b", SignalValue::Bool(true))];
+    assert_eq!(run(&pack, &bits, SignalType::Word)?, SignalValue::Word(0x8001));
+    assert_eq!(run(&pack, &bits, SignalType::Int16)?, SignalValue::Int16(i16::MIN + 1));
+    assert!(run(&pack, &bits, SignalType::USInt).unwrap_err().to_string().contains("usint has no bit15"));
+    
+    let bus = SignalBus::new();
+    bus.declare("status", SignalType::Word);
+    bus.set("status", SignalValue::Word(0x0005))?;
+    let unpack = block("UNPACK", &[("in", "status")], &[("bit0", "ready"), ("bit1", "fault"), ("bit2", "run")], &[]);
+    create_block(&unpack, &bus)?.execute(&bus, &SimulatedClock::new())?;
+    assert!(bus.get_bool("ready")?);
+    assert!(!bus.get_bool("fault")?);
+    assert!(bus.get_bool("run")?);
+    
+    let bcd = |block_type: &str, value: SignalValue, out_type: SignalType| {
+        run(&block(block_type, &[("in", "x")], &[("out", "out")], &[]), &[("x", value)], out_type)
+    };
+    assert_eq!(bcd("BCD_TO_INT", SignalValue::Word(0x1234), SignalType::Int)?, SignalValue::Int(1234));
+    assert!(bcd("BCD_TO_INT", SignalValue::Word(0x12A4), SignalType::Int).unwrap_err().to_string().contains("16#12A4 is not a BCD value"));
+    assert_eq!(bcd("INT_TO_BCD", SignalValue::Int(9876), SignalType::Word)?, SignalValue::Word(0x9876));
+    assert!(bcd("INT_TO_BCD", SignalValue::Int(12345), SignalType::Word).is_err());
+    assert!(bcd("INT_TO_BCD", SignalValue::Int(-1), SignalType::Word).is_err());
+    
+    Ok(())
+}
+
+#[test]
+fn test_bit_block_ports_are_validated() -> Result<()> {
+    let config = PlcConfig::from_yaml(r#"
+strict_types: true
+signals:
+  - name: "status"
+    type: "word"
+  - name: "mask"
+    type: "uint"
+  - name: "masked"
+    type: "word"
+  - name: "count"
+    type: "usint"
+  - name: "shifted"
+    type: "word"
+blocks:
+  - name: "mask_status"
+    type: "BAND"
+    inputs:
+      in1: "status"
+      in2: "mask"
+    outputs:
+      out: "masked"
+  - name: "shift"
+    type: "SHL"
+    inputs:
+      in: "masked"
+      n: "count"
+    outputs:
+      out: "shifted"
+scan_time_ms: 100
+"#)?;
+    let messages = match config.validate() {
+        Err(PlcError::ValidationError(diagnostics)) => diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+        other => panic!("expected validation failure, got {:?}", other),
+    };
+    // The shift count may be any integer, but the masked operands must agree
+    assert_eq!(messages.len(), 1, "{:?}", messages);
+    assert!(messages[0].contains("'mask' is uint but 'status' is word"), "{:?}", messages);
+    
+    Ok(())
+}
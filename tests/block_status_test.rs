@@ -0,0 +1,108 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine},
+    Result,
+};
+
+const STATUS_CONFIG: &str = r#"
+signals:
+  - name: "a"
+    type: "int"
+    initial: 8
+  - name: "b"
+    type: "int"
+    initial: 2
+  - name: "quotient"
+    type: "int"
+  - name: "doubled"
+    type: "int"
+  - name: "divide_ok"
+    type: "bool"
+  - name: "two"
+    type: "int"
+    initial: 2
+  - name: "two_ok"
+    type: "bool"
+
+blocks:
+  - name: "divide"
+    type: "DIV"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "quotient"
+      eno: "divide_ok"
+
+  - name: "double"
+    type: "MUL"
+    inputs:
+      in1: "quotient"
+      in2: "two"
+    outputs:
+      out: "doubled"
+
+  - name: "constant"
+    type: "CONST"
+    inputs: {}
+    params:
+      value: 2
+    outputs:
+      out: "two"
+      eno: "two_ok"
+
+scan_time_ms: 100
+"#;
+
+#[test]
+fn test_failed_block_is_isolated_and_counted() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(STATUS_CONFIG)?)?;
+    let bus = engine.signal_bus().clone();
+    
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_int("doubled")?, 8);
+    assert!(bus.get_bool("divide_ok")?);
+    assert!(bus.get_bool("two_ok")?);
+    
+    bus.set("b", SignalValue::Int(0))?;
+    bus.set("a", SignalValue::Int(6))?;
+    assert!(engine.execute_blocks().is_err());
+    assert!(engine.execute_blocks().is_err());
+    
+    // Downstream blocks keep running on the last good quotient
+    assert_eq!(bus.get_int("quotient")?, 4);
+    assert_eq!(bus.get_int("doubled")?, 8);
+    assert!(!bus.get_bool("divide_ok")?);
+    assert!(bus.get_bool("two_ok")?);
+    
+    let status = engine.block_status().iter().find(|status| status.name == "divide").unwrap();
+    assert!(status.failed);
+    assert_eq!(status.error_count, 2);
+    assert!(status.last_error.as_ref().unwrap().contains("division by zero"));
+    assert_eq!(engine.faults().entries().len(), 1, "a repeated error is recorded once");
+    
+    bus.set("b", SignalValue::Int(3))?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_int("doubled")?, 4);
+    assert!(bus.get_bool("divide_ok")?);
+    
+    let status = engine.block_status().iter().find(|status| status.name == "divide").unwrap();
+    assert!(!status.failed);
+    assert_eq!(status.error_count, 2, "counters survive recovery");
+    assert!(status.last_error.is_some());
+    
+    Ok(())
+}
+
+#[test]
+fn test_eno_must_be_a_bool_signal() -> Result<()> {
+    let diagnostics = PlcConfig::from_yaml(STATUS_CONFIG)?.diagnostics();
+    assert!(diagnostics.is_empty(), "{:#?}", diagnostics);
+    
+    let yaml = STATUS_CONFIG.replace("eno: \"divide_ok\"", "eno: \"doubled\"");
+    let diagnostics = PlcConfig::from_yaml(&yaml)?.diagnostics();
+    assert!(diagnostics[0].message.contains("produces bool"), "{:#?}", diagnostics);
+    assert_eq!((diagnostics[0].block.as_deref(), diagnostics[0].port.as_deref()), (Some("divide"), Some("eno")));
+    
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use soft_plc::{
+    signal::{SignalBus, SignalType, SignalValue},
+    blocks::{create_block, BlockConfig},
+    engine::SimulatedClock,
+    Result,
+};
+use std::collections::HashMap;
+
+/// Configuration of a block named `test`
+pub fn block(block_type: &str, inputs: &[(&str, &str)], outputs: &[(&str, &str)], params: &[(&str, serde_yaml::Value)]) -> BlockConfig {
+    let map = |ports: &[(&str, &str)]| ports.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>();
+    BlockConfig {
+        name: "test".to_string(),
+        block_type: block_type.to_string(),
+        inputs: map(inputs),
+        outputs: map(outputs),
+        params: params.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        retain: false,
+    }
+}
+
+/// Run one block once on a bus where `out` is declared as `out_type`
+pub fn run(config: &BlockConfig, values: &[(&str, SignalValue)], out_type: SignalType) -> Result<SignalValue> {
+    let bus = SignalBus::new();
+    for (name, value) in values {
+        bus.declare(name, value.signal_type());
+        bus.set(name, value.clone())?;
+    }
+    bus.declare("out", out_type);
+    let mut created = create_block(config, &bus)?;
+    created.execute(&bus, &SimulatedClock::new())?;
+    bus.get("out")
+}
@@ -0,0 +1,120 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine},
+    PlcError, Result,
+};
+
+fn position(order: &[&str], name: &str) -> usize {
+    order.iter().position(|&n| n == name).unwrap()
+}
+
+#[test]
+fn test_producers_run_before_consumers() -> Result<()> {
+    let config = PlcConfig::from_yaml(include_str!("../config/advanced_example.yaml"))?;
+    let engine = ScanEngine::new(config)?;
+    let order = engine.execution_order();
+    
+    assert!(position(&order, "const_max_speed") < position(&order, "speed_limit_check"));
+    assert!(position(&order, "const_false") < position(&order, "part_counter"));
+    assert!(position(&order, "emergency_check") < position(&order, "conveyor_control"));
+    assert!(position(&order, "alarm_timer") < position(&order, "speed_alarm"));
+    
+    // Blocks with no data dependency between them keep their configured order
+    assert!(position(&order, "start_trigger") < position(&order, "part_trigger"));
+    
+    Ok(())
+}
+
+const LOOP_CONFIG: &str = r#"
+signals:
+  - name: "enable"
+    type: "bool"
+    initial: true
+
+blocks:
+  - name: "first"
+    type: "AND"
+    inputs:
+      in1: "enable"
+      in2: "b"
+    outputs:
+      out: "a"
+
+  - name: "second"
+    type: "NOT"
+    inputs:
+      in: "a"
+    outputs:
+      out: "b"
+"#;
+
+#[test]
+fn test_algebraic_loop_is_rejected() -> Result<()> {
+    let config = PlcConfig::from_yaml(LOOP_CONFIG)?;
+    
+    match ScanEngine::new(config) {
+        Err(PlcError::AlgebraicLoop(cycle)) => {
+            assert_eq!(cycle.first(), cycle.last());
+            assert!(cycle.contains(&"first".to_string()));
+            assert!(cycle.contains(&"second".to_string()));
+        }
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("loop was not detected"),
+    }
+    
+    Ok(())
+}
+
+const DELAY_CONFIG: &str = r#"
+signals:
+  - name: "enable"
+    type: "bool"
+    initial: true
+
+blocks:
+  - name: "toggle"
+    type: "AND"
+    inputs:
+      in1: "enable"
+      in2: "not_b"
+    outputs:
+      out: "a"
+
+  - name: "invert"
+    type: "NOT"
+    inputs:
+      in: "b"
+    outputs:
+      out: "not_b"
+
+  - name: "previous_a"
+    type: "UNIT_DELAY"
+    inputs:
+      in: "a"
+    outputs:
+      out: "b"
+    params:
+      initial: false
+"#;
+
+#[test]
+fn test_unit_delay_breaks_loop() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(DELAY_CONFIG)?)?;
+    assert_eq!(engine.execution_order(), vec!["previous_a", "invert", "toggle"]);
+    
+    let mut outputs = Vec::new();
+    for _ in 0..4 {
+        engine.execute_blocks()?;
+        outputs.push(engine.signal_bus().get("a")?);
+    }
+    
+    // a = enable AND NOT (last scan's a)
+    assert_eq!(outputs, vec![
+        SignalValue::Bool(true),
+        SignalValue::Bool(false),
+        SignalValue::Bool(true),
+        SignalValue::Bool(false),
+    ]);
+    
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{FaultKind, FaultPolicy, PlcConfig, ScanEngine, SimulatedClock},
+    Result,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+const FAULT_CONFIG: &str = r#"
+signals:
+  - name: "a"
+    type: "int"
+    initial: 7
+  - name: "b"
+    type: "int"
+    initial: 2
+  - name: "quotient"
+    type: "int"
+  - name: "enable"
+    type: "bool"
+    initial: true
+  - name: "pump_run"
+    type: "bool"
+    safe_value: false
+  - name: "heater_on"
+    type: "bool"
+    initial: true
+    safe_value: false
+  - name: "heartbeat"
+    type: "bool"
+
+blocks:
+  - name: "divide"
+    type: "DIV"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "quotient"
+
+  - name: "pump_logic"
+    type: "AND"
+    inputs:
+      in1: "enable"
+      in2: "enable"
+    outputs:
+      out: "pump_run"
+
+  - name: "blink"
+    type: "NOT"
+    inputs:
+      in: "heartbeat"
+    outputs:
+      out: "heartbeat"
+
+programs:
+  - name: "control"
+    blocks: ["divide", "pump_logic"]
+    on_fault: "continue"
+
+tasks:
+  - name: "cyclic"
+    interval_ms: 10
+    programs: ["control"]
+"#;
+
+fn engine(policy: &str) -> Result<ScanEngine> {
+    let yaml = FAULT_CONFIG.replace("on_fault: \"continue\"", &format!("on_fault: \"{}\"", policy));
+    ScanEngine::new(PlcConfig::from_yaml(&yaml)?)
+}
+
+#[test]
+fn test_continue_records_fault_once_and_runs_other_blocks() -> Result<()> {
+    let mut engine = engine("continue")?;
+    let bus = engine.signal_bus().clone();
+    
+    bus.set("b", SignalValue::Int(0))?;
+    assert!(engine.execute_blocks().is_err());
+    bus.set("enable", SignalValue::Bool(false))?;
+    assert!(engine.execute_blocks().is_err());
+    
+    assert!(!bus.get_bool("pump_run")?, "blocks after the failed one still run");
+    assert!(!engine.is_faulted());
+    
+    let faults = engine.faults().entries();
+    assert_eq!(faults.len(), 1, "{:#?}", faults);
+    assert_eq!(faults[0].kind, FaultKind::BlockError);
+    assert_eq!(faults[0].reaction, FaultPolicy::Continue);
+    assert_eq!((faults[0].task.as_str(), faults[0].program.as_deref(), faults[0].block.as_deref()),
+        ("cyclic", Some("control"), Some("divide")));
+    assert!(faults[0].message.contains("division by zero"));
+    
+    bus.set("b", SignalValue::Int(7))?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_int("quotient")?, 1);
+    
+    Ok(())
+}
+
+#[test]
+fn test_skip_block_until_faults_are_cleared() -> Result<()> {
+    let mut engine = engine("skip_block")?;
+    let bus = engine.signal_bus().clone();
+    
+    bus.set("b", SignalValue::Int(0))?;
+    assert!(engine.execute_blocks().is_err());
+    
+    bus.set("b", SignalValue::Int(7))?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_int("quotient")?, 0, "skipped");
+    
+    engine.clear_faults();
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_int("quotient")?, 1);
+    
+    Ok(())
+}
+
+#[test]
+fn test_safe_state_holds_program_outputs() -> Result<()> {
+    let mut engine = engine("safe_state")?;
+    let bus = engine.signal_bus().clone();
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("pump_run")?);
+    
+    bus.set("b", SignalValue::Int(0))?;
+    assert!(engine.execute_blocks().is_err());
+    assert!(!bus.get_bool("pump_run")?);
+    
+    // The program stays in its safe state, the rest of the PLC keeps running
+    bus.set("b", SignalValue::Int(7))?;
+    bus.set("pump_run", SignalValue::Bool(true))?;
+    let heartbeat = bus.get_bool("heartbeat")?;
+    engine.execute_blocks()?;
+    assert!(!bus.get_bool("pump_run")?);
+    assert_ne!(bus.get_bool("heartbeat")?, heartbeat);
+    assert!(bus.get_bool("heater_on")?, "signals outside the program are left alone");
+    
+    engine.clear_faults();
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("pump_run")?);
+    
+    Ok(())
+}
+
+#[test]
+fn test_stop_engine_drives_every_safe_state() -> Result<()> {
+    let clock = SimulatedClock::new();
+    let yaml = FAULT_CONFIG.replace("on_fault: \"continue\"", "on_fault: \"stop_engine\"");
+    let mut engine = ScanEngine::with_clock(PlcConfig::from_yaml(&yaml)?, Arc::new(clock.clone()))?;
+    let bus = engine.signal_bus().clone();
+    
+    bus.set("b", SignalValue::Int(0))?;
+    assert!(engine.run_simulated(Duration::from_millis(100)).is_err());
+    
+    assert!(engine.is_faulted());
+    assert!(!bus.get_bool("pump_run")?);
+    assert!(!bus.get_bool("heater_on")?);
+    assert!(engine.scan().is_err(), "no scans until faults are cleared");
+    
+    bus.set("b", SignalValue::Int(7))?;
+    engine.clear_faults();
+    clock.advance(Duration::from_millis(10));
+    engine.scan()?;
+    assert_eq!(bus.get_int("quotient")?, 1);
+    
+    Ok(())
+}
+
+#[test]
+fn test_watchdog_trips_on_repeated_overruns() -> Result<()> {
+    let clock = SimulatedClock::new();
+    let yaml = format!("{}\nwatchdog:\n  max_overruns: 2\n", FAULT_CONFIG);
+    let mut engine = ScanEngine::with_clock(PlcConfig::from_yaml(&yaml)?, Arc::new(clock.clone()))?;
+    
+    engine.scan()?;
+    clock.advance(Duration::from_millis(25));
+    engine.scan()?;
+    assert!(!engine.is_faulted(), "a single overrun is tolerated");
+    
+    clock.advance(Duration::from_millis(25));
+    engine.scan()?;
+    assert!(engine.is_faulted());
+    assert!(!engine.signal_bus().get_bool("heater_on")?);
+    
+    let faults = engine.faults().entries();
+    assert_eq!(faults.len(), 1);
+    assert_eq!((faults[0].kind, faults[0].task.as_str()), (FaultKind::RepeatedOverruns, "cyclic"));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_watchdog_trips_on_slow_task() -> Result<()> {
+    // Enough blocks that one execution takes well over a millisecond
+    let mut yaml = String::from("signals:\n  - name: \"one\"\n    type: \"int\"\n    initial: 1\n  - name: \"valve\"\n    type: \"bool\"\n    initial: true\n    safe_value: false\nblocks:\n");
+    for index in 0..20000 {
+        yaml.push_str(&format!(
+            "  - name: \"add{0}\"\n    type: \"ADD\"\n    inputs:\n      in1: \"one\"\n      in2: \"one\"\n    outputs:\n      out: \"sum{0}\"\n",
+            index));
+    }
+    yaml.push_str("scan_time_ms: 1000\nwatchdog:\n  timeout_ms: 1\n");
+    
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(&yaml)?)?;
+    tokio::time::timeout(Duration::from_secs(10), engine.run()).await.expect("engine stops")?;
+    
+    assert!(engine.is_faulted());
+    assert!(!engine.is_running());
+    assert!(!engine.signal_bus().get_bool("valve")?);
+    assert_eq!(engine.faults().entries()[0].kind, FaultKind::WatchdogTimeout);
+    
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use soft_plc::{
+    signal::{Quality, SignalValue, FORCES_ACTIVE},
+    engine::{PlcConfig, ScanEngine},
+    PlcError, Result,
+};
+use std::time::SystemTime;
+
+const CONFIG: &str = r#"
+signals:
+  - name: "start"
+    type: "bool"
+  - name: "motor"
+    type: "bool"
+  - name: "lamp"
+    type: "bool"
+blocks:
+  - name: "run"
+    type: "OR"
+    inputs:
+      in1: "start"
+      in2: "start"
+    outputs:
+      out: "motor"
+  - name: "indicate"
+    type: "OR"
+    inputs:
+      in1: "motor"
+      in2: "motor"
+    outputs:
+      out: "lamp"
+"#;
+
+#[test]
+fn test_forced_signals_ignore_writes() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(CONFIG)?)?;
+    let bus = engine.signal_bus().clone();
+    assert!(!bus.get_bool(FORCES_ACTIVE)?);
+    
+    // Forcing an output overrides the logic, downstream blocks see the force
+    let before = SystemTime::now();
+    bus.force("motor", SignalValue::Bool(true), "commissioning")?;
+    assert!(bus.get_bool("motor")?, "takes effect at once");
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("motor")?);
+    assert_eq!(bus.quality("motor")?, Quality::Forced);
+    assert!(bus.get_bool("lamp")?);
+    assert_eq!(bus.quality("lamp")?, Quality::Good);
+    assert!(bus.get_bool(FORCES_ACTIVE)?);
+    
+    // Forcing an input overrides external writes
+    bus.force("start", SignalValue::Bool(false), "commissioning")?;
+    bus.set("start", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    assert!(!bus.get_bool("start")?);
+    
+    let forces = bus.forces();
+    assert_eq!(forces.len(), 2);
+    assert_eq!(forces[0].signal, "motor");
+    assert_eq!(forces[0].by, "commissioning");
+    assert!(forces[0].since >= before);
+    assert!(bus.is_forced("start"));
+    
+    // Removed forces hand the signal back to whoever writes it next
+    assert!(bus.unforce("motor", "commissioning"));
+    assert!(!bus.unforce("motor", "commissioning"));
+    engine.execute_blocks()?;
+    assert!(!bus.get_bool("motor")?);
+    assert_eq!(bus.quality("motor")?, Quality::Good);
+    assert!(bus.get_bool(FORCES_ACTIVE)?, "start is still forced");
+    
+    assert_eq!(bus.clear_forces("commissioning"), 1);
+    assert!(bus.forces().is_empty());
+    assert!(!bus.get_bool(FORCES_ACTIVE)?);
+    bus.set("start", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("motor")?);
+    
+    Ok(())
+}
+
+#[test]
+fn test_force_errors() -> Result<()> {
+    let engine = ScanEngine::new(PlcConfig::from_yaml(CONFIG)?)?;
+    let bus = engine.signal_bus().clone();
+    
+    assert!(matches!(bus.force("missing", SignalValue::Bool(true), "me"), Err(PlcError::SignalNotFound(_))));
+    assert!(matches!(bus.force("motor", SignalValue::Int(1), "me"), Err(PlcError::TypeMismatch { .. })));
+    assert!(bus.force(FORCES_ACTIVE, SignalValue::Bool(true), "me").is_err());
+    
+    // The indicator cannot be written by anyone else either
+    bus.set(FORCES_ACTIVE, SignalValue::Bool(true))?;
+    assert!(!bus.get_bool(FORCES_ACTIVE)?);
+    
+    let mut config = PlcConfig::from_yaml(CONFIG)?;
+    config.blocks[1].outputs.insert("out".to_string(), FORCES_ACTIVE.to_string());
+    let diagnostics = config.diagnostics();
+    assert!(diagnostics.iter().any(|d| d.message.contains("maintained by the force table")), "{:#?}", diagnostics);
+    
+    Ok(())
+}
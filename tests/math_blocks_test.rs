@@ -0,0 +1,133 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine},
+    PlcError, Result,
+};
+
+const MATH_CONFIG: &str = r#"
+signals:
+  - name: "a"
+    type: "int"
+    initial: 7
+  - name: "b"
+    type: "int"
+    initial: 2
+  - name: "c"
+    type: "int"
+    initial: 1
+  - name: "x"
+    type: "float"
+    initial: 1.5
+
+blocks:
+  - name: "sum"
+    type: "ADD"
+    inputs:
+      in1: "a"
+      in2: "b"
+      in3: "c"
+    outputs:
+      out: "sum_out"
+
+  - name: "difference"
+    type: "SUB"
+    inputs:
+      in1: "a"
+      in2: "b"
+      in3: "c"
+    outputs:
+      out: "sub_out"
+
+  - name: "product"
+    type: "MUL"
+    inputs:
+      in1: "a"
+      in2: "x"
+    outputs:
+      out: "mul_out"
+
+  - name: "quotient"
+    type: "DIV"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "div_out"
+
+  - name: "remainder"
+    type: "MOD"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "mod_out"
+
+  - name: "negate"
+    type: "NEG"
+    inputs:
+      in: "a"
+    outputs:
+      out: "neg_out"
+
+  - name: "absolute"
+    type: "ABS"
+    inputs:
+      in: "neg_out"
+    outputs:
+      out: "abs_out"
+"#;
+
+#[test]
+fn test_arithmetic_with_promotion() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(MATH_CONFIG)?)?;
+    engine.execute_blocks()?;
+    
+    let bus = engine.signal_bus();
+    assert_eq!(bus.get("sum_out")?, SignalValue::Int(10));
+    assert_eq!(bus.get("sub_out")?, SignalValue::Int(4));
+    assert_eq!(bus.get("mul_out")?, SignalValue::Float(10.5));
+    assert_eq!(bus.get("div_out")?, SignalValue::Int(3));
+    assert_eq!(bus.get("mod_out")?, SignalValue::Int(1));
+    assert_eq!(bus.get("neg_out")?, SignalValue::Int(-7));
+    assert_eq!(bus.get("abs_out")?, SignalValue::Int(7));
+    
+    Ok(())
+}
+
+#[test]
+fn test_division_by_zero_is_an_error() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(MATH_CONFIG)?)?;
+    engine.signal_bus().set("b", SignalValue::Int(0))?;
+    
+    let err = engine.execute_blocks().unwrap_err();
+    assert!(matches!(err, PlcError::ExecutionError(ref msg) if msg.contains("division by zero")));
+    
+    Ok(())
+}
+
+#[test]
+fn test_integer_overflow_is_an_error() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(MATH_CONFIG)?)?;
+    engine.signal_bus().set("a", SignalValue::Int(i32::MAX))?;
+    
+    let err = engine.execute_blocks().unwrap_err();
+    assert!(matches!(err, PlcError::ExecutionError(ref msg) if msg.contains("overflow")));
+    
+    Ok(())
+}
+
+#[test]
+fn test_bool_operand_is_rejected() -> Result<()> {
+    let yaml = MATH_CONFIG.replace("  - name: \"b\"\n    type: \"int\"\n    initial: 2", "  - name: \"b\"\n    type: \"bool\"\n    initial: true");
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(&yaml)?)?;
+    
+    let err = engine.execute_blocks().unwrap_err();
+    assert!(matches!(err, PlcError::TypeMismatch { .. }), "{:?}", err);
+    
+    // The bus keeps declared types, so a bool written to an int never reaches the block
+    let engine = ScanEngine::new(PlcConfig::from_yaml(MATH_CONFIG)?)?;
+    let err = engine.signal_bus().set("b", SignalValue::Bool(true)).unwrap_err();
+    assert!(matches!(err, PlcError::TypeMismatch { .. }));
+    
+    Ok(())
+}
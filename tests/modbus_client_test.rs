@@ -0,0 +1,184 @@
+use soft_plc::{
+    signal::{Quality, SignalBus, SignalValue},
+    engine::{PlcConfig, ScanEngine},
+    modbus::{ModbusServer, ModbusServerConfig},
+    Result,
+};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const DEVICE_SERVER: &str = r#"
+bind: "127.0.0.1:0"
+coils:
+  - address: 0
+    signal: "remote_pump"
+holding_registers:
+  - address: 0
+    signal: "remote_level"
+    type: "float32"
+  - address: 2
+    signal: "remote_code"
+"#;
+
+fn plc_config(address: &str) -> String {
+    format!(r#"
+scan_time_ms: 10
+signals:
+  - name: "level"
+    type: "float"
+  - name: "pump_cmd"
+    type: "bool"
+    initial: true
+  - name: "level_ok"
+    type: "bool"
+blocks:
+  - name: "level_ok"
+    type: "NOT"
+    inputs:
+      in: "level_comm_fault"
+    outputs:
+      out: "level_ok"
+modbus_devices:
+  - name: "tank"
+    tcp: "{}"
+    timeout_ms: 100
+    retries: 1
+    poll_ms: 10
+    poll_groups:
+      - table: "holding_registers"
+        signals:
+          - address: 0
+            signal: "level"
+            type: "float32"
+      - table: "coils"
+        direction: "write"
+        signals:
+          - address: 0
+            signal: "pump_cmd"
+"#, address)
+}
+
+/// Serve a simulated device on a loopback port, returning its bus and address
+async fn start_device() -> Result<(SignalBus, String)> {
+    let bus = SignalBus::new();
+    bus.set("remote_level", SignalValue::Float(0.0))?;
+    bus.set("remote_pump", SignalValue::Bool(false))?;
+    bus.set("remote_code", SignalValue::UInt(0))?;
+    let config: ModbusServerConfig = serde_yaml::from_str(DEVICE_SERVER).unwrap();
+    
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?.to_string();
+    tokio::spawn(ModbusServer::new(&config, bus.clone())?.serve(listener));
+    
+    Ok((bus, address))
+}
+
+/// Scan until `done` holds, failing after two seconds
+async fn scan_until(engine: &mut ScanEngine, done: impl Fn(&SignalBus) -> bool) -> Result<()> {
+    for _ in 0..200 {
+        engine.scan()?;
+        if done(engine.signal_bus()) {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("condition not reached: {:?}", engine.dump_signals());
+}
+
+#[tokio::test]
+async fn test_poll_groups_exchange_with_device() -> Result<()> {
+    let (device, address) = start_device().await?;
+    device.set("remote_level", SignalValue::Float(42.5))?;
+    
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(&plc_config(&address))?)?;
+    let bus = engine.signal_bus().clone();
+    assert!(bus.get_bool("level_comm_fault")?, "faulted until the first poll");
+    engine.scan()?;
+    assert_eq!(bus.quality("level")?, Quality::CommFault);
+    
+    engine.start_io();
+    scan_until(&mut engine, |bus| !bus.get_bool("level_comm_fault").unwrap()).await?;
+    assert_eq!(bus.get("level")?, SignalValue::Float(42.5));
+    assert_eq!(bus.quality("level")?, Quality::Good);
+    
+    // Logic sees the latched fault flag in the same scan
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("level_ok")?);
+    
+    // Outputs are written after logic
+    scan_until(&mut engine, |_| device.get_bool("remote_pump").unwrap()).await?;
+    assert!(!bus.get_bool("pump_cmd_comm_fault")?);
+    
+    bus.set("pump_cmd", SignalValue::Bool(false))?;
+    scan_until(&mut engine, |_| !device.get_bool("remote_pump").unwrap()).await?;
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unreachable_device_raises_fault() -> Result<()> {
+    // Reserve a port and close it again so nothing is listening
+    let address = TcpListener::bind("127.0.0.1:0").await?.local_addr()?.to_string();
+    
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(&plc_config(&address))?)?;
+    let bus = engine.signal_bus().clone();
+    bus.set("level", SignalValue::Float(7.0))?;
+    
+    engine.start_io();
+    for _ in 0..20 {
+        engine.scan()?;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    
+    assert!(bus.get_bool("level_comm_fault")?);
+    assert!(bus.get_bool("pump_cmd_comm_fault")?);
+    assert!(!bus.get_bool("level_ok")?);
+    assert_eq!(bus.get("level")?, SignalValue::Float(7.0), "last value is kept");
+    assert_eq!(bus.quality("level")?, Quality::CommFault);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_point_that_cannot_be_latched_is_faulted_alone() -> Result<()> {
+    let (device, address) = start_device().await?;
+    device.set("remote_level", SignalValue::Float(42.5))?;
+    device.set("remote_code", SignalValue::UInt(7))?;
+    
+    // A register read into a string signal cannot be latched
+    let yaml = plc_config(&address).replace("type: \"float32\"\n", "type: \"float32\"\n          - address: 2\n            signal: \"label\"\n")
+        .replace("\nsignals:\n", "\nsignals:\n  - name: \"label\"\n    type: \"string\"\n");
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(&yaml)?)?;
+    let bus = engine.signal_bus().clone();
+    
+    engine.start_io();
+    scan_until(&mut engine, |bus| !bus.get_bool("level_comm_fault").unwrap()).await?;
+    assert_eq!(bus.get("level")?, SignalValue::Float(42.5));
+    assert!(bus.get_bool("label_comm_fault")?);
+    assert_eq!(bus.quality("label")?, Quality::CommFault);
+    
+    // The rest of the scan still ran
+    assert!(bus.get_bool("level_ok")?);
+    
+    Ok(())
+}
+
+#[test]
+fn test_device_config_errors_are_diagnostics() -> Result<()> {
+    let yaml = plc_config("127.0.0.1:502")
+        .replace("          - address: 0\n            signal: \"pump_cmd\"",
+            "          - address: 0\n            signal: \"pump_cmd\"\n          - address: 2\n            signal: \"level_ok\"")
+        .replace("type: \"float32\"", "type: \"float32\"\n          - address: 2\n            signal: \"missing\"");
+    let config = PlcConfig::from_yaml(&yaml)?;
+    
+    let messages: Vec<String> = config.diagnostics().into_iter().map(|d| d.message).collect();
+    assert!(messages.iter().any(|m| m == "modbus device 'tank': holding_registers 2: signal 'missing' is not declared"),
+        "{:#?}", messages);
+    assert!(messages.iter().any(|m| m.contains("address 1 is not mapped; write groups must be contiguous")),
+        "{:#?}", messages);
+    
+    // Fault flags count as declared bool signals
+    assert!(!messages.iter().any(|m| m.contains("level_comm_fault")), "{:#?}", messages);
+    
+    Ok(())
+}
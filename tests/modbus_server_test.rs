@@ -0,0 +1,161 @@
+use soft_plc::{
+    signal::{SignalBus, SignalValue},
+    engine::{PlcConfig, ScanEngine},
+    modbus::{ModbusServer, RegisterType, WordOrder},
+    Result,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Minimal Modbus TCP client: send one PDU and return the response PDU
+async fn request(stream: &mut TcpStream, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x12, 0x34, 0, 0];
+    frame.extend((pdu.len() as u16 + 1).to_be_bytes());
+    frame.push(1);
+    frame.extend(pdu);
+    stream.write_all(&frame).await.unwrap();
+    
+    let mut header = [0u8; 7];
+    stream.read_exact(&mut header).await.unwrap();
+    assert_eq!(&header[..2], &[0x12, 0x34], "transaction id echoed");
+    
+    let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+    let mut response = vec![0u8; length - 1];
+    stream.read_exact(&mut response).await.unwrap();
+    response
+}
+
+fn registers(response: &[u8]) -> Vec<u16> {
+    response[2..].chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect()
+}
+
+/// Start the server from `config/modbus_server.yaml` on a loopback port
+async fn start() -> Result<(ScanEngine, SignalBus, TcpStream)> {
+    start_with(include_str!("../config/modbus_server.yaml")).await
+}
+
+async fn start_with(yaml: &str) -> Result<(ScanEngine, SignalBus, TcpStream)> {
+    let config = PlcConfig::from_yaml(yaml)?;
+    let modbus = config.modbus_server.clone().unwrap();
+    let engine = ScanEngine::new(config)?;
+    let bus = engine.signal_bus().clone();
+    
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(ModbusServer::new(&modbus, bus.clone())?.serve(listener));
+    
+    Ok((engine, bus, TcpStream::connect(address).await?))
+}
+
+#[tokio::test]
+async fn test_read_registers_and_bits() -> Result<()> {
+    let (mut engine, bus, mut client) = start().await?;
+    bus.set("tank_level", SignalValue::Float(12.5))?;
+    bus.set("fill_enable", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    
+    // fill_setpoint 80.0 as float32, high word first
+    let response = request(&mut client, &[0x03, 0, 0, 0, 2]).await;
+    assert_eq!(registers(&response), vec![0x42A0, 0x0000]);
+    
+    // tank_level 12.5 as float32 low word first, then fill_count as int32
+    let response = request(&mut client, &[0x04, 0, 0, 0, 4]).await;
+    assert_eq!(registers(&response), vec![0x0000, 0x4148, 0x0000, 0x0001]);
+    assert_eq!(RegisterType::Int32.encode(&SignalValue::Int(-2), WordOrder::Big)?, vec![0xFFFF, 0xFFFE]);
+    
+    // level_low and fill_valve are on
+    let response = request(&mut client, &[0x02, 0, 0, 0, 2]).await;
+    assert_eq!(response, vec![0x02, 1, 0b11]);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_negative_int32_is_read_over_the_wire() -> Result<()> {
+    let yaml = include_str!("../config/modbus_server.yaml")
+        .replace("  - name: \"never\"", "  - name: \"level_trim\"\n    type: \"int\"\n    initial: -2\n  - name: \"never\"")
+        .replace("      signal: \"fill_count\"\n      type: \"int32\"",
+            "      signal: \"fill_count\"\n      type: \"int32\"\n    - address: 4\n      signal: \"level_trim\"\n      type: \"int32\"");
+    let (_engine, _bus, mut client) = start_with(&yaml).await?;
+    
+    let response = request(&mut client, &[0x04, 0, 4, 0, 2]).await;
+    assert_eq!(registers(&response), vec![0xFFFF, 0xFFFE]);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_writes_reach_the_engine() -> Result<()> {
+    let (mut engine, bus, mut client) = start().await?;
+    
+    let response = request(&mut client, &[0x05, 0, 0, 0xFF, 0x00]).await;
+    assert_eq!(response, vec![0x05, 0, 0, 0xFF, 0x00]);
+    assert!(!bus.get_bool("fill_enable")?, "queued until the next scan");
+    
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("fill_enable")?);
+    let response = request(&mut client, &[0x02, 0, 1, 0, 1]).await;
+    assert_eq!(response, vec![0x02, 1, 1], "fill_valve opened");
+    
+    // Setpoint 25.0 as float32 keeps the signal a float
+    bus.set("tank_level", SignalValue::Float(30.0))?;
+    let response = request(&mut client, &[0x10, 0, 0, 0, 2, 4, 0x41, 0xC8, 0x00, 0x00]).await;
+    assert_eq!(response, vec![0x10, 0, 0, 0, 2]);
+    
+    engine.execute_blocks()?;
+    assert_eq!(bus.get("fill_setpoint")?, SignalValue::Float(25.0));
+    assert!(!bus.get_bool("fill_valve")?);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_single_register_writes_to_both_words_merge() -> Result<()> {
+    let (mut engine, bus, mut client) = start().await?;
+    
+    // 25.0 as float32 written one word at a time before the next scan
+    assert_eq!(request(&mut client, &[0x06, 0, 0, 0x41, 0xC8]).await, vec![0x06, 0, 0, 0x41, 0xC8]);
+    assert_eq!(request(&mut client, &[0x06, 0, 1, 0x00, 0x00]).await, vec![0x06, 0, 1, 0x00, 0x00]);
+    assert_eq!(bus.get("fill_setpoint")?, SignalValue::Float(80.0), "queued until the next scan");
+    
+    engine.execute_blocks()?;
+    assert_eq!(bus.get("fill_setpoint")?, SignalValue::Float(25.0));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_exceptions() -> Result<()> {
+    let (_engine, bus, mut client) = start().await?;
+    
+    // Unmapped address
+    assert_eq!(request(&mut client, &[0x03, 0, 10, 0, 1]).await, vec![0x83, 0x02]);
+    
+    // Reads that stray past the end of a mapping
+    assert_eq!(request(&mut client, &[0x04, 0, 2, 0, 3]).await, vec![0x84, 0x02]);
+    
+    // Input registers are read-only
+    assert_eq!(request(&mut client, &[0x06, 0, 2, 0, 1]).await, vec![0x86, 0x02]);
+    assert_eq!(bus.get("fill_count")?, SignalValue::Int(0));
+    
+    // Quantity out of range and unsupported function
+    assert_eq!(request(&mut client, &[0x01, 0, 0, 0, 0]).await, vec![0x81, 0x03]);
+    assert_eq!(request(&mut client, &[0x2B, 0x0E, 1, 0]).await, vec![0xAB, 0x01]);
+    
+    Ok(())
+}
+
+#[test]
+fn test_overlapping_registers_are_rejected() -> Result<()> {
+    let yaml = include_str!("../config/modbus_server.yaml").replace(
+        "      signal: \"fill_count\"\n      type: \"int32\"",
+        "      signal: \"fill_count\"\n      type: \"int32\"\n    - address: 3\n      signal: \"tank_level\"",
+    );
+    let config = PlcConfig::from_yaml(&yaml)?;
+    
+    let diagnostics = config.diagnostics();
+    assert!(diagnostics.iter().any(|d| d.message.contains("input register 3 is used by both 'fill_count' and 'tank_level'")),
+        "{:#?}", diagnostics);
+    
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine, SimulatedClock},
+    Result,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+const PID_CONFIG: &str = r#"
+signals:
+  - name: "setpoint"
+    type: "float"
+    initial: 50.0
+  - name: "pressure"
+    type: "float"
+    initial: 0.0
+  - name: "auto"
+    type: "bool"
+    initial: true
+  - name: "manual_output"
+    type: "float"
+    initial: 20.0
+
+blocks:
+  - name: "pressure_loop"
+    type: "PID"
+    inputs:
+      sp: "setpoint"
+      pv: "pressure"
+      auto: "auto"
+      man: "manual_output"
+    outputs:
+      out: "pump_speed"
+    params:
+      kp: 0.8
+      ki: 0.5
+      kd: 0.05
+      out_min: 0.0
+      out_max: 100.0
+
+scan_time_ms: 100
+"#;
+
+/// First-order plant: pressure settles toward the pump speed with a 2 s time constant
+fn step_plant(engine: &mut ScanEngine, clock: &SimulatedClock, scans: usize) -> Result<f64> {
+    let dt = 0.1;
+    for _ in 0..scans {
+        engine.execute_blocks()?;
+        let speed = engine.signal_bus().get_float("pump_speed")?;
+        let pressure = engine.signal_bus().get_float("pressure")?;
+        let next = pressure + (speed - pressure) * dt / 2.0;
+        engine.signal_bus().set("pressure", SignalValue::Float(next))?;
+        clock.advance(Duration::from_millis(100));
+    }
+    engine.signal_bus().get_float("pressure")
+}
+
+fn pid_engine() -> Result<(ScanEngine, SimulatedClock)> {
+    let clock = SimulatedClock::new();
+    let engine = ScanEngine::with_clock(PlcConfig::from_yaml(PID_CONFIG)?, Arc::new(clock.clone()))?;
+    Ok((engine, clock))
+}
+
+#[test]
+fn test_pid_settles_on_setpoint() -> Result<()> {
+    let (mut engine, clock) = pid_engine()?;
+    
+    let pressure = step_plant(&mut engine, &clock, 600)?;
+    assert!((pressure - 50.0).abs() < 0.5, "pressure {} did not settle", pressure);
+    
+    Ok(())
+}
+
+#[test]
+fn test_pid_output_is_clamped_without_windup() -> Result<()> {
+    let (mut engine, clock) = pid_engine()?;
+    
+    // Unreachable setpoint saturates the output for a long time
+    engine.signal_bus().set("setpoint", SignalValue::Float(500.0))?;
+    step_plant(&mut engine, &clock, 600)?;
+    assert_eq!(engine.signal_bus().get_float("pump_speed")?, 100.0);
+    
+    // A wound-up integrator would keep the output pinned long after the setpoint drops
+    engine.signal_bus().set("setpoint", SignalValue::Float(50.0))?;
+    step_plant(&mut engine, &clock, 20)?;
+    assert!(engine.signal_bus().get_float("pump_speed")? < 100.0);
+    
+    Ok(())
+}
+
+#[test]
+fn test_p_only_output_recovers_from_saturation() -> Result<()> {
+    let yaml = PID_CONFIG.replace("kp: 0.8", "kp: 10.0").replace("ki: 0.5", "ki: 0.0").replace("kd: 0.05", "kd: 0.0");
+    let clock = SimulatedClock::new();
+    let mut engine = ScanEngine::with_clock(PlcConfig::from_yaml(&yaml)?, Arc::new(clock.clone()))?;
+    let bus = engine.signal_bus().clone();
+    
+    // kp * error = 200 saturates the output
+    bus.set("setpoint", SignalValue::Float(20.0))?;
+    for _ in 0..10 {
+        engine.execute_blocks()?;
+        clock.advance(Duration::from_millis(100));
+    }
+    assert_eq!(bus.get_float("pump_speed")?, 100.0);
+    
+    // Without integral action the output is kp * error again, with no offset left behind
+    bus.set("pressure", SignalValue::Float(15.0))?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_float("pump_speed")?, 50.0);
+    
+    Ok(())
+}
+
+#[test]
+fn test_pid_manual_to_auto_is_bumpless() -> Result<()> {
+    let (mut engine, clock) = pid_engine()?;
+    
+    engine.signal_bus().set("auto", SignalValue::Bool(false))?;
+    step_plant(&mut engine, &clock, 50)?;
+    assert_eq!(engine.signal_bus().get_float("pump_speed")?, 20.0);
+    
+    engine.signal_bus().set("auto", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    let first_auto_output = engine.signal_bus().get_float("pump_speed")?;
+    
+    // Only one scan of integral action may separate the two; a bump would be kp * error (~25)
+    assert!((first_auto_output - 20.0).abs() < 2.5, "output jumped to {}", first_auto_output);
+    
+    Ok(())
+}
@@ -13,7 +13,7 @@ async fn test_pump_alternation() -> Result<()> {
     
     // Initial state - pressure is OK (55.0)
     engine.execute_blocks()?;
-    assert_eq!(engine.signal_bus().get_bool("pump1_run")?, false);
+    assert!(!engine.signal_bus().get_bool("pumps[1].run")?);
     assert_eq!(engine.signal_bus().get_int("pump_index")?, 0);
     
     println!("Initial state - all pumps off, index=0");
@@ -24,8 +24,8 @@ async fn test_pump_alternation() -> Result<()> {
     engine.execute_blocks()?; // Need two scans for edge detection
     
     // Pump 1 should start (index 0)
-    assert_eq!(engine.signal_bus().get_bool("pump1_run")?, true);
-    assert_eq!(engine.signal_bus().get_bool("pump2_run")?, false);
+    assert!(engine.signal_bus().get_bool("pumps[1].run")?);
+    assert!(!engine.signal_bus().get_bool("pumps[2].run")?);
     assert_eq!(engine.signal_bus().get_int("pump_index")?, 0);
     println!("Low pressure detected - Pump 1 started");
     
@@ -35,7 +35,7 @@ async fn test_pump_alternation() -> Result<()> {
     engine.execute_blocks()?;
     
     // All pumps should stop
-    assert_eq!(engine.signal_bus().get_bool("pump1_run")?, false);
+    assert!(!engine.signal_bus().get_bool("pumps[1].run")?);
     println!("Pressure recovered - Pump 1 stopped");
     
     // Second pressure drop - should start pump 2
@@ -43,8 +43,8 @@ async fn test_pump_alternation() -> Result<()> {
     engine.execute_blocks()?;
     engine.execute_blocks()?;
     
-    assert_eq!(engine.signal_bus().get_bool("pump1_run")?, false);
-    assert_eq!(engine.signal_bus().get_bool("pump2_run")?, true);
+    assert!(!engine.signal_bus().get_bool("pumps[1].run")?);
+    assert!(engine.signal_bus().get_bool("pumps[2].run")?);
     assert_eq!(engine.signal_bus().get_int("pump_index")?, 1);
     println!("Second low pressure - Pump 2 started");
     
@@ -72,7 +72,7 @@ async fn test_pump_alternation() -> Result<()> {
     engine.execute_blocks()?;
     engine.execute_blocks()?;
     
-    assert_eq!(engine.signal_bus().get_bool("pump1_run")?, true);
+    assert!(engine.signal_bus().get_bool("pumps[1].run")?);
     assert_eq!(engine.signal_bus().get_int("pump_index")?, 0);
     println!("Wrapped back to Pump 1");
     
@@ -91,7 +91,7 @@ async fn test_pump_alternation() -> Result<()> {
     engine.execute_blocks()?;
     
     // No pumps should run in manual mode
-    assert_eq!(engine.signal_bus().get_bool("pump1_run")?, false);
+    assert!(!engine.signal_bus().get_bool("pumps[1].run")?);
     println!("Manual override active - no auto pump control");
     
     Ok(())
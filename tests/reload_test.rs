@@ -0,0 +1,163 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine, SimulatedClock},
+    PlcError, Result,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+const RUNNING_CONFIG: &str = r#"
+signals:
+  - name: "pulse"
+    type: "bool"
+  - name: "no"
+    type: "bool"
+  - name: "count"
+    type: "int"
+  - name: "delayed"
+    type: "bool"
+  - name: "inverted"
+    type: "bool"
+  - name: "mode"
+    type: "int"
+    initial: 1
+
+blocks:
+  - name: "counter"
+    type: "COUNTER"
+    inputs:
+      cu: "pulse"
+      cd: "no"
+      r: "no"
+    outputs:
+      cv: "count"
+
+  - name: "delay"
+    type: "TON"
+    inputs:
+      in: "pulse"
+    outputs:
+      q: "delayed"
+    params:
+      preset_ms: 1000
+
+  - name: "invert"
+    type: "NOT"
+    inputs:
+      in: "pulse"
+    outputs:
+      out: "inverted"
+
+scan_time_ms: 100
+"#;
+
+/// The delay is shortened, the inverter and its output are removed, a latch
+/// is added and `mode` changes type
+fn changed_config() -> Result<PlcConfig> {
+    let yaml = RUNNING_CONFIG
+        .replace("preset_ms: 1000", "preset_ms: 500")
+        .replace("  - name: \"inverted\"\n    type: \"bool\"\n", "")
+        .replace("  - name: \"mode\"\n    type: \"int\"\n    initial: 1", "  - name: \"mode\"\n    type: \"bool\"\n    initial: true\n  - name: \"latched\"\n    type: \"bool\"\n    initial: true")
+        .replace("  - name: \"invert\"\n    type: \"NOT\"\n    inputs:\n      in: \"pulse\"\n    outputs:\n      out: \"inverted\"\n",
+            "  - name: \"latch\"\n    type: \"SR_LATCH\"\n    inputs:\n      set: \"pulse\"\n      reset: \"no\"\n    outputs:\n      q: \"latched\"\n");
+    PlcConfig::from_yaml(&yaml)
+}
+
+fn start() -> Result<(ScanEngine, SimulatedClock)> {
+    let clock = SimulatedClock::new();
+    let engine = ScanEngine::with_clock(PlcConfig::from_yaml(RUNNING_CONFIG)?, Arc::new(clock.clone()))?;
+    Ok((engine, clock))
+}
+
+#[test]
+fn test_change_carries_state_over() -> Result<()> {
+    let (mut engine, clock) = start()?;
+    let bus = engine.signal_bus().clone();
+    
+    for pulse in [true, false, true] {
+        bus.set("pulse", SignalValue::Bool(pulse))?;
+        engine.execute_blocks()?;
+    }
+    clock.advance(Duration::from_millis(400));
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_int("count")?, 2);
+    assert!(!bus.get_bool("inverted")?);
+    
+    let report = engine.apply_change(changed_config()?)?;
+    assert_eq!(report.added_signals, vec!["latched"]);
+    assert_eq!(report.removed_signals, vec!["inverted"]);
+    assert_eq!(report.retyped_signals, vec!["mode"]);
+    assert_eq!(report.added_blocks, vec!["latch"]);
+    assert_eq!(report.removed_blocks, vec!["invert"]);
+    assert_eq!(report.modified_blocks, vec!["delay"]);
+    assert!(report.replaced_blocks.is_empty());
+    assert!(!report.scheduling_changed);
+    
+    // Values are there before the first scan of the new logic
+    assert_eq!(bus.get("mode")?, SignalValue::Bool(true));
+    assert!(bus.get_bool("latched")?);
+    
+    clock.advance(Duration::from_millis(100));
+    engine.execute_blocks()?;
+    assert_eq!(engine.execution_order(), vec!["counter", "delay", "latch"]);
+    assert_eq!(bus.get_int("count")?, 2, "counter state carried over");
+    assert!(bus.get_bool("delayed")?, "timer kept its 500 ms elapsed time");
+    assert!(!bus.get_bool("inverted")?, "output of a removed block holds");
+    
+    bus.set("pulse", SignalValue::Bool(false))?;
+    engine.execute_blocks()?;
+    bus.set("pulse", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_int("count")?, 3);
+    
+    Ok(())
+}
+
+#[test]
+fn test_rejected_change_leaves_engine_running() -> Result<()> {
+    let (mut engine, _clock) = start()?;
+    engine.execute_blocks()?;
+    
+    let invalid = PlcConfig::from_yaml(&RUNNING_CONFIG.replace("cv: \"count\"", "cv: \"delayed\""))?;
+    assert!(matches!(engine.plan_change(&invalid), Err(PlcError::ValidationError(_))));
+    let error = engine.apply_change(invalid).unwrap_err();
+    assert!(matches!(error, PlcError::ValidationError(_)));
+    assert!(error.to_string().contains("signal 'delayed' is already written by block 'counter'"), "{}", error);
+    
+    let restart = PlcConfig::from_yaml(&format!("{}\nmodbus_server:\n  port: 5502\n", RUNNING_CONFIG))?;
+    assert!(engine.apply_change(restart).unwrap_err().to_string().contains("modbus_server"));
+    
+    // A dry run changes nothing
+    let report = engine.plan_change(&changed_config()?)?;
+    assert_eq!(report.added_blocks, vec!["latch"]);
+    assert_eq!(engine.execution_order(), vec!["counter", "delay", "invert"]);
+    assert_eq!(engine.signal_bus().get_int("mode")?, 1);
+    
+    assert!(engine.plan_change(&PlcConfig::from_yaml(RUNNING_CONFIG)?)?.is_empty());
+    
+    // Leaving scan_time_ms out means the default interval, not an invalid zero
+    let implicit = PlcConfig::from_yaml(&RUNNING_CONFIG.replace("scan_time_ms: 100\n", ""))?;
+    assert!(engine.plan_change(&implicit)?.is_empty());
+    
+    engine.execute_blocks()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reload_handle_applies_between_scans() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(RUNNING_CONFIG)?)?;
+    let reload = engine.reload_handle();
+    let stop = engine.stop_handle();
+    let running = tokio::spawn(async move { engine.run().await.map(|_| engine) });
+    
+    let report = reload.dry_run(changed_config()?).await?;
+    assert_eq!(report.added_blocks, vec!["latch"]);
+    let applied = reload.apply(changed_config()?).await?;
+    assert_eq!(applied, report);
+    
+    stop.stop().await;
+    let engine = running.await.expect("engine task")?;
+    assert_eq!(engine.execution_order(), vec!["counter", "delay", "latch"]);
+    
+    Ok(())
+}
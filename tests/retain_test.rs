@@ -0,0 +1,186 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, RetainStore, ScanEngine, SimulatedClock},
+    Result,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const RETAIN_CONFIG: &str = r#"
+signals:
+  - name: "part_sensor"
+    type: "bool"
+  - name: "no"
+    type: "bool"
+  - name: "part_count"
+    type: "int"
+  - name: "batch_done"
+    type: "bool"
+  - name: "batch_total"
+    type: "float"
+    initial: 1.5
+    retain: true
+  - name: "setpoint"
+    type: "float"
+    initial: 1.5
+
+blocks:
+  - name: "parts"
+    type: "COUNTER"
+    inputs:
+      cu: "part_sensor"
+      cd: "no"
+      r: "no"
+    outputs:
+      cv: "part_count"
+      q: "batch_done"
+    params:
+      preset: 5
+    retain: true
+
+scan_time_ms: 10
+retain:
+  path: "RETAIN_PATH"
+  interval_ms: 1000
+"#;
+
+fn retain_path(test: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("soft_plc_{}_{}.retain", test, std::process::id()));
+    std::fs::remove_file(&path).ok();
+    path
+}
+
+fn config(path: &Path) -> Result<PlcConfig> {
+    PlcConfig::from_yaml(&RETAIN_CONFIG.replace("RETAIN_PATH", &path.display().to_string()))
+}
+
+fn start(path: &Path) -> Result<ScanEngine> {
+    ScanEngine::with_clock(config(path)?, Arc::new(SimulatedClock::new()))
+}
+
+/// Count `parts` rising edges of the part sensor
+fn count_parts(engine: &mut ScanEngine, parts: usize) -> Result<()> {
+    let bus = engine.signal_bus().clone();
+    for _ in 0..parts {
+        bus.set("part_sensor", SignalValue::Bool(true))?;
+        engine.execute_blocks()?;
+        bus.set("part_sensor", SignalValue::Bool(false))?;
+        engine.execute_blocks()?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_retained_values_survive_restart() -> Result<()> {
+    let path = retain_path("restart");
+    
+    let mut engine = start(&path)?;
+    count_parts(&mut engine, 3)?;
+    engine.signal_bus().set("batch_total", SignalValue::Float(42.5))?;
+    engine.signal_bus().set("setpoint", SignalValue::Float(9.0))?;
+    engine.execute_blocks()?;
+    engine.save_retained()?;
+    drop(engine);
+    
+    let mut engine = start(&path)?;
+    let bus = engine.signal_bus().clone();
+    assert_eq!(bus.get("batch_total")?, SignalValue::Float(42.5));
+    assert_eq!(bus.get("setpoint")?, SignalValue::Float(1.5), "not retentive");
+    
+    // The counter carries on from where it stopped
+    count_parts(&mut engine, 1)?;
+    assert_eq!(bus.get_int("part_count")?, 4);
+    
+    std::fs::remove_file(&path).ok();
+    Ok(())
+}
+
+#[test]
+fn test_counter_preset_comes_from_the_configuration() -> Result<()> {
+    let path = retain_path("preset");
+    let with_preset = |preset: &str| -> Result<PlcConfig> {
+        let mut config = config(&path)?;
+        let parts = config.blocks.iter_mut().find(|block| block.name == "parts").unwrap();
+        parts.params.insert("preset".to_string(), serde_yaml::from_str(preset).unwrap());
+        Ok(config)
+    };
+    
+    // Lowered online, the new preset counts and the count carries over
+    let mut engine = start(&path)?;
+    let bus = engine.signal_bus().clone();
+    count_parts(&mut engine, 1)?;
+    engine.apply_change(with_preset("2")?)?;
+    count_parts(&mut engine, 1)?;
+    assert_eq!(bus.get_int("part_count")?, 2);
+    assert!(bus.get_bool("batch_done")?);
+    engine.save_retained()?;
+    drop(engine);
+    
+    // A retained count is checked against the preset of the configuration it restarts with
+    let mut engine = ScanEngine::with_clock(with_preset("3")?, Arc::new(SimulatedClock::new()))?;
+    engine.execute_blocks()?;
+    assert_eq!(engine.signal_bus().get_int("part_count")?, 2);
+    assert!(!engine.signal_bus().get_bool("batch_done")?);
+    
+    std::fs::remove_file(&path).ok();
+    Ok(())
+}
+
+#[test]
+fn test_saved_every_interval() -> Result<()> {
+    let path = retain_path("interval");
+    let mut engine = start(&path)?;
+    
+    engine.run_simulated(Duration::from_millis(990))?;
+    assert!(!path.exists());
+    
+    engine.signal_bus().set("batch_total", SignalValue::Float(7.0))?;
+    engine.run_simulated(Duration::from_millis(20))?;
+    let saved = RetainStore::new(&path).load()?.expect("saved after the interval");
+    assert_eq!(saved.signals.get("batch_total"), Some(&SignalValue::Float(7.0)));
+    assert!(saved.blocks.contains_key("parts"));
+    
+    std::fs::remove_file(&path).ok();
+    Ok(())
+}
+
+#[test]
+fn test_corrupted_file_starts_cold() -> Result<()> {
+    let path = retain_path("corrupted");
+    
+    let mut engine = start(&path)?;
+    engine.signal_bus().set("batch_total", SignalValue::Float(42.5))?;
+    engine.execute_blocks()?;
+    engine.save_retained()?;
+    
+    let contents = std::fs::read_to_string(&path)?;
+    std::fs::write(&path, contents.replace("42.5", "43.5"))?;
+    assert!(RetainStore::new(&path).load().is_err());
+    
+    let engine = start(&path)?;
+    assert_eq!(engine.signal_bus().get("batch_total")?, SignalValue::Float(1.5));
+    
+    std::fs::remove_file(&path).ok();
+    Ok(())
+}
+
+#[test]
+fn test_retain_configuration_errors() -> Result<()> {
+    let cases = [
+        ("retain:\n  path: \"RETAIN_PATH\"\n  interval_ms: 1000\n", "",
+            "signal 'batch_total' is retentive but no retain file is configured"),
+        ("interval_ms: 1000", "interval_ms: 0",
+            "retain interval_ms must be greater than zero"),
+        ("\nscan_time_ms", "  - name: \"invert\"\n    type: \"NOT\"\n    inputs:\n      in: \"no\"\n    outputs:\n      out: \"part_sensor\"\n    retain: true\n\nscan_time_ms",
+            "NOT has no internal state to retain"),
+    ];
+    
+    for (from, to, expected) in cases {
+        let config = PlcConfig::from_yaml(&RETAIN_CONFIG.replace(from, to))?;
+        let messages: Vec<String> = config.diagnostics().into_iter().map(|d| d.message).collect();
+        assert!(messages.iter().any(|m| m.contains(expected)), "{}: {:#?}", expected, messages);
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::{block, run};
+use soft_plc::{
+    signal::{SignalBus, SignalType, SignalValue},
+    blocks::create_block,
+    engine::{PlcConfig, SimulatedClock},
+    PlcError, Result,
+};
+
+#[test]
+fn test_scale_raw_to_engineering_units() -> Result<()> {
+    let params = |raw_min: f64, raw_max: f64, clamp: bool| vec![
+        ("raw_min", serde_yaml::Value::from(raw_min)),
+        ("raw_max", serde_yaml::Value::from(raw_max)),
+        ("eu_min", serde_yaml::Value::from(0.0)),
+        ("eu_max", serde_yaml::Value::from(10.0)),
+        ("clamp", serde_yaml::Value::from(clamp)),
+    ];
+    let bus = SignalBus::new();
+    bus.declare("raw", SignalType::Int);
+    bus.declare("bar", SignalType::Real);
+    bus.declare("fault", SignalType::Bool);
+    let config = block("SCALE", &[("in", "raw")], &[("out", "bar"), ("out_of_range", "fault")], &params(0.0, 27648.0, true));
+    let mut scale = create_block(&config, &bus)?;
+    let clock = SimulatedClock::new();
+    
+    bus.set("raw", SignalValue::Int(13824))?;
+    scale.execute(&bus, &clock)?;
+    assert_eq!(bus.get("bar")?, SignalValue::Real(5.0));
+    assert!(!bus.get_bool("fault")?);
+    
+    // Overrange counts clamp to the EU range and raise the flag
+    bus.set("raw", SignalValue::Int(32767))?;
+    scale.execute(&bus, &clock)?;
+    assert_eq!(bus.get("bar")?, SignalValue::Real(10.0));
+    assert!(bus.get_bool("fault")?);
+    
+    // A broken 4-20 mA loop reads below 4 mA
+    let milliamps = |value: f64, clamp: bool| {
+        let config = block("SCALE", &[("in", "ma")], &[("out", "out")], &params(4.0, 20.0, clamp));
+        run(&config, &[("ma", SignalValue::Float(value))], SignalType::Float)
+    };
+    assert_eq!(milliamps(12.0, true)?, SignalValue::Float(5.0));
+    assert_eq!(milliamps(0.0, true)?, SignalValue::Float(0.0));
+    assert_eq!(milliamps(0.0, false)?, SignalValue::Float(-2.5));
+    
+    let mut missing = params(4.0, 20.0, true);
+    missing.retain(|(key, _)| *key != "eu_max");
+    assert!(matches!(create_block(&block("SCALE", &[("in", "ma")], &[("out", "out")], &missing), &bus), Err(PlcError::ConfigError(_))));
+    let flat = params(20.0, 20.0, true);
+    assert!(create_block(&block("SCALE", &[("in", "ma")], &[("out", "out")], &flat), &bus).is_err());
+    
+    Ok(())
+}
+
+#[test]
+fn test_limit_min_max() -> Result<()> {
+    let limit = |value: i32| {
+        let config = block("LIMIT", &[("mn", "lo"), ("in", "x"), ("mx", "hi")], &[("out", "out")], &[]);
+        run(&config, &[("lo", SignalValue::Int(0)), ("x", SignalValue::Int(value)), ("hi", SignalValue::Int(100))], SignalType::Int)
+    };
+    assert_eq!(limit(-5)?, SignalValue::Int(0));
+    assert_eq!(limit(42)?, SignalValue::Int(42));
+    assert_eq!(limit(150)?, SignalValue::Int(100));
+    
+    let config = block("LIMIT", &[("mn", "lo"), ("in", "x"), ("mx", "hi")], &[("out", "out")], &[]);
+    let values = [("lo", SignalValue::Real(5.0)), ("x", SignalValue::Real(1.0)), ("hi", SignalValue::Real(2.0))];
+    assert!(run(&config, &values, SignalType::Real).unwrap_err().to_string().contains("mn is greater than mx"));
+    
+    let inputs = [("in1", "a"), ("in2", "b"), ("in3", "c")];
+    let values = [("a", SignalValue::UInt(7)), ("b", SignalValue::UInt(3)), ("c", SignalValue::UInt(9))];
+    assert_eq!(run(&block("MIN", &inputs, &[("out", "out")], &[]), &values, SignalType::UInt)?, SignalValue::UInt(3));
+    assert_eq!(run(&block("MAX", &inputs, &[("out", "out")], &[]), &values, SignalType::UInt)?, SignalValue::UInt(9));
+    
+    // A NaN input never wins
+    let values = [("a", SignalValue::Float(f64::NAN)), ("b", SignalValue::Float(1.5)), ("c", SignalValue::Float(-2.0))];
+    assert_eq!(run(&block("MAX", &inputs, &[("out", "out")], &[]), &values, SignalType::Float)?, SignalValue::Float(1.5));
+    
+    Ok(())
+}
+
+#[test]
+fn test_sel_and_mux() -> Result<()> {
+    let sel = |g: bool| {
+        let config = block("SEL", &[("g", "g"), ("in0", "a"), ("in1", "b")], &[("out", "out")], &[]);
+        run(&config, &[("g", SignalValue::Bool(g)), ("a", SignalValue::Int(10)), ("b", SignalValue::Int(20))], SignalType::Int)
+    };
+    assert_eq!(sel(false)?, SignalValue::Int(10));
+    assert_eq!(sel(true)?, SignalValue::Int(20));
+    
+    let mux = |k: i32| {
+        let config = block("MUX", &[("k", "k"), ("in0", "a"), ("in1", "b"), ("in2", "c")], &[("out", "out")], &[]);
+        let values = [("k", SignalValue::Int(k)), ("a", SignalValue::Word(1)), ("b", SignalValue::Word(2)), ("c", SignalValue::Word(4))];
+        run(&config, &values, SignalType::Word)
+    };
+    assert_eq!(mux(0)?, SignalValue::Word(1));
+    assert_eq!(mux(2)?, SignalValue::Word(4));
+    assert!(mux(3).unwrap_err().to_string().contains("index 3 is outside in0 to in2"));
+    assert!(mux(-1).is_err());
+    
+    let gap = block("MUX", &[("k", "k"), ("in0", "a"), ("in2", "c")], &[("out", "out")], &[]);
+    assert!(matches!(create_block(&gap, &SignalBus::new()), Err(PlcError::ConfigError(_))));
+    
+    Ok(())
+}
+
+#[test]
+fn test_selection_ports_are_validated() -> Result<()> {
+    let config = PlcConfig::from_yaml(r#"
+strict_types: true
+signals:
+  - name: "index"
+    type: "usint"
+  - name: "low"
+    type: "real"
+  - name: "high"
+    type: "lreal"
+  - name: "chosen"
+    type: "real"
+blocks:
+  - name: "choose"
+    type: "MUX"
+    inputs:
+      k: "index"
+      in0: "low"
+      in1: "high"
+    outputs:
+      out: "chosen"
+scan_time_ms: 100
+"#)?;
+    let messages = match config.validate() {
+        Err(PlcError::ValidationError(diagnostics)) => diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+        other => panic!("expected validation failure, got {:?}", other),
+    };
+    // Any integer selects, but the choices must share one type
+    assert_eq!(messages.len(), 1, "{:?}", messages);
+    assert!(messages[0].contains("'high' is float but 'low' is real"), "{:?}", messages);
+    
+    Ok(())
+}
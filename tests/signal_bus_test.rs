@@ -0,0 +1,317 @@
+use soft_plc::{
+    signal::{matches_pattern, Quality, SignalBus, SignalValue},
+    engine::{PlcConfig, ScanEngine},
+    PlcError, Result,
+};
+
+#[test]
+fn test_handles_and_names_share_slots() -> Result<()> {
+    let bus = SignalBus::new();
+    bus.set("level", SignalValue::Float(1.0))?;
+    
+    let level = bus.register("level");
+    assert_eq!(bus.register("level"), level);
+    assert_eq!(bus.id("level"), Some(level));
+    assert_eq!(bus.name(level), "level");
+    assert_eq!(bus.read_float(level)?, 1.0);
+    
+    bus.write(level, SignalValue::Float(2.5))?;
+    assert_eq!(bus.get_float("level")?, 2.5);
+    
+    // Clones see the same slots
+    let clone = bus.clone();
+    clone.set("level", SignalValue::Float(4.0))?;
+    assert_eq!(bus.read_float(level)?, 4.0);
+    
+    Ok(())
+}
+
+#[test]
+fn test_registered_signal_without_value_is_missing() -> Result<()> {
+    let bus = SignalBus::new();
+    let pending = bus.register("pending");
+    
+    assert!(!bus.exists("pending"));
+    assert!(matches!(bus.read(pending), Err(PlcError::SignalNotFound(name)) if name == "pending"));
+    assert!(bus.iter().iter().all(|(name, _)| name != "pending"));
+    
+    bus.write(pending, SignalValue::Bool(true))?;
+    assert!(bus.exists("pending"));
+    
+    // Clearing empties the slots but keeps the handles
+    bus.clear();
+    assert!(!bus.exists("pending"));
+    bus.set("pending", SignalValue::Bool(false))?;
+    assert!(!bus.read_bool(pending)?);
+    
+    Ok(())
+}
+
+const CONFIG: &str = r#"
+signals:
+  - name: "input"
+    type: "bool"
+  - name: "output"
+    type: "bool"
+
+blocks:
+  - name: "invert"
+    type: "NOT"
+    inputs:
+      in: "input"
+    outputs:
+      out: "output"
+"#;
+
+#[test]
+fn test_external_writes_reach_blocks() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(CONFIG)?)?;
+    let bus = engine.signal_bus().clone();
+    
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("output")?);
+    
+    bus.set("input", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    assert!(!bus.get_bool("output")?);
+    
+    Ok(())
+}
+
+#[test]
+fn test_process_image_isolates_scan_from_external_access() -> Result<()> {
+    let bus = SignalBus::new();
+    bus.set("input", SignalValue::Int(1))?;
+    bus.set("output", SignalValue::Int(0))?;
+    let input = bus.register("input");
+    let output = bus.register("output");
+    
+    let image = bus.process_image();
+    assert_eq!(image.read_int(input)?, 1, "image starts from the current values");
+    
+    // External writes are queued, even while a scan is reading the image
+    bus.set("input", SignalValue::Int(2))?;
+    assert_eq!(bus.get_int("input")?, 1);
+    assert_eq!(image.read_int(input)?, 1);
+    
+    // Input phase applies them in order
+    bus.set("input", SignalValue::Int(3))?;
+    image.apply_queued_writes();
+    assert_eq!(image.read_int(input)?, 3);
+    
+    // Logic writes stay in the image until the output phase
+    image.write(output, SignalValue::Int(30))?;
+    assert_eq!(bus.get_int("output")?, 0);
+    image.publish();
+    assert_eq!(bus.get_int("output")?, 30);
+    assert_eq!(bus.get_int("input")?, 3);
+    
+    // Signals registered later get a slot in the image too
+    bus.set("late", SignalValue::Bool(true))?;
+    assert!(!bus.exists("late"));
+    image.apply_queued_writes();
+    image.publish();
+    assert!(bus.get_bool("late")?);
+    
+    Ok(())
+}
+
+#[test]
+fn test_scan_sees_external_writes_from_the_next_input_phase() -> Result<()> {
+    let yaml = r#"
+signals:
+  - name: "a"
+    type: "float"
+    initial: 1.0
+  - name: "b"
+    type: "float"
+    initial: 2.0
+  - name: "sum"
+    type: "float"
+blocks:
+  - name: "adder"
+    type: "ADD"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "sum"
+"#;
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(yaml)?)?;
+    let bus = engine.signal_bus().clone();
+    
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_float("sum")?, 3.0);
+    
+    // Both writes land together at the next scan, never one without the other
+    bus.set("a", SignalValue::Float(10.0))?;
+    bus.set("b", SignalValue::Float(20.0))?;
+    assert_eq!(bus.get_float("sum")?, 3.0);
+    assert_eq!(bus.get_float("a")?, 1.0);
+    
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_float("sum")?, 30.0);
+    
+    Ok(())
+}
+
+#[test]
+fn test_changes_are_sent_on_publish() -> Result<()> {
+    let bus = SignalBus::new();
+    bus.set("level", SignalValue::Float(1.0))?;
+    bus.set("pump", SignalValue::Bool(false))?;
+    
+    // Without a process image every write that changes a value is sent
+    let mut changes = bus.changes();
+    bus.set("level", SignalValue::Float(1.0))?;
+    bus.set("level", SignalValue::Float(2.0))?;
+    let set = changes.try_recv().unwrap();
+    assert_eq!(set.changes.len(), 1);
+    assert_eq!(set.changes[0].name, "level");
+    assert_eq!(set.changes[0].previous, Some(SignalValue::Float(1.0)));
+    assert_eq!(set.changes[0].value, SignalValue::Float(2.0));
+    assert!(changes.try_recv().is_err(), "unchanged value not sent");
+    
+    // With one, the changes of a scan are sent together at the output phase
+    let image = bus.process_image();
+    bus.set("pump", SignalValue::Bool(true))?;
+    image.apply_queued_writes();
+    image.set("level", SignalValue::Float(3.0))?;
+    image.set("alarm", SignalValue::Bool(false))?;
+    assert!(changes.try_recv().is_err());
+    
+    image.set_scan_number(7);
+    image.publish();
+    let set = changes.try_recv().unwrap();
+    assert_eq!(set.scan, 7);
+    let names: Vec<&str> = set.changes.iter().map(|change| change.name.as_str()).collect();
+    assert_eq!(names, vec!["level", "pump", "alarm"]);
+    assert_eq!(set.changes[2].previous, None);
+    
+    image.publish();
+    assert!(changes.try_recv().is_err(), "nothing changed since the last publish");
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscriptions_report_old_and_new_values() -> Result<()> {
+    let bus = SignalBus::new();
+    bus.set("tank_level", SignalValue::Float(10.0))?;
+    bus.set("tank_temp", SignalValue::Float(20.0))?;
+    bus.set("pump", SignalValue::Bool(false))?;
+    
+    let mut tanks = bus.subscribe(["tank_*"]);
+    let mut pump = bus.watch("pump");
+    let mut later = bus.watch("later");
+    assert_eq!(pump.borrow().as_ref().map(|sample| sample.value.clone()), Some(SignalValue::Bool(false)));
+    assert_eq!(*later.borrow(), None);
+    
+    let image = bus.process_image();
+    image.set("tank_level", SignalValue::Float(12.0))?;
+    image.set("pump", SignalValue::Bool(true))?;
+    image.set("later", SignalValue::Int(1))?;
+    image.publish();
+    
+    let set = tanks.recv().await.unwrap();
+    assert_eq!(set.changes.len(), 1, "only the subscribed signal that changed");
+    assert_eq!(set.changes[0].name, "tank_level");
+    assert_eq!(set.changes[0].previous, Some(SignalValue::Float(10.0)));
+    assert_eq!(set.changes[0].value, SignalValue::Float(12.0));
+    
+    pump.changed().await.unwrap();
+    assert_eq!(pump.borrow_and_update().as_ref().map(|sample| sample.value.clone()), Some(SignalValue::Bool(true)));
+    later.changed().await.unwrap();
+    assert_eq!(later.borrow_and_update().as_ref().map(|sample| sample.value.clone()), Some(SignalValue::Int(1)));
+    
+    // Publishing the same values again notifies nobody
+    image.set("pump", SignalValue::Bool(true))?;
+    image.publish();
+    assert!(tanks.try_recv().is_none());
+    assert!(!pump.has_changed().unwrap());
+    assert_eq!(tanks.missed(), 0);
+    
+    Ok(())
+}
+
+#[test]
+fn test_quality_propagates_through_blocks() -> Result<()> {
+    let yaml = r#"
+signals:
+  - name: "a"
+    type: "bool"
+    initial: true
+  - name: "b"
+    type: "bool"
+    initial: true
+  - name: "both"
+    type: "bool"
+  - name: "neither"
+    type: "bool"
+blocks:
+  - name: "and"
+    type: "AND"
+    inputs:
+      in1: "a"
+      in2: "b"
+    outputs:
+      out: "both"
+  - name: "not"
+    type: "NOT"
+    inputs:
+      in: "both"
+    outputs:
+      out: "neither"
+"#;
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(yaml)?)?;
+    let bus = engine.signal_bus().clone();
+    engine.execute_blocks()?;
+    assert_eq!(bus.quality("neither")?, Quality::Good);
+    let since = bus.get_sample("neither")?.timestamp;
+    
+    // A bad input makes every output downstream bad, the value still flows
+    bus.set_with_quality("b", SignalValue::Bool(true), Quality::CommFault)?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.quality("b")?, Quality::CommFault);
+    assert_eq!(bus.quality("both")?, Quality::Bad);
+    assert_eq!(bus.quality("neither")?, Quality::Bad);
+    assert!(!bus.get_bool("neither")?);
+    assert!(bus.get_sample("neither")?.timestamp >= since);
+    
+    // Uncertain is passed on as uncertain, forced and simulated as good
+    bus.set_with_quality("a", SignalValue::Bool(true), Quality::Uncertain)?;
+    bus.set_with_quality("b", SignalValue::Bool(true), Quality::Forced)?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.quality("both")?, Quality::Uncertain);
+    
+    bus.set("a", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.quality("both")?, Quality::Good);
+    assert_eq!(bus.quality("b")?, Quality::Forced);
+    
+    // An unchanged value and quality keeps its timestamp
+    let stamped = bus.get_sample("both")?.timestamp;
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_sample("both")?.timestamp, stamped);
+    
+    // Only the quality can be changed too
+    let a = bus.id("a").unwrap();
+    bus.write_quality(a, Quality::Bad)?;
+    engine.execute_blocks()?;
+    assert_eq!(bus.get_sample("a")?.value, SignalValue::Bool(true));
+    assert_eq!(bus.quality("both")?, Quality::Bad);
+    
+    Ok(())
+}
+
+#[test]
+fn test_patterns() {
+    assert!(matches_pattern("tank_*", "tank_level"));
+    assert!(matches_pattern("tank_*", "tank_"));
+    assert!(matches_pattern("pump?.run", "pump2.run"));
+    assert!(!matches_pattern("pump?.run", "pump12.run"));
+    assert!(matches_pattern("*.run", "line.pump1.run"));
+    assert!(matches_pattern("*a*b", "xxaxxab"));
+    assert!(!matches_pattern("level", "level2"));
+    assert!(matches_pattern("*", "anything"));
+}
@@ -0,0 +1,133 @@
+use soft_plc::{
+    signal::{SignalBus, SignalType, SignalValue},
+    engine::{PlcConfig, ScanEngine},
+    PlcError, Result,
+};
+
+const CONFIG: &str = r#"
+signals:
+  - name: "level"
+    type: "UINT"
+    initial: 65535
+  - name: "total"
+    type: "lint"
+    initial: 5000000000
+  - name: "counter"
+    type: "udint"
+    initial: 4000000000
+  - name: "step"
+    type: "int"
+    initial: 1
+  - name: "next"
+    type: "udint"
+  - name: "delay"
+    type: "TIME"
+    initial: "T#1m30s"
+  - name: "started"
+    type: "dt"
+    initial: "2024-03-01T06:30:00.250Z"
+  - name: "status"
+    type: "word"
+    initial: 0x8001
+  - name: "gain"
+    type: "real"
+    initial: 1.5
+blocks:
+  - name: "count"
+    type: "ADD"
+    inputs:
+      in1: "counter"
+      in2: "step"
+    outputs:
+      out: "next"
+"#;
+
+#[test]
+fn test_signals_keep_declared_types() -> Result<()> {
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(CONFIG)?)?;
+    let bus = engine.signal_bus().clone();
+    
+    assert_eq!(bus.get("level")?, SignalValue::UInt(65535));
+    assert_eq!(bus.get("total")?, SignalValue::LInt(5_000_000_000));
+    assert_eq!(bus.get("delay")?, SignalValue::Time(90_000));
+    assert_eq!(bus.get("started")?, SignalValue::DateAndTime(1_709_274_600_250));
+    assert_eq!(bus.get("status")?, SignalValue::Word(0x8001));
+    assert_eq!(bus.get("gain")?, SignalValue::Real(1.5));
+    
+    // Arithmetic widens to a type holding both operands
+    engine.execute_blocks()?;
+    assert_eq!(bus.get("next")?, SignalValue::UDInt(4_000_000_001));
+    bus.set("counter", SignalValue::UDInt(u32::MAX))?;
+    let err = engine.execute_blocks().unwrap_err();
+    assert!(err.to_string().contains("out of range for udint"), "{}", err);
+    
+    Ok(())
+}
+
+#[test]
+fn test_writes_convert_to_declared_type() -> Result<()> {
+    let bus = SignalBus::new();
+    let level = bus.declare("level", SignalType::UInt);
+    assert_eq!(bus.declared_type(level), Some(SignalType::UInt));
+    
+    // Writes convert to the declared type, and fail if the value does not fit
+    bus.write(level, SignalValue::Int(12))?;
+    assert_eq!(bus.read(level)?, SignalValue::UInt(12));
+    assert!(matches!(bus.write(level, SignalValue::Int(-1)), Err(PlcError::ConversionError(_))));
+    assert!(matches!(bus.write(level, SignalValue::Float(2.5)), Err(PlcError::ConversionError(_))));
+    assert!(matches!(bus.write(level, SignalValue::Bool(true)), Err(PlcError::TypeMismatch { .. })));
+    assert_eq!(bus.read(level)?, SignalValue::UInt(12));
+    
+    Ok(())
+}
+
+#[test]
+fn test_yaml_values_are_checked() {
+    let load = |signal_type: &str, initial: &str| {
+        let yaml = format!("signals:\n  - name: \"x\"\n    type: \"{}\"\n    initial: {}\nblocks: []\n", signal_type, initial);
+        PlcConfig::from_yaml(&yaml).and_then(ScanEngine::new).map(|engine| engine.signal_bus().get("x").unwrap())
+    };
+    
+    assert_eq!(load("int", "~").unwrap(), SignalValue::Int(0));
+    assert_eq!(load("ulint", "18446744073709551615").unwrap(), SignalValue::ULInt(u64::MAX));
+    assert_eq!(load("lreal", "3").unwrap(), SignalValue::Float(3.0));
+    assert_eq!(load("time", "250").unwrap(), SignalValue::Time(250));
+    assert_eq!(load("time", "\"1.5s\"").unwrap(), SignalValue::Time(1500));
+    assert_eq!(load("time", "\"T#1d2h\"").unwrap(), SignalValue::Time(93_600_000));
+    assert_eq!(load("date_and_time", "\"DT#1970-01-02-00:00:00\"").unwrap(), SignalValue::DateAndTime(86_400_000));
+    
+    // Out of range, fractional or mistyped values no longer truncate silently
+    assert!(load("int", "3000000000").is_err());
+    assert!(load("sint", "128").is_err());
+    assert!(load("usint", "-1").is_err());
+    assert!(load("int", "1.5").is_err());
+    assert!(load("bool", "1").is_err());
+    assert!(load("real", "1e300").is_err());
+    assert!(load("time", "\"1m30\"").is_err());
+    assert!(load("date_and_time", "\"2023-02-29T00:00:00\"").is_err());
+    assert!(load("int8", "1").is_err());
+}
+
+#[test]
+fn test_conversion_rules() -> Result<()> {
+    assert_eq!(SignalValue::Int(200).convert(SignalType::USInt)?, SignalValue::USInt(200));
+    assert!(matches!(SignalValue::Int(300).convert(SignalType::USInt), Err(PlcError::ConversionError(_))));
+    assert_eq!(SignalValue::LInt(7).convert(SignalType::Real)?, SignalValue::Real(7.0));
+    assert_eq!(SignalValue::Float(42.0).convert(SignalType::Int16)?, SignalValue::Int16(42));
+    assert!(SignalValue::Float(42.5).convert(SignalType::Int16).is_err());
+    assert!(SignalValue::Float(f64::NAN).convert(SignalType::LInt).is_err());
+    assert_eq!(SignalValue::Bool(true).convert(SignalType::Word)?, SignalValue::Word(1));
+    assert_eq!(SignalValue::UInt(0).convert(SignalType::Bool)?, SignalValue::Bool(false));
+    assert!(SignalValue::String("1".to_string()).convert(SignalType::Int).is_err());
+    
+    // Storing only mixes numbers, never bools or strings
+    assert!(SignalValue::Bool(true).coerce(SignalType::Int).is_err());
+    assert_eq!(SignalValue::Int(5000).coerce(SignalType::Time)?, SignalValue::Time(5000));
+    
+    assert_eq!(SignalType::parse("DINT"), Some(SignalType::Int));
+    assert_eq!(SignalValue::UDInt(1).type_name(), "udint");
+    assert_eq!(SignalValue::ULInt(u64::MAX).as_i64(), None);
+    assert_eq!(SignalValue::Int16(-3).as_int(), Some(-3));
+    
+    Ok(())
+}
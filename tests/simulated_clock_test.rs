@@ -0,0 +1,115 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine, SimulatedClock},
+    Result,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMER_CONFIG: &str = r#"
+signals:
+  - name: "input"
+    type: "bool"
+    initial: false
+
+blocks:
+  - name: "on_delay"
+    type: "TON"
+    inputs:
+      in: "input"
+    outputs:
+      q: "ton_q"
+      et: "ton_et"
+    params:
+      preset_ms: 1000
+
+  - name: "off_delay"
+    type: "TOF"
+    inputs:
+      in: "input"
+    outputs:
+      q: "tof_q"
+    params:
+      preset_ms: 500
+
+  - name: "pulse"
+    type: "TP"
+    inputs:
+      in: "input"
+    outputs:
+      q: "tp_q"
+    params:
+      preset_ms: 300
+
+scan_time_ms: 10
+"#;
+
+fn simulated_engine(scan_time_ms: u64) -> Result<ScanEngine> {
+    let mut config = PlcConfig::from_yaml(TIMER_CONFIG)?;
+    config.scan_time_ms = scan_time_ms;
+    ScanEngine::with_clock(config, Arc::new(SimulatedClock::new()))
+}
+
+#[test]
+fn test_timers_follow_simulated_clock() -> Result<()> {
+    let mut engine = simulated_engine(10)?;
+
+    engine.signal_bus().set("input", SignalValue::Bool(true))?;
+    engine.run_simulated(Duration::from_millis(200))?;
+
+    assert!(!engine.signal_bus().get_bool("ton_q")?);
+    assert!(engine.signal_bus().get_bool("tp_q")?);
+    assert!(engine.signal_bus().get_bool("tof_q")?);
+
+    engine.run_simulated(Duration::from_millis(900))?;
+
+    assert!(engine.signal_bus().get_bool("ton_q")?);
+    assert!(!engine.signal_bus().get_bool("tp_q")?);
+    assert_eq!(engine.signal_bus().get_int("ton_et")?, 1090);
+
+    engine.signal_bus().set("input", SignalValue::Bool(false))?;
+    engine.run_simulated(Duration::from_millis(400))?;
+    assert!(engine.signal_bus().get_bool("tof_q")?);
+
+    engine.run_simulated(Duration::from_millis(200))?;
+    assert!(!engine.signal_bus().get_bool("tof_q")?);
+    assert!(!engine.signal_bus().get_bool("ton_q")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_simulation_is_deterministic() -> Result<()> {
+    let mut runs = Vec::new();
+
+    for _ in 0..2 {
+        let mut engine = simulated_engine(100)?;
+
+        // About an hour of 100 ms scans, toggling the input after periods
+        // both shorter and longer than the timer presets
+        let mut input = false;
+        let mut trace = Vec::new();
+        for period_ms in [1200, 400, 200, 1100, 600].into_iter().cycle().take(5000) {
+            input = !input;
+            engine.signal_bus().set("input", SignalValue::Bool(input))?;
+            engine.run_simulated(Duration::from_millis(period_ms))?;
+            let bus = engine.signal_bus();
+            trace.push((bus.get_bool("ton_q")?, bus.get_bool("tof_q")?, bus.get_bool("tp_q")?));
+        }
+
+        let mut signals = engine.dump_signals();
+        signals.sort_by(|a, b| a.0.cmp(&b.0));
+        runs.push((trace, signals, engine.scan_count()));
+    }
+
+    assert_eq!(runs[0], runs[1]);
+    assert_eq!(runs[0].2, 35_000);
+
+    // Every timer both timed out and was cut short along the way
+    let trace = &runs[0].0;
+    assert!(trace.iter().any(|q| q.0) && trace.iter().any(|q| !q.0 && q.1));
+    assert!(trace.iter().any(|q| q.1) && trace.iter().any(|q| !q.1));
+    assert!(trace.iter().any(|q| q.2) && trace.iter().any(|q| !q.2));
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine, SimulatedClock, Snapshot},
+    PlcError, Result,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A block of every stateful kind, driven by `run` and `level`
+const SNAPSHOT_CONFIG: &str = r#"
+signals:
+  - name: "run"
+    type: "bool"
+  - name: "stop"
+    type: "bool"
+  - name: "level"
+    type: "float"
+  - name: "setpoint"
+    type: "float"
+    initial: 50.0
+  - name: "started"
+    type: "bool"
+  - name: "running"
+    type: "bool"
+  - name: "stopped_late"
+    type: "bool"
+  - name: "pulse"
+    type: "bool"
+  - name: "latched"
+    type: "bool"
+  - name: "run_edge"
+    type: "bool"
+  - name: "stop_edge"
+    type: "bool"
+  - name: "starts"
+    type: "int"
+  - name: "step"
+    type: "int"
+  - name: "valve"
+    type: "float"
+  - name: "previous_level"
+    type: "float"
+
+blocks:
+  - name: "start_delay"
+    type: "TON"
+    inputs:
+      in: "run"
+    outputs:
+      q: "started"
+    params:
+      preset_ms: 1000
+
+  - name: "run_on"
+    type: "TOF"
+    inputs:
+      in: "run"
+    outputs:
+      q: "stopped_late"
+    params:
+      preset_ms: 300
+
+  - name: "start_pulse"
+    type: "TP"
+    inputs:
+      in: "run"
+    outputs:
+      q: "pulse"
+    params:
+      preset_ms: 200
+
+  - name: "latch"
+    type: "SR_LATCH"
+    inputs:
+      set: "run"
+      reset: "stop"
+    outputs:
+      q: "latched"
+
+  - name: "run_rising"
+    type: "R_TRIG"
+    inputs:
+      clk: "run"
+    outputs:
+      q: "run_edge"
+
+  - name: "run_falling"
+    type: "F_TRIG"
+    inputs:
+      clk: "run"
+    outputs:
+      q: "stop_edge"
+
+  - name: "start_count"
+    type: "COUNTER"
+    inputs:
+      cu: "run"
+      cd: "stop"
+      r: "stop"
+    outputs:
+      cv: "starts"
+
+  - name: "steps"
+    type: "SEQUENCER"
+    inputs:
+      trigger: "run"
+      reset: "stop"
+    outputs:
+      index: "step"
+    params:
+      max: 3
+
+  - name: "level_control"
+    type: "PID"
+    inputs:
+      sp: "setpoint"
+      pv: "level"
+      auto: "latched"
+    outputs:
+      out: "valve"
+    params:
+      kp: 2.0
+      ki: 0.5
+
+  - name: "delay"
+    type: "UNIT_DELAY"
+    inputs:
+      in: "level"
+    outputs:
+      out: "previous_level"
+    params:
+      initial: 0.0
+
+scan_time_ms: 50
+"#;
+
+fn start() -> Result<(ScanEngine, SimulatedClock)> {
+    let clock = SimulatedClock::new();
+    let engine = ScanEngine::with_clock(PlcConfig::from_yaml(SNAPSHOT_CONFIG)?, Arc::new(clock.clone()))?;
+    Ok((engine, clock))
+}
+
+/// Switch `run` on and off a few times while the level rises
+fn exercise(engine: &mut ScanEngine, clock: &SimulatedClock, scans: u32) -> Result<()> {
+    let bus = engine.signal_bus().clone();
+    for scan in 0..scans {
+        bus.set("run", SignalValue::Bool(scan % 7 < 5))?;
+        bus.set("level", SignalValue::Float(scan as f64 * 1.5))?;
+        engine.execute_blocks()?;
+        clock.advance(Duration::from_millis(50));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_restored_engine_continues_in_step() -> Result<()> {
+    let (mut primary, primary_clock) = start()?;
+    exercise(&mut primary, &primary_clock, 17)?;
+    
+    let snapshot = primary.snapshot();
+    assert_eq!(snapshot.blocks.len(), 10, "{:#?}", snapshot.blocks);
+    
+    // A standby with a clock at a different time
+    let (mut standby, standby_clock) = start()?;
+    standby_clock.advance(Duration::from_secs(3600));
+    standby.restore(&snapshot)?;
+    assert_eq!(standby.snapshot(), snapshot);
+    
+    for scans in [1, 5, 20] {
+        exercise(&mut primary, &primary_clock, scans)?;
+        exercise(&mut standby, &standby_clock, scans)?;
+        assert_eq!(standby.dump_signals(), primary.dump_signals());
+    }
+    
+    Ok(())
+}
+
+#[test]
+fn test_running_timer_carries_on_after_restore() -> Result<()> {
+    let (mut engine, clock) = start()?;
+    let bus = engine.signal_bus().clone();
+    bus.set("run", SignalValue::Bool(true))?;
+    engine.execute_blocks()?;
+    clock.advance(Duration::from_millis(600));
+    engine.execute_blocks()?;
+    
+    let json = serde_json::to_string(&engine.snapshot()).unwrap();
+    let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+    
+    // The new engine's clock starts at zero, before the timer was started
+    let (mut restored, clock) = start()?;
+    restored.restore(&snapshot)?;
+    let bus = restored.signal_bus().clone();
+    
+    clock.advance(Duration::from_millis(399));
+    restored.execute_blocks()?;
+    assert!(!bus.get_bool("started")?);
+    clock.advance(Duration::from_millis(1));
+    restored.execute_blocks()?;
+    assert!(bus.get_bool("started")?);
+    
+    Ok(())
+}
+
+#[test]
+fn test_restore_rejects_mismatched_snapshot() -> Result<()> {
+    let (mut engine, _clock) = start()?;
+    engine.execute_blocks()?;
+    let before = engine.snapshot();
+    
+    let mut snapshot = before.clone();
+    snapshot.signals.insert("level".to_string(), SignalValue::Float(12.0));
+    snapshot.signals.insert("unknown".to_string(), SignalValue::Bool(true));
+    assert!(matches!(engine.restore(&snapshot), Err(PlcError::SignalNotFound(_))));
+    
+    let mut snapshot = before.clone();
+    snapshot.signals.insert("level".to_string(), SignalValue::Bool(true));
+    assert!(matches!(engine.restore(&snapshot), Err(PlcError::TypeMismatch { .. })));
+    
+    let mut snapshot = before.clone();
+    snapshot.blocks.insert("missing".to_string(), serde_json::json!({}));
+    assert!(matches!(engine.restore(&snapshot), Err(PlcError::StateError(_))));
+    
+    assert_eq!(engine.snapshot(), before, "nothing changed");
+    
+    Ok(())
+}
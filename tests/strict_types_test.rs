@@ -0,0 +1,166 @@
+use soft_plc::{
+    blocks::conversion::conversion_types,
+    signal::{SignalBus, SignalType, SignalValue},
+    engine::{PlcConfig, ScanEngine},
+    PlcError, Result,
+};
+
+const CONFIG: &str = r#"
+strict_types: true
+signals:
+  - name: "count"
+    type: "int"
+    initial: 3
+  - name: "count_real"
+    type: "real"
+  - name: "gain"
+    type: "real"
+    initial: 2.5
+  - name: "scaled"
+    type: "real"
+  - name: "rounded"
+    type: "sint"
+  - name: "limit"
+    type: "real"
+  - name: "over"
+    type: "bool"
+blocks:
+  - name: "to_real"
+    type: "INT_TO_REAL"
+    inputs:
+      in: "count"
+    outputs:
+      out: "count_real"
+  - name: "scale"
+    type: "MUL"
+    inputs:
+      in1: "count_real"
+      in2: "gain"
+    outputs:
+      out: "scaled"
+  - name: "to_sint"
+    type: "REAL_TO_SINT"
+    inputs:
+      in: "scaled"
+    outputs:
+      out: "rounded"
+  - name: "limit"
+    type: "CONST"
+    inputs: {}
+    params:
+      value: 100
+    outputs:
+      out: "limit"
+  - name: "too_high"
+    type: "GT"
+    inputs:
+      in1: "scaled"
+      in2: "limit"
+    outputs:
+      out: "over"
+scan_time_ms: 100
+"#;
+
+#[test]
+fn test_conversions_are_explicit() -> Result<()> {
+    let config = PlcConfig::from_yaml(CONFIG)?;
+    config.validate()?;
+    let mut engine = ScanEngine::new(config)?;
+    let bus = engine.signal_bus().clone();
+    
+    engine.execute_blocks()?;
+    assert_eq!(bus.get("count_real")?, SignalValue::Real(3.0));
+    assert_eq!(bus.get("scaled")?, SignalValue::Real(7.5));
+    assert_eq!(bus.get("limit")?, SignalValue::Real(100.0));
+    // Reals round to the nearest integer, halves away from zero
+    assert_eq!(bus.get("rounded")?, SignalValue::SInt(8));
+    assert!(!bus.get_bool("over")?);
+    
+    // Writes of another type are rejected rather than converted
+    assert!(matches!(bus.set("count", SignalValue::Real(60.0)), Err(PlcError::TypeMismatch { .. })));
+    bus.set("count", SignalValue::Int(60))?;
+    let err = engine.execute_blocks().unwrap_err();
+    assert!(err.to_string().contains("REAL_TO_SINT 'to_sint': 150 is out of range for sint"), "{}", err);
+    assert!(bus.get_bool("over")?);
+    
+    Ok(())
+}
+
+#[test]
+fn test_strict_ports_are_checked_at_load() -> Result<()> {
+    let strict = CONFIG.replace("in2: \"gain\"", "in2: \"count\"")
+        .replace("in: \"count\"\n    outputs:\n      out: \"count_real\"", "in: \"gain\"\n    outputs:\n      out: \"count_real\"");
+    let messages = match PlcConfig::from_yaml(&strict)?.validate() {
+        Err(PlcError::ValidationError(diagnostics)) => diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+        other => panic!("expected validation failure, got {:?}", other),
+    };
+    assert!(messages.iter().any(|m| m.contains("expected int signal, 'gain' is real")), "{:?}", messages);
+    assert!(messages.iter().any(|m| m.contains("'count' is int but 'count_real' is real")), "{:?}", messages);
+    
+    // The same configuration is fine when values convert implicitly
+    let lenient = strict.replace("strict_types: true", "strict_types: false")
+        .replace("INT_TO_REAL", "UNIT_DELAY");
+    PlcConfig::from_yaml(&lenient)?.validate()?;
+    
+    // COUNTER reads its preset as int, so strictly a uint preset needs a conversion
+    let counter = r#"
+strict_types: true
+signals:
+  - name: "pulse"
+    type: "bool"
+  - name: "preset"
+    type: "uint"
+  - name: "count"
+    type: "int"
+  - name: "done"
+    type: "bool"
+blocks:
+  - name: "counter"
+    type: "COUNTER"
+    inputs:
+      cu: "pulse"
+      cd: "pulse"
+      r: "pulse"
+      pv: "preset"
+    outputs:
+      cv: "count"
+      q: "done"
+scan_time_ms: 100
+"#;
+    assert!(PlcConfig::from_yaml(counter)?.validate().is_err());
+    PlcConfig::from_yaml(&counter.replace("strict_types: true", "strict_types: false"))?.validate()?;
+    
+    Ok(())
+}
+
+#[test]
+fn test_strict_bus_and_comparisons() -> Result<()> {
+    let bus = SignalBus::new();
+    let level = bus.declare("level", SignalType::UInt);
+    let flag = bus.declare("flag", SignalType::Int);
+    bus.write(flag, SignalValue::Int(1))?;
+    assert!(bus.read_bool(flag)?);
+    
+    bus.set_strict(true);
+    assert!(matches!(bus.write(level, SignalValue::Int(12)), Err(PlcError::TypeMismatch { .. })));
+    bus.write(level, SignalValue::UInt(12))?;
+    assert!(matches!(bus.read_bool(flag), Err(PlcError::TypeMismatch { .. })));
+    assert!(matches!(bus.read_float(flag), Err(PlcError::TypeMismatch { .. })));
+    
+    // EQ of different types is an error instead of quietly false
+    let eq = "signals:\n  - name: \"a\"\n    type: \"int\"\n  - name: \"b\"\n    type: \"float\"\n  - name: \"same\"\n    type: \"bool\"\n    initial: true\nblocks:\n  - name: \"eq\"\n    type: \"EQ\"\n    inputs:\n      in1: \"a\"\n      in2: \"b\"\n    outputs:\n      out: \"same\"\nscan_time_ms: 100\n";
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(eq)?)?;
+    engine.execute_blocks()?;
+    assert!(!engine.signal_bus().get_bool("same")?);
+    
+    let mut engine = ScanEngine::new(PlcConfig::from_yaml(&format!("strict_types: true\n{}", eq))?)?;
+    assert!(matches!(engine.execute_blocks(), Err(PlcError::TypeMismatch { .. })));
+    
+    assert_eq!(conversion_types("INT_TO_REAL"), Some((SignalType::Int, SignalType::Real)));
+    assert_eq!(conversion_types("UINT_TO_LREAL"), Some((SignalType::UInt, SignalType::Float)));
+    assert_eq!(conversion_types("BOOL_TO_REAL"), None);
+    assert_eq!(conversion_types("STRING_TO_INT"), None);
+    assert_eq!(conversion_types("INT_TO_INT"), None);
+    
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use soft_plc::{
+    signal::SignalValue,
+    engine::{PlcConfig, ScanEngine},
+    PlcError, Result,
+};
+
+const CONFIG: &str = r#"
+types:
+  - name: "Motor"
+    fields:
+      - name: "run"
+        type: "bool"
+      - name: "speed"
+        type: "real"
+        initial: 50.0
+  - name: "Station"
+    fields:
+      - name: "motors"
+        type: "Motor"
+        array: 2
+      - name: "alarms"
+        type: "bool"
+        array: [1, 3]
+signals:
+  - name: "station"
+    type: "Station"
+    initial:
+      motors:
+        - { run: true }
+        - { speed: 75.0 }
+  - name: "levels"
+    type: "uint"
+    array: 4
+    initial: [10, 20, 30, 40]
+  - name: "enable"
+    type: "bool"
+    initial: true
+blocks:
+  - name: "start_second"
+    type: "AND"
+    inputs:
+      in1: "station.motors[0].run"
+      in2: "enable"
+    outputs:
+      out: "station.motors[1].run"
+  - name: "sum"
+    type: "ADD"
+    inputs:
+      in1: "levels[0]"
+      in2: "levels[3]"
+    outputs:
+      out: "levels[1]"
+"#;
+
+#[test]
+fn test_elements_are_signals() -> Result<()> {
+    let config = PlcConfig::from_yaml(CONFIG)?;
+    let names: Vec<String> = config.elementary_signals()?.into_iter().map(|signal| signal.name).collect();
+    assert_eq!(names, vec![
+        "station.motors[0].run", "station.motors[0].speed",
+        "station.motors[1].run", "station.motors[1].speed",
+        "station.alarms[1]", "station.alarms[2]", "station.alarms[3]",
+        "levels[0]", "levels[1]", "levels[2]", "levels[3]",
+        "enable",
+    ]);
+    
+    let mut engine = ScanEngine::new(config)?;
+    let bus = engine.signal_bus().clone();
+    
+    // Field defaults apply unless the signal's initial value overrides them
+    assert_eq!(bus.get("station.motors[0].speed")?, SignalValue::Real(50.0));
+    assert_eq!(bus.get("station.motors[1].speed")?, SignalValue::Real(75.0));
+    assert_eq!(bus.get("levels[2]")?, SignalValue::UInt(30));
+    assert!(!bus.get_bool("station.alarms[3]")?);
+    
+    // Block ports address elements directly
+    engine.execute_blocks()?;
+    assert!(bus.get_bool("station.motors[1].run")?);
+    assert_eq!(bus.get("levels[1]")?, SignalValue::UInt(50));
+    
+    let elements: Vec<String> = bus.element_samples("station.motors[1]").into_iter().map(|(name, _)| name).collect();
+    assert_eq!(elements, vec!["station.motors[1].run", "station.motors[1].speed"]);
+    
+    Ok(())
+}
+
+#[test]
+fn test_invalid_structures_are_reported() {
+    let messages = |yaml: &str| match PlcConfig::from_yaml(&format!("scan_time_ms: 100\n{}", yaml)).unwrap().validate() {
+        Err(PlcError::ValidationError(diagnostics)) => diagnostics.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+        other => panic!("expected validation failure, got {:?}", other),
+    };
+    let types = "types:\n  - name: \"Pump\"\n    fields:\n      - name: \"run\"\n        type: \"bool\"\n";
+    
+    let found = messages(&format!("{}signals:\n  - name: \"pumps\"\n    type: \"Pump\"\n    array: 2\n    initial: {{ speed: 1 }}\n", types));
+    assert!(found[0].contains("Pump has no field 'speed'"), "{:?}", found);
+    
+    let found = messages("signals:\n  - name: \"levels\"\n    type: \"int\"\n    array: 3\n    initial: [1, 2]\n");
+    assert!(found[0].contains("array 'levels' has 3 elements but initial has 2"), "{:?}", found);
+    
+    let found = messages("signals:\n  - name: \"levels\"\n    type: \"int\"\n    array: [5, 1]\n");
+    assert!(found[0].contains("must have between 1 and"), "{:?}", found);
+    
+    let found = messages("types:\n  - name: \"Loop\"\n    fields:\n      - name: \"next\"\n        type: \"Loop\"\nsignals:\n  - name: \"x\"\n    type: \"Loop\"\n");
+    assert!(found.iter().any(|m| m.contains("type 'Loop' contains itself")), "{:?}", found);
+    
+    let found = messages(&format!("{}  - name: \"Pump\"\n    fields:\n      - name: \"run\"\n        type: \"pump\"\n", types));
+    assert!(found.iter().any(|m| m.contains("type 'Pump' is declared more than once")), "{:?}", found);
+    assert!(found.iter().any(|m| m.contains("field 'run' has unknown type 'pump'")), "{:?}", found);
+    
+    let found = messages(&format!("{}signals:\n  - name: \"pumps\"\n    type: \"Pump\"\n    array: 2\n  - name: \"pumps[1].run\"\n    type: \"bool\"\n", types));
+    assert!(found[0].contains("signal 'pumps[1].run' is declared more than once"), "{:?}", found);
+}
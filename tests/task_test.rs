@@ -0,0 +1,150 @@
+use soft_plc::{
+    engine::{PlcConfig, ScanEngine, SimulatedClock},
+    Result,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Each tick block adds one to its own output every time its task runs
+const TASKS_CONFIG: &str = r#"
+signals:
+  - name: "one"
+    type: "int"
+    initial: 1
+  - name: "fast_count"
+    type: "int"
+  - name: "slow_count"
+    type: "int"
+  - name: "main_count"
+    type: "int"
+
+blocks:
+  - name: "main_tick"
+    type: "ADD"
+    inputs:
+      in1: "main_count"
+      in2: "one"
+    outputs:
+      out: "main_count"
+
+  - name: "slow_tick"
+    type: "ADD"
+    inputs:
+      in1: "slow_count"
+      in2: "one"
+    outputs:
+      out: "slow_count"
+
+  - name: "fast_tick"
+    type: "ADD"
+    inputs:
+      in1: "fast_count"
+      in2: "one"
+    outputs:
+      out: "fast_count"
+
+programs:
+  - name: "interlocks"
+    blocks: ["fast_tick"]
+  - name: "housekeeping"
+    blocks: ["slow_tick"]
+
+tasks:
+  - name: "slow"
+    interval_ms: 100
+    priority: 5
+    programs: ["housekeeping"]
+  - name: "fast"
+    interval_ms: 10
+    priority: 0
+    programs: ["interlocks"]
+
+scan_time_ms: 50
+"#;
+
+fn engine() -> Result<(ScanEngine, SimulatedClock)> {
+    let clock = SimulatedClock::new();
+    let engine = ScanEngine::with_clock(PlcConfig::from_yaml(TASKS_CONFIG)?, Arc::new(clock.clone()))?;
+    Ok((engine, clock))
+}
+
+#[test]
+fn test_tasks_run_at_their_own_rate() -> Result<()> {
+    let (mut engine, _clock) = engine()?;
+    engine.run_simulated(Duration::from_secs(1))?;
+    
+    let bus = engine.signal_bus();
+    assert_eq!(bus.get_int("fast_count")?, 100);
+    assert_eq!(bus.get_int("slow_count")?, 10);
+    assert_eq!(bus.get_int("main_count")?, 20);
+    
+    let stats = engine.task_stats();
+    let executions: Vec<(&str, u64, u64)> = stats.iter()
+        .map(|task| (task.name.as_str(), task.executions, task.overruns))
+        .collect();
+    assert_eq!(executions, vec![("fast", 100, 0), ("slow", 10, 0), ("main", 20, 0)]);
+    
+    Ok(())
+}
+
+#[test]
+fn test_higher_priority_tasks_run_first() -> Result<()> {
+    let (engine, _clock) = engine()?;
+    
+    assert_eq!(engine.execution_order(), vec!["fast_tick", "slow_tick", "main_tick"]);
+    assert_eq!(engine.programs(), vec![("fast", "interlocks"), ("slow", "housekeeping"), ("main", "main")]);
+    
+    Ok(())
+}
+
+#[test]
+fn test_late_release_counts_as_overrun() -> Result<()> {
+    let (mut engine, clock) = engine()?;
+    engine.scan()?;
+    
+    // The fast task was due at 10 and 20 ms
+    clock.advance(Duration::from_millis(25));
+    engine.scan()?;
+    
+    let fast = &engine.task_stats()[0];
+    assert_eq!((fast.executions, fast.overruns), (2, 1));
+    assert_eq!(engine.signal_bus().get_int("fast_count")?, 2);
+    
+    // The missed release is skipped: the next one is at 30 ms
+    clock.advance(Duration::from_millis(4));
+    engine.scan()?;
+    assert_eq!(engine.signal_bus().get_int("fast_count")?, 2);
+    clock.advance(Duration::from_millis(1));
+    engine.scan()?;
+    assert_eq!(engine.signal_bus().get_int("fast_count")?, 3);
+    
+    let slow = &engine.task_stats()[1];
+    assert_eq!((slow.executions, slow.overruns), (1, 0));
+    
+    Ok(())
+}
+
+#[test]
+fn test_task_configuration_errors() -> Result<()> {
+    let cases = [
+        ("blocks: [\"fast_tick\"]", "blocks: [\"fast_tick\", \"nothing\"]",
+            "program 'interlocks': block 'nothing' does not exist"),
+        ("blocks: [\"slow_tick\"]", "blocks: [\"slow_tick\", \"fast_tick\"]",
+            "block 'fast_tick' is in both program 'interlocks' and 'housekeeping'"),
+        ("programs: [\"housekeeping\"]", "programs: [\"housekeeping\", \"interlocks\"]",
+            "program 'interlocks' is assigned to more than one task"),
+        ("programs: [\"housekeeping\"]", "programs: []",
+            "program 'housekeeping' is not assigned to a task"),
+        ("interval_ms: 10\n", "interval_ms: 0\n",
+            "task 'fast': interval_ms must be greater than zero"),
+    ];
+    
+    for (from, to, expected) in cases {
+        let config = PlcConfig::from_yaml(&TASKS_CONFIG.replace(from, to))?;
+        let messages: Vec<String> = config.diagnostics().into_iter().map(|d| d.message).collect();
+        assert!(messages.iter().any(|m| m.contains(expected)), "{}: {:#?}", expected, messages);
+        assert!(ScanEngine::new(config).is_err());
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use soft_plc::{engine::PlcConfig, PlcError, Result};
+
+#[test]
+fn test_shipped_configs_are_valid() -> Result<()> {
+    for yaml in [
+        include_str!("../config/advanced_example.yaml"),
+        include_str!("../config/example_logic.yaml"),
+        include_str!("../config/pump_alternation.yaml"),
+        include_str!("../config/modbus_server.yaml"),
+        include_str!("../config/modbus_client.yaml"),
+        include_str!("../config/test_basic.yaml"),
+    ] {
+        PlcConfig::from_yaml(yaml)?.validate()?;
+    }
+    Ok(())
+}
+
+const BAD_CONFIG: &str = r#"signals:
+  - name: "level"
+    type: "float"
+  - name: "running"
+    type: "bool"
+  - name: "running"
+    type: "bool"
+
+blocks:
+  - name: "high_level"
+    type: "GT"
+    inputs:
+      in1: "running"
+      in2: "level"
+    outputs:
+      out: "alarm"
+
+  - name: "high_level"
+    type: "NOT"
+    inputs:
+      in: "running"
+    outputs:
+      out: "running"
+
+  - name: "starter"
+    type: "SR_LATCH"
+    inputs:
+      set: "level"
+      reset: "running"
+    outputs:
+      q: "running"
+
+scan_time_ms: 100
+"#;
+
+#[test]
+fn test_every_problem_is_reported() -> Result<()> {
+    let config = PlcConfig::from_yaml(BAD_CONFIG)?;
+    
+    let diagnostics = match config.validate() {
+        Err(PlcError::ValidationError(diagnostics)) => diagnostics,
+        other => panic!("expected validation failure, got {:?}", other),
+    };
+    
+    let find = |needle: &str| {
+        diagnostics.iter()
+            .find(|d| d.to_string().contains(needle))
+            .unwrap_or_else(|| panic!("no diagnostic containing {:?} in {:#?}", needle, diagnostics))
+    };
+    
+    assert_eq!(find("declared more than once").line, Some(6));
+    
+    let type_mismatch = find("expected numeric signal, 'running' is bool");
+    assert_eq!(type_mismatch.block.as_deref(), Some("high_level"));
+    assert_eq!(type_mismatch.port.as_deref(), Some("in1"));
+    assert_eq!(type_mismatch.line, Some(13));
+    
+    let undeclared = find("signal 'alarm' is not declared");
+    assert_eq!(undeclared.line, Some(16));
+    
+    assert_eq!(find("block name is used more than once").line, Some(18));
+    
+    let second_writer = find("already written by block 'high_level'");
+    assert_eq!(second_writer.block.as_deref(), Some("starter"));
+    assert_eq!(second_writer.line, Some(31));
+    
+    assert_eq!(find("expected bool signal, 'level' is float").line, Some(28));
+    
+    Ok(())
+}
+
+#[test]
+fn test_one_block_writing_a_signal_twice_is_reported() -> Result<()> {
+    let config = PlcConfig::from_yaml(r#"signals:
+  - name: "start"
+    type: "bool"
+  - name: "x"
+    type: "bool"
+
+blocks:
+  - name: "start_edge"
+    type: "R_TRIG"
+    inputs:
+      clk: "start"
+    outputs:
+      q: "x"
+      eno: "x"
+
+scan_time_ms: 100
+"#)?;
+    
+    let diagnostics = match config.validate() {
+        Err(PlcError::ValidationError(diagnostics)) => diagnostics,
+        other => panic!("expected validation failure, got {:?}", other),
+    };
+    
+    assert_eq!(diagnostics.len(), 1, "{:#?}", diagnostics);
+    assert!(diagnostics[0].to_string().contains("signal 'x' is already written by block 'start_edge' port 'eno'"), "{:#?}", diagnostics);
+    assert_eq!(diagnostics[0].port.as_deref(), Some("q"));
+    assert_eq!(diagnostics[0].line, Some(13));
+    
+    Ok(())
+}